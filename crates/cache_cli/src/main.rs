@@ -0,0 +1,163 @@
+//! Inspect and maintain entries of `fs_cache_fetcher`.
+//!
+//! ```text
+//! divicards-cache list
+//! divicards-cache refresh <name>
+//! divicards-cache prune <hours>
+//! ```
+//!
+//! Keyed entries, like card prices per league, are listed as `{name}/{key}`, e.g.
+//! `prices/Standard`, for every key found on disk.
+
+use card_element::{
+    base_items_fetcher::BaseItemsFetcher, fetcher::Fetcher as CardElementFetcher,
+    uniques_fetcher::UniquesFetcher,
+};
+use divcord::spreadsheet::fs_cache_fetcher::SpreadsheetFetcher;
+use fs_cache_fetcher::registry::{EntryInfo, Registry};
+use fs_cache_fetcher::{LoadPolicy, Stale};
+use lib::prices::PricesFetcher;
+use poe_data::fetchers::{
+    ActsFetcher, CardsFetcher, MapBossesFetcher, MapsFetcher, PoeDataFetcher,
+};
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::time::Duration;
+
+const USAGE: &str = "Usage:
+    divicards-cache list             List cache entries of all known fetchers
+    divicards-cache refresh <name>   Fetch one entry again, e.g. poeData or prices/Standard
+    divicards-cache prune <hours>    Remove entries older than <hours>";
+
+/// Data dir of the desktop app, where it keeps `{league}-prices.json`.
+/// Same as tauri's `app_local_data_dir` for the `divicards` identifier.
+fn app_dir() -> PathBuf {
+    dirs::data_local_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join("divicards")
+}
+
+fn registry() -> Registry {
+    Registry::new()
+        .register("poeData", PoeDataFetcher::default())
+        .register("acts", ActsFetcher::default())
+        .register("cards", CardsFetcher::default())
+        .register("maps", MapsFetcher::default())
+        .register("mapBosses", MapBossesFetcher::default())
+        .register("spreadsheet", SpreadsheetFetcher::default())
+        .register("cardElementData", CardElementFetcher::default())
+        .register("baseItems", BaseItemsFetcher::default())
+        .register("uniques", UniquesFetcher::default())
+        .register_keyed("prices", PricesFetcher::new(app_dir()))
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    dotenv::dotenv().ok();
+    let registry = registry();
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+    match args.as_slice() {
+        ["list"] | [] => {
+            print_entries(&registry.list());
+            ExitCode::SUCCESS
+        }
+        ["refresh", name] => match registry.refresh(name).await {
+            Ok(()) => {
+                println!("Refreshed {name}");
+                ExitCode::SUCCESS
+            }
+            Err(err) => {
+                eprintln!("Could not refresh {name}: {err}");
+                eprintln!(
+                    "Known entries: {}",
+                    registry.names().collect::<Vec<_>>().join(", ")
+                );
+                ExitCode::FAILURE
+            }
+        },
+        ["prune", hours] => {
+            let Ok(hours) = hours.parse::<u64>() else {
+                eprintln!("{USAGE}");
+                return ExitCode::FAILURE;
+            };
+            match registry.prune(Duration::from_secs(hours * 3600)) {
+                Ok(removed) if removed.is_empty() => println!("Nothing to prune"),
+                Ok(removed) => println!("Removed {}", removed.join(", ")),
+                Err(err) => {
+                    eprintln!("Could not prune: {err}");
+                    return ExitCode::FAILURE;
+                }
+            }
+            ExitCode::SUCCESS
+        }
+        _ => {
+            eprintln!("{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn print_entries(entries: &[EntryInfo]) {
+    println!(
+        "{:<24} {:<10} {:>10} {:<14} {:<20} {:<14} PATH",
+        "NAME", "STATUS", "SIZE", "AGE", "STALE", "POLICY"
+    );
+    for entry in entries {
+        let status = match (entry.exists, entry.up_to_date) {
+            (false, _) => "missing",
+            (true, true) => "fresh",
+            (true, false) => "stale",
+        };
+        let policy = format_policy(entry.policy);
+        println!(
+            "{:<24} {:<10} {:>10} {:<14} {:<20} {:<14} {}",
+            entry.name,
+            status,
+            entry.size.map(format_size).unwrap_or_default(),
+            entry.age.map(format_duration).unwrap_or_default(),
+            format_stale(&entry.stale),
+            policy,
+            entry.path.display()
+        );
+    }
+}
+
+fn format_policy(policy: LoadPolicy) -> String {
+    match policy {
+        LoadPolicy::Online => "online".to_owned(),
+        LoadPolicy::StaleOnError { max_age: None } => "stale on error".to_owned(),
+        LoadPolicy::StaleOnError {
+            max_age: Some(max_age),
+        } => format!("stale < {}", format_duration(max_age)),
+        LoadPolicy::Offline => "offline".to_owned(),
+        LoadPolicy::StaleWhileRevalidate => "swr".to_owned(),
+    }
+}
+
+fn format_stale(stale: &Stale) -> String {
+    match stale {
+        Stale::Never => "never".to_owned(),
+        Stale::After(duration) => format!("after {}", format_duration(*duration)),
+        Stale::ReloadEveryTime => "reload every time".to_owned(),
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        s if s < 60 => format!("{s}s"),
+        s if s < 3600 => format!("{}m", s / 60),
+        s if s < 86_400 => format!("{}h {}m", s / 3600, s % 3600 / 60),
+        s => format!("{}d {}h", s / 86_400, s % 86_400 / 3600),
+    }
+}
+
+fn format_size(bytes: u64) -> String {
+    match bytes {
+        b if b < 1024 => format!("{b} B"),
+        b if b < 1024 * 1024 => format!("{:.1} KB", b as f64 / 1024.0),
+        b => format!("{:.1} MB", b as f64 / (1024.0 * 1024.0)),
+    }
+}
@@ -25,8 +25,9 @@ impl Default for BaseItemsFetcher {
     fn default() -> Self {
         Self(Config {
             save: true,
-            filename: "base_items.json",
+            filename: "base_items.json".to_owned(),
             stale: Stale::After(std::time::Duration::from_secs(3600 * 24)), // Update once a day
+            ..Default::default()
         })
     }
 }
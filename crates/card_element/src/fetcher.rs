@@ -6,7 +6,7 @@ use crate::{
     uniques_fetcher::UniquesFetcher,
     DivinationCardElementData, Error,
 };
-use fs_cache_fetcher::{Config, DataFetcher, Stale};
+use fs_cache_fetcher::{Codec, Config, DataFetcher, Stale};
 use poe::TradeLeague;
 use poe_data::fetchers::CardsFetcher;
 
@@ -16,8 +16,10 @@ impl Default for Fetcher {
     fn default() -> Self {
         Self(Config {
             save: true,
-            filename: "cardElementData.json",
+            filename: "cardElementData.json".to_owned(),
             stale: Stale::ReloadEveryTime,
+            codec: Codec::Cbor,
+            ..Default::default()
         })
     }
 }
@@ -28,8 +28,8 @@ pub struct DivinationCardElementData {
 }
 
 impl DivinationCardElementData {
-    pub fn filename() -> &'static str {
-        Fetcher::default().config().filename
+    pub fn filename() -> String {
+        Fetcher::default().filename().to_owned()
     }
 
     pub async fn load() -> Result<Vec<DivinationCardElementData>, Error> {
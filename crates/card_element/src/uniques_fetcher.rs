@@ -17,8 +17,9 @@ impl Default for UniquesFetcher {
     fn default() -> Self {
         Self(Config {
             save: true,
-            filename: "uniques.json",
+            filename: "uniques.json".to_owned(),
             stale: Stale::After(std::time::Duration::from_secs(3600 * 24)), // Update once a day
+            ..Default::default()
         })
     }
 }
@@ -13,16 +13,22 @@ pub fn criterion_benchmark(c: &mut Criterion) {
     let records = divcord::records(&spreadsheet, &poe_data).unwrap();
 
     let all_source_types = Source::types();
+    let graph = divcord::graph::SourceGraph::new(&poe_data);
 
     c.bench_function("cards", |b| {
         b.iter(|| {
             divcord::cards::cards_by_source_types(
                 black_box(&all_source_types),
                 black_box(&records),
-                black_box(&poe_data),
+                black_box(&graph),
             )
         })
     });
+
+    let index = divcord::DivcordIndex::new(&records, &poe_data);
+    c.bench_function("index", |b| {
+        b.iter(|| index.cards_by_source_types(black_box(&all_source_types)))
+    });
 }
 
 fn configured_criterion() -> Criterion {
@@ -0,0 +1,276 @@
+//! Structured data from the free-text "tag hypothesis" and "notes" columns.
+//!
+//! Both columns keep their raw text on [`Record`](crate::Record), [`Annotations`] holds what
+//! could be recognized in it: monster and area tags (`snow_area`), referenced drop sources, links,
+//! league versions (`3.21`) and mentions of datamining.
+
+use crate::{
+    dropsource::{
+        predefined::{definitions::definitions, PredefinedSource},
+        Source,
+    },
+    PoeData,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Shorter names are mostly words, not sources.
+const MIN_SOURCE_NAME_LEN: usize = 4;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Annotations {
+    /// Snake-case tags, like `snow_area` or `hunter_conqueror_boss`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Drop sources mentioned by name.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sources: Vec<Source>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub urls: Vec<String>,
+    /// League versions, like `3.21`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub leagues: Vec<String>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub datamined: bool,
+}
+
+impl Annotations {
+    /// Annotations of one text. To annotate many, build [`SourceNames`] once.
+    pub fn parse(text: &str, poe_data: &PoeData) -> Self {
+        SourceNames::new(poe_data).annotate(text)
+    }
+
+    /// Everything but sources.
+    fn parse_words(text: &str) -> Self {
+        let mut annotations = Annotations {
+            datamined: text.to_lowercase().contains("datamin"),
+            ..Default::default()
+        };
+
+        for word in text.split_whitespace() {
+            if word.starts_with("http://") || word.starts_with("https://") {
+                let url = word.trim_end_matches(|c: char| ".,;:)]\"'".contains(c));
+                push_unique(&mut annotations.urls, url.to_owned());
+                continue;
+            }
+            for token in word.split(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '.'))
+            {
+                let token = token.trim_matches('.');
+                if is_tag(token) {
+                    push_unique(&mut annotations.tags, token.to_owned());
+                } else if is_league(token) {
+                    push_unique(&mut annotations.leagues, token.to_owned());
+                }
+            }
+        }
+        annotations
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self == &Annotations::default()
+    }
+}
+
+fn push_unique(values: &mut Vec<String>, value: String) {
+    if !values.contains(&value) {
+        values.push(value);
+    }
+}
+
+/// `snow_area`, but not `QBotU_DyNess` or `_area`.
+fn is_tag(token: &str) -> bool {
+    token.contains('_')
+        && !token.starts_with('_')
+        && !token.ends_with('_')
+        && !token.contains("__")
+        && token
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+/// `3.21`. Other versions are usually numbers, not leagues.
+fn is_league(token: &str) -> bool {
+    token.strip_prefix("3.").is_some_and(|minor| {
+        (1..=2).contains(&minor.len()) && minor.chars().all(|c| c.is_ascii_digit())
+    })
+}
+
+/// Names of maps (also without the " Map" suffix), map bosses, act areas, act bosses and
+/// predefined sources, by their first word. Build once to annotate many texts.
+#[derive(Debug, Clone, Default)]
+pub struct SourceNames {
+    by_first_word: HashMap<String, Vec<(String, Source)>>,
+}
+
+impl SourceNames {
+    pub fn new(poe_data: &PoeData) -> Self {
+        let mut names = SourceNames::default();
+        for map in &poe_data.maps {
+            let source = Source::from(map.clone());
+            if let Some(short) = map.name.strip_suffix(" Map") {
+                names.insert(short, source.clone());
+            }
+            names.insert(&map.name, source);
+        }
+        for boss in &poe_data.mapbosses {
+            names.insert(&boss.name, Source::from(boss.clone()));
+        }
+        // Areas of different acts share names, like `The Coast`: a mention is each of them.
+        for act in poe_data.acts.iter().filter(|act| !act.is_town) {
+            names.insert(&act.name, Source::from(act.clone()));
+            for boss in &act.bossfights {
+                names.insert(&boss.name, Source::from(boss.clone()));
+            }
+        }
+        for definition in definitions().iter() {
+            let source = Source::Predefined(PredefinedSource::from(definition));
+            for name in std::iter::once(&definition.id).chain(&definition.aliases) {
+                names.insert(name, source.clone());
+            }
+        }
+        names
+    }
+
+    fn insert(&mut self, name: &str, source: Source) {
+        let name = name.to_lowercase();
+        if name.len() < MIN_SOURCE_NAME_LEN {
+            return;
+        }
+        let Some(first_word) = words(&name).next().map(|(_, word)| word.to_owned()) else {
+            return;
+        };
+        self.by_first_word
+            .entry(first_word)
+            .or_default()
+            .push((name, source));
+    }
+
+    pub fn annotate(&self, text: &str) -> Annotations {
+        let mut annotations = Annotations::parse_words(text);
+        annotations.sources = self.mentioned_sources(text);
+        annotations
+    }
+
+    /// Sources named by whole words of the text, in order of appearance.
+    fn mentioned_sources(&self, text: &str) -> Vec<Source> {
+        let text = text.to_lowercase();
+        let mut sources: Vec<Source> = vec![];
+        for (start, word) in words(&text) {
+            let Some(candidates) = self.by_first_word.get(word) else {
+                continue;
+            };
+            for (name, source) in candidates {
+                let end = start + name.len();
+                if text[start..].starts_with(name.as_str())
+                    && is_whole_word(&text, start, end)
+                    && !sources.contains(source)
+                {
+                    sources.push(source.clone());
+                }
+            }
+        }
+        sources
+    }
+}
+
+/// Words with their byte offsets. Tags like `forest_map_area` are one word.
+fn words(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split(|c: char| !is_word_char(c))
+        .filter(|word| !word.is_empty())
+        .map(move |word| (word.as_ptr() as usize - text.as_ptr() as usize, word))
+}
+
+fn is_whole_word(text: &str, start: usize, end: usize) -> bool {
+    let before = text[..start].chars().next_back();
+    let after = text[end..].chars().next();
+    [before, after]
+        .into_iter()
+        .flatten()
+        .all(|c| !is_word_char(c))
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poe_data::{
+        act::{ActArea, ActAreaId, Bossfight},
+        cards::CardsData,
+        mapbosses::MapBoss,
+        maps::Map,
+    };
+
+    #[test]
+    fn parses_notes() {
+        let poe_data = PoeData {
+            acts: vec![ActArea {
+                id: ActAreaId::new("1_1_7_1".to_owned()),
+                name: "The Submerged Passage".to_owned(),
+                act: 1,
+                area_level: 5,
+                bossfights: vec![Bossfight {
+                    name: "Hailrake".to_owned(),
+                    url: String::new(),
+                }],
+                ..Default::default()
+            }],
+            cards: CardsData(HashMap::new()),
+            maps: vec![Map {
+                name: "Spider Forest Map".to_owned(),
+                tier: 4,
+                available: true,
+                unique: false,
+                icon: String::new(),
+                slug: "spider-forest-map".to_owned(),
+            }],
+            mapbosses: vec![MapBoss {
+                name: "Rama, The Kinslayer".to_owned(),
+                maps: vec!["Acid Caverns Map".to_owned()],
+            }],
+        };
+
+        let hypothesis = Annotations::parse("forest_map_area", &poe_data);
+        assert_eq!(hypothesis.tags, vec!["forest_map_area"]);
+        assert!(hypothesis.sources.is_empty());
+
+        let notes = Annotations::parse(
+            "Divcord: 3.20, Rama, The Kinslayer verified by Zimzams (`QBotU_DyNess`)\n\
+             Spider Forest verified in divcord. Datamined as `forest_map_area`; see \
+             https://discord.com/channels/834368692560461846/990692208191627334.\n\
+             3.21: also drops from Kirac Missions",
+            &poe_data,
+        );
+        assert_eq!(notes.tags, vec!["forest_map_area"]);
+        assert_eq!(notes.leagues, vec!["3.20", "3.21"]);
+        assert_eq!(
+            notes.urls,
+            vec!["https://discord.com/channels/834368692560461846/990692208191627334"]
+        );
+        assert!(notes.datamined);
+        assert_eq!(
+            notes.sources,
+            vec![
+                Source::MapBoss("Rama, The Kinslayer".to_owned()),
+                Source::Map("Spider Forest Map".to_owned()),
+                Source::Predefined(PredefinedSource::KiracMissions),
+            ]
+        );
+
+        assert_eq!(
+            Annotations::parse("Hailrake in The Submerged Passage", &poe_data).sources,
+            vec![
+                Source::ActBoss("Hailrake".to_owned()),
+                Source::Act(ActAreaId::new("1_1_7_1".to_owned())),
+            ]
+        );
+
+        assert!(Annotations::parse("Forest", &poe_data).is_empty());
+        assert!(Annotations::parse("spider_forest_area", &poe_data)
+            .sources
+            .is_empty());
+    }
+}
@@ -1,4 +1,8 @@
-use crate::{dropsource::predefined::PredefinedSource, Record, Source};
+use crate::{
+    dropsource::predefined::PredefinedSource,
+    graph::{Node, SourceGraph},
+    Record, Source,
+};
 use poe_data::{act::Bossfight, mapbosses::MapBoss, maps::Map, PoeData};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -11,16 +15,18 @@ pub enum VerificationStatus {
     Verify,
 }
 
+/// Direct and transitive cards of the source. Build the graph once with [`SourceGraph::new`]
+/// and reuse it between calls.
 pub fn cards_by_source<'a>(
     source: &'a Source,
     records: &'a [Record],
-    poe_data: &'a PoeData,
+    graph: &'a SourceGraph,
 ) -> Vec<CardBySource> {
     let direct_cards = get_direct_cards_from_source(source, records)
         .collect::<HashSet<Direct>>()
         .into_iter()
         .map(CardBySource::Direct);
-    let transitive_cards = get_transitive_cards_from_source(source, records, poe_data)
+    let transitive_cards = get_transitive_cards_from_source(source, records, graph)
         .collect::<HashSet<Transitive>>()
         .into_iter()
         .map(CardBySource::Transitive);
@@ -30,7 +36,7 @@ pub fn cards_by_source<'a>(
 pub fn cards_by_source_types(
     source_types: &[String],
     records: &[Record],
-    poe_data: &PoeData,
+    graph: &SourceGraph,
 ) -> Vec<SourceAndCards> {
     let mut hash_map: HashMap<Source, HashSet<CardBySource>> = HashMap::new();
 
@@ -56,39 +62,19 @@ pub fn cards_by_source_types(
             .for_each(|v| process_source(r, v, VerificationStatus::Verify));
     });
 
-    // 2. Transitive sources(for maps and acts)
-    if source_types.contains(&"Map".to_owned()) {
-        poe_data.maps.iter().for_each(|m| {
-            let map = Source::Map(m.name.clone());
-
-            let set: HashSet<CardBySource> =
-                get_transitive_cards_from_source(&map, records, poe_data)
-                    .map(CardBySource::Transitive)
-                    .collect();
-
-            if !set.is_empty() {
-                hash_map.entry(map).or_default().extend(set);
-            }
-        });
-    };
-
-    if source_types.contains(&"Act".to_owned()) {
-        poe_data.acts.iter().for_each(|a| {
-            if a.is_town {
-                return;
-            }
-
-            let act = Source::Act(a.id.clone());
+    // 2. Transitive sources(for maps, acts and members of groups)
+    for source in graph.nodes().iter().filter_map(Node::source) {
+        if !source_types.iter().any(|s| source._type() == *s) {
+            continue;
+        }
 
-            let set: HashSet<CardBySource> =
-                get_transitive_cards_from_source(&act, records, poe_data)
-                    .map(CardBySource::Transitive)
-                    .collect();
+        let set: HashSet<CardBySource> = get_transitive_cards_from_source(source, records, graph)
+            .map(CardBySource::Transitive)
+            .collect();
 
-            if !set.is_empty() {
-                hash_map.entry(act).or_default().extend(set);
-            }
-        });
+        if !set.is_empty() {
+            hash_map.entry(source.clone()).or_default().extend(set);
+        }
     }
 
     hash_map
@@ -100,7 +86,9 @@ pub fn cards_by_source_types(
         .collect()
 }
 
+/// Denies unknown fields so that untagged [`CardBySource`] deserializes [`Transitive`] as such.
 #[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct Direct {
     // #[serde(skip_serializing)]
     // pub source: Source,
@@ -196,33 +184,20 @@ impl From<poe_data::act::ActArea> for Source {
     }
 }
 
+/// Sources that also drop in this one, bosses of maps and acts. See [`crate::graph`].
+#[deprecated(note = "use `SourceGraph::transitive_sources`, which also resolves group members")]
 pub fn transitive_sources(source: &Source, poe_data: &PoeData) -> Vec<Source> {
-    match source {
-        Source::Act(act) => poe_data
-            .act_area_id(act)
-            .map(|act_area| {
-                act_area
-                    .bossfights
-                    .iter()
-                    .map(|b| Source::from(b.to_owned()))
-                    .collect()
-            })
-            .unwrap_or_default(),
-        Source::Map(map) => poe_data
-            .bosses_of_map(map)
-            .into_iter()
-            .map(|b| Source::from(b.to_owned()))
-            .collect(),
-        _ => vec![],
-    }
+    crate::graph::bosses_of(source, poe_data)
 }
 
+/// Cards of the [transitive sources](SourceGraph::transitive_sources) of `direct_source`.
 pub fn get_transitive_cards_from_source<'a>(
     direct_source: &'a Source,
     records: &'a [Record],
-    poe_data: &'a PoeData,
+    graph: &'a SourceGraph,
 ) -> impl Iterator<Item = Transitive> + 'a {
-    transitive_sources(direct_source, poe_data)
+    graph
+        .transitive_sources(direct_source)
         .into_iter()
         .flat_map(move |transit| {
             get_direct_cards_from_source(&transit, records)
@@ -282,7 +257,8 @@ impl From<Direct> for CardBySource {
 mod tests {
     use super::VerificationStatus;
     use crate::{
-        dropsource::predefined::PredefinedSource, spreadsheet::record::Confidence, Record, Source,
+        dropsource::predefined::PredefinedSource, graph::SourceGraph,
+        spreadsheet::record::Confidence, Record, Source,
     };
 
     #[test]
@@ -298,6 +274,8 @@ mod tests {
                 remaining_work: Default::default(),
                 sources: vec![source.clone()],
                 notes: None,
+                tag_hypothesis_annotations: Default::default(),
+                notes_annotations: Default::default(),
                 verify_sources: vec![],
             },
             Record {
@@ -309,6 +287,8 @@ mod tests {
                 remaining_work: Default::default(),
                 sources: vec![],
                 notes: None,
+                tag_hypothesis_annotations: Default::default(),
+                notes_annotations: Default::default(),
                 verify_sources: vec![source.clone()],
             },
             Record {
@@ -320,6 +300,8 @@ mod tests {
                 remaining_work: Default::default(),
                 sources: vec![],
                 notes: None,
+                tag_hypothesis_annotations: Default::default(),
+                notes_annotations: Default::default(),
                 verify_sources: vec![],
             },
         ];
@@ -352,6 +334,8 @@ mod tests {
             remaining_work: Default::default(),
             sources: vec![boss_source.clone()],
             notes: None,
+            tag_hypothesis_annotations: Default::default(),
+            notes_annotations: Default::default(),
             verify_sources: vec![],
         }];
 
@@ -365,8 +349,9 @@ mod tests {
             }],
         };
 
+        let graph = SourceGraph::new(&poe_data);
         let result: Vec<_> =
-            super::get_transitive_cards_from_source(&map_source, &records, &poe_data).collect();
+            super::get_transitive_cards_from_source(&map_source, &records, &graph).collect();
 
         assert_eq!(result.len(), 1);
         let transitive = &result[0];
@@ -393,6 +378,8 @@ mod tests {
                 remaining_work: Default::default(),
                 sources: vec![map_source.clone()],
                 notes: None,
+                tag_hypothesis_annotations: Default::default(),
+                notes_annotations: Default::default(),
                 verify_sources: vec![],
             },
             Record {
@@ -404,6 +391,8 @@ mod tests {
                 remaining_work: Default::default(),
                 sources: vec![boss_source.clone()],
                 notes: None,
+                tag_hypothesis_annotations: Default::default(),
+                notes_annotations: Default::default(),
                 verify_sources: vec![],
             },
         ];
@@ -418,7 +407,8 @@ mod tests {
             }],
         };
 
-        let result = super::cards_by_source(&map_source, &records, &poe_data);
+        let graph = SourceGraph::new(&poe_data);
+        let result = super::cards_by_source(&map_source, &records, &graph);
 
         assert_eq!(result.len(), 2);
         assert!(result.iter().any(|c| c.is_direct() && c.card() == "Direct Card"));
@@ -444,6 +434,8 @@ mod tests {
                 remaining_work: Default::default(),
                 sources: vec![map_source.clone()],
                 notes: None,
+                tag_hypothesis_annotations: Default::default(),
+                notes_annotations: Default::default(),
                 verify_sources: vec![],
             },
             Record {
@@ -455,6 +447,8 @@ mod tests {
                 remaining_work: Default::default(),
                 sources: vec![boss_source.clone()],
                 notes: None,
+                tag_hypothesis_annotations: Default::default(),
+                notes_annotations: Default::default(),
                 verify_sources: vec![],
             },
             Record {
@@ -466,6 +460,8 @@ mod tests {
                 remaining_work: Default::default(),
                 sources: vec![other_source],
                 notes: None,
+                tag_hypothesis_annotations: Default::default(),
+                notes_annotations: Default::default(),
                 verify_sources: vec![],
             },
         ];
@@ -487,8 +483,9 @@ mod tests {
             }],
         };
 
+        let graph = SourceGraph::new(&poe_data);
         let result =
-            super::cards_by_source_types(&["Map".to_string()], &records, &poe_data);
+            super::cards_by_source_types(&["Map".to_string()], &records, &graph);
 
         assert_eq!(result.len(), 1);
         let source_and_cards = &result[0];
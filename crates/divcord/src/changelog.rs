@@ -0,0 +1,391 @@
+//! Changes between two snapshots of records, for "what changed" notes.
+//!
+//! Records are matched by card name, so reordered or inserted rows don't show up as changes.
+
+use crate::{
+    cards::VerificationStatus,
+    dropsource::{id::Identified, Source},
+    parse::records_iter,
+    spreadsheet::record::{Confidence, GreyNote, Record, RemainingWork},
+    PoeData, Spreadsheet,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum Change {
+    CardAdded,
+    CardRemoved,
+    Greynote {
+        from: GreyNote,
+        to: GreyNote,
+    },
+    TagHypothesis {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    Confidence {
+        from: Confidence,
+        to: Confidence,
+    },
+    RemainingWork {
+        from: RemainingWork,
+        to: RemainingWork,
+    },
+    Notes {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    Source {
+        source: Source,
+        change: SourceChange,
+    },
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SourceChange {
+    Added(VerificationStatus),
+    Removed(VerificationStatus),
+    /// Moved from `verify_sources` to `sources`.
+    Verified,
+    /// Moved from `sources` back to `verify_sources`.
+    Unverified,
+}
+
+impl Display for SourceChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = |status: &VerificationStatus| match status {
+            VerificationStatus::Done => "",
+            VerificationStatus::Verify => " (to verify)",
+        };
+        match self {
+            SourceChange::Added(s) => write!(f, "added{}", status(s)),
+            SourceChange::Removed(s) => write!(f, "removed{}", status(s)),
+            SourceChange::Verified => f.write_str("verified"),
+            SourceChange::Unverified => f.write_str("moved back to verify"),
+        }
+    }
+}
+
+impl Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = |value: &Option<String>| match value {
+            Some(value) => format!("\"{value}\""),
+            None => "none".to_owned(),
+        };
+        match self {
+            Change::CardAdded => f.write_str("New card"),
+            Change::CardRemoved => f.write_str("Card removed"),
+            Change::Greynote { from, to } => write!(f, "Greynote: {from} → {to}"),
+            Change::TagHypothesis { from, to } => {
+                write!(f, "Tag hypothesis: {} → {}", text(from), text(to))
+            }
+            Change::Confidence { from, to } => write!(f, "Confidence: {from} → {to}"),
+            Change::RemainingWork { from, to } => write!(f, "Remaining work: {from} → {to}"),
+            Change::Notes { from, to } => write!(f, "Notes: {} → {}", text(from), text(to)),
+            Change::Source { source, change } => {
+                write!(f, "{} {}: {change}", source._type(), source.id())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CardChanges {
+    pub card: String,
+    pub changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceCardChange {
+    pub card: String,
+    pub change: SourceChange,
+}
+
+/// Changes of one source across all cards.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceChanges {
+    pub source: Source,
+    pub cards: Vec<SourceCardChange>,
+}
+
+/// Changed cards, sorted by name. Serializes to JSON for publishing.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Changelog {
+    pub cards: Vec<CardChanges>,
+}
+
+/// Records of one card. A card can take several rows, their sources are combined.
+struct CardRecords<'a> {
+    first: &'a Record,
+    sources: HashMap<&'a Source, VerificationStatus>,
+}
+
+fn by_card(records: &[Record]) -> BTreeMap<&str, CardRecords<'_>> {
+    let mut cards: BTreeMap<&str, CardRecords> = BTreeMap::new();
+    for record in records {
+        let card = cards.entry(&record.card).or_insert_with(|| CardRecords {
+            first: record,
+            sources: HashMap::new(),
+        });
+        for source in &record.verify_sources {
+            card.sources.insert(source, VerificationStatus::Verify);
+        }
+        // A source that is both verified and to verify counts as verified.
+        for source in &record.sources {
+            card.sources.insert(source, VerificationStatus::Done);
+        }
+    }
+    cards
+}
+
+fn source_changes(old: &CardRecords, new: &CardRecords) -> Vec<Change> {
+    let mut changes: Vec<Change> = vec![];
+    for (source, old_status) in &old.sources {
+        let change = match (old_status, new.sources.get(source)) {
+            (status, None) => SourceChange::Removed(*status),
+            (VerificationStatus::Verify, Some(VerificationStatus::Done)) => SourceChange::Verified,
+            (VerificationStatus::Done, Some(VerificationStatus::Verify)) => {
+                SourceChange::Unverified
+            }
+            _ => continue,
+        };
+        changes.push(Change::Source {
+            source: (*source).clone(),
+            change,
+        });
+    }
+    for (source, status) in &new.sources {
+        if !old.sources.contains_key(source) {
+            changes.push(Change::Source {
+                source: (*source).clone(),
+                change: SourceChange::Added(*status),
+            });
+        }
+    }
+
+    changes.sort_by(|a, b| match (a, b) {
+        (Change::Source { source: a, .. }, Change::Source { source: b, .. }) => {
+            (a._type(), a.id()).cmp(&(b._type(), b.id()))
+        }
+        _ => std::cmp::Ordering::Equal,
+    });
+    changes
+}
+
+fn card_changes(old: &CardRecords, new: &CardRecords) -> Vec<Change> {
+    let (old_record, new_record) = (old.first, new.first);
+    let mut changes = vec![];
+    if old_record.greynote != new_record.greynote {
+        changes.push(Change::Greynote {
+            from: old_record.greynote.clone(),
+            to: new_record.greynote.clone(),
+        });
+    }
+    if old_record.tag_hypothesis != new_record.tag_hypothesis {
+        changes.push(Change::TagHypothesis {
+            from: old_record.tag_hypothesis.clone(),
+            to: new_record.tag_hypothesis.clone(),
+        });
+    }
+    if old_record.confidence != new_record.confidence {
+        changes.push(Change::Confidence {
+            from: old_record.confidence.clone(),
+            to: new_record.confidence.clone(),
+        });
+    }
+    if old_record.remaining_work != new_record.remaining_work {
+        changes.push(Change::RemainingWork {
+            from: old_record.remaining_work.clone(),
+            to: new_record.remaining_work.clone(),
+        });
+    }
+    if old_record.notes != new_record.notes {
+        changes.push(Change::Notes {
+            from: old_record.notes.clone(),
+            to: new_record.notes.clone(),
+        });
+    }
+    changes.extend(source_changes(old, new));
+    changes
+}
+
+impl Changelog {
+    pub fn new(old: &[Record], new: &[Record]) -> Self {
+        let old = by_card(old);
+        let mut new = by_card(new);
+        let empty = |first| CardRecords {
+            first,
+            sources: HashMap::new(),
+        };
+
+        let mut cards: Vec<CardChanges> = vec![];
+        for (card, old_card) in &old {
+            let changes = match new.remove(card) {
+                Some(new_card) => card_changes(old_card, &new_card),
+                None => {
+                    let mut changes = vec![Change::CardRemoved];
+                    changes.extend(source_changes(old_card, &empty(old_card.first)));
+                    changes
+                }
+            };
+            if !changes.is_empty() {
+                cards.push(CardChanges {
+                    card: (*card).to_owned(),
+                    changes,
+                });
+            }
+        }
+        for (card, new_card) in new {
+            let mut changes = vec![Change::CardAdded];
+            changes.extend(source_changes(&empty(new_card.first), &new_card));
+            cards.push(CardChanges {
+                card: card.to_owned(),
+                changes,
+            });
+        }
+
+        cards.sort_by(|a, b| a.card.cmp(&b.card));
+        Changelog { cards }
+    }
+
+    /// Diffs records of two spreadsheet snapshots. Rows that fail to parse are left out of both.
+    pub fn from_spreadsheets(old: &Spreadsheet, new: &Spreadsheet, poe_data: &PoeData) -> Self {
+        let records = |spreadsheet| -> Vec<Record> {
+            records_iter(spreadsheet, poe_data)
+                .filter_map(|result| result.ok().map(|result| result.record))
+                .collect()
+        };
+        Changelog::new(&records(old), &records(new))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cards.is_empty()
+    }
+
+    /// The same changes grouped by source, sorted by source type and id.
+    pub fn by_source(&self) -> Vec<SourceChanges> {
+        let mut by_source: Vec<SourceChanges> = vec![];
+        for card in &self.cards {
+            for change in &card.changes {
+                let Change::Source { source, change } = change else {
+                    continue;
+                };
+                let card_change = SourceCardChange {
+                    card: card.card.clone(),
+                    change: *change,
+                };
+                match by_source.iter_mut().find(|s| &s.source == source) {
+                    Some(source_changes) => source_changes.cards.push(card_change),
+                    None => by_source.push(SourceChanges {
+                        source: source.clone(),
+                        cards: vec![card_change],
+                    }),
+                }
+            }
+        }
+        by_source.sort_by(|a, b| {
+            (a.source._type(), a.source.id()).cmp(&(b.source._type(), b.source.id()))
+        });
+        by_source
+    }
+}
+
+/// Markdown list of changes per card.
+impl Display for Changelog {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_empty() {
+            return f.write_str("No changes\n");
+        }
+        for (i, card) in self.cards.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            writeln!(f, "## {}", card.card)?;
+            for change in &card.changes {
+                writeln!(f, "- {change}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spreadsheet::record;
+
+    /// Low confidence record, as the ones to be verified.
+    fn record(card: &str, sources: Vec<Source>, verify_sources: Vec<Source>) -> Record {
+        Record {
+            confidence: Confidence::Low,
+            verify_sources,
+            ..record::record(3, card, sources)
+        }
+    }
+
+    #[test]
+    fn diffs_records() {
+        let map = |name: &str| Source::Map(name.to_owned());
+        let old = vec![
+            record(
+                "The Doctor",
+                vec![map("Burial Chambers")],
+                vec![map("Spider Forest")],
+            ),
+            record("Humility", vec![map("Tower")], vec![]),
+            record("Rain of Chaos", vec![], vec![]),
+        ];
+        let mut doctor = record("The Doctor", vec![map("Spider Forest")], vec![]);
+        doctor.confidence = Confidence::Done;
+        let new = vec![
+            record("Rain of Chaos", vec![], vec![]),
+            doctor,
+            record("The Fiend", vec![], vec![map("Burial Chambers")]),
+        ];
+
+        let changelog = Changelog::new(&old, &new);
+        let cards: Vec<&str> = changelog.cards.iter().map(|c| c.card.as_str()).collect();
+        assert_eq!(cards, ["Humility", "The Doctor", "The Fiend"]);
+
+        assert_eq!(
+            changelog.cards[1].changes,
+            [
+                Change::Confidence {
+                    from: Confidence::Low,
+                    to: Confidence::Done
+                },
+                Change::Source {
+                    source: map("Burial Chambers"),
+                    change: SourceChange::Removed(VerificationStatus::Done)
+                },
+                Change::Source {
+                    source: map("Spider Forest"),
+                    change: SourceChange::Verified
+                },
+            ]
+        );
+        assert_eq!(changelog.cards[0].changes[0], Change::CardRemoved);
+
+        let by_source = changelog.by_source();
+        assert_eq!(by_source[0].source, map("Burial Chambers"));
+        assert_eq!(by_source[0].cards.len(), 2);
+
+        let text = changelog.to_string();
+        assert!(text.contains("## The Doctor\n- Confidence: Low → Done\n"));
+        assert!(text.contains("- Map Burial Chambers: added (to verify)"));
+
+        let json = serde_json::to_value(&changelog).unwrap();
+        assert_eq!(json["cards"][1]["changes"][0]["kind"], "confidence");
+        assert!(Changelog::new(&new, &new).is_empty());
+    }
+}
@@ -2,12 +2,15 @@
 
 use id::Identified;
 use poe_data::act::ActAreaId;
-#[allow(unused_imports)]
 use poe_data::PoeData;
 use predefined::PredefinedSource;
 use serde::{de, ser::SerializeStruct, Deserialize, Serialize};
+use std::ops::RangeInclusive;
 use strum::IntoEnumIterator;
 
+/// Area level of tier 16 maps.
+pub const MAX_MAP_LEVEL: u32 = 83;
+
 pub mod id;
 pub mod predefined;
 
@@ -100,6 +103,48 @@ impl Source {
         }
     }
 
+    /// Lowest and highest area level where the source drops cards. Bosses appear in several
+    /// maps or acts, predefined sources have a level only if their definition sets one.
+    pub fn area_levels(&self, poe_data: &PoeData) -> Option<RangeInclusive<u32>> {
+        // Voidstones raise the tier of every map up to 16.
+        let map_levels = |name: &str| {
+            poe_data
+                .maps
+                .iter()
+                .find(|map| map.name == name || map.name.replace(" Map", "") == name)
+                .map(|map| map.level()..=MAX_MAP_LEVEL.max(map.level()))
+        };
+        let levels: Vec<RangeInclusive<u32>> = match self {
+            Source::Act(id) => poe_data
+                .act_area_id(id)
+                .map(|act| u32::from(act.area_level)..=u32::from(act.area_level))
+                .into_iter()
+                .collect(),
+            Source::ActBoss(name) => poe_data
+                .acts
+                .iter()
+                .filter(|act| act.bossfights.iter().any(|boss| &boss.name == name))
+                .map(|act| u32::from(act.area_level)..=u32::from(act.area_level))
+                .collect(),
+            Source::Map(name) => map_levels(name).into_iter().collect(),
+            Source::MapBoss(name) => poe_data
+                .mapbosses
+                .iter()
+                .filter(|boss| &boss.name == name)
+                .flat_map(|boss| boss.maps.iter().filter_map(|map| map_levels(map)))
+                .collect(),
+            Source::Predefined(source) => predefined::definitions::definitions()
+                .find(source.id())
+                .and_then(|definition| definition.area_level)
+                .map(|level| level..=level)
+                .into_iter()
+                .collect(),
+        };
+        let lowest = levels.iter().map(|levels| *levels.start()).min()?;
+        let highest = levels.iter().map(|levels| *levels.end()).max()?;
+        Some(lowest..=highest)
+    }
+
     pub fn disabled() -> Source {
         Source::Predefined(PredefinedSource::Disabled)
     }
@@ -1,5 +1,4 @@
-use super::super::id::{parseid, Identified, UnknownVariant};
-use std::str::FromStr;
+use super::super::id::Identified;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
@@ -34,33 +33,24 @@ impl Area {
         }
     }
 
-    pub fn _types() -> impl Iterator<Item = String> {
-        Area::iter().map(|a| a._type().to_string())
+    /// Every area, including each variant of nested groups.
+    pub fn all() -> Vec<Area> {
+        Area::iter()
+            .flat_map(|area| -> Vec<Area> {
+                match area {
+                    Area::AreaSpecific(_) => AreaSpecific::iter().map(Area::AreaSpecific).collect(),
+                    Area::AtziriArea(_) => AtziriArea::iter().map(Area::AtziriArea).collect(),
+                    Area::UniqueHeistContractOrBoss(_) => UniqueHeistContractOrBoss::iter()
+                        .map(Area::UniqueHeistContractOrBoss)
+                        .collect(),
+                    area => vec![area],
+                }
+            })
+            .collect()
     }
-}
-
-impl FromStr for Area {
-    type Err = strum::ParseError;
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Trial of Stinging Doubt" => Ok(Self::TrialOfStingingDoubt),
-            "The Temple of Atzoatl" => Ok(Self::TempleOfAtzoatl),
-            "Redeemer influenced maps" => Ok(Self::RedeemerInfluencedMaps),
-            "All Vaal side areas" | "All Vaal side areas (need specific information)" => {
-                Ok(Self::AllVaalSideAreas)
-            }
-            "Vaal Side Areas" => Ok(Self::VaalSideAreas),
-            "Labyrinth Trial Areas" => Ok(Self::LabyrinthTrialAreas),
-            "Expedition Logbook" | "Expedition Logbooks" => Ok(Self::ExpeditionLogbook),
-            _ => AreaSpecific::from_str(s)
-                .map(Self::AreaSpecific)
-                .or_else(|_| AtziriArea::from_str(s).map(Self::AtziriArea))
-                .or_else(|_| {
-                    UniqueHeistContractOrBoss::from_str(s).map(Self::UniqueHeistContractOrBoss)
-                })
-                .map_err(|_| strum::ParseError::VariantNotFound),
-        }
+    pub fn _types() -> impl Iterator<Item = String> {
+        Area::iter().map(|a| a._type().to_string())
     }
 }
 
@@ -106,14 +96,6 @@ impl Identified for AtziriArea {
     }
 }
 
-impl FromStr for AtziriArea {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum AreaSpecific {
     #[default]
@@ -136,14 +118,6 @@ impl Identified for AreaSpecific {
     }
 }
 
-impl FromStr for AreaSpecific {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum UniqueHeistContractOrBoss {
     #[default]
@@ -166,45 +140,30 @@ impl Identified for UniqueHeistContractOrBoss {
             }
         }
     }
-
-    fn aliases(&self) -> Vec<&str> {
-        match self {
-            UniqueHeistContractOrBoss::DeathToDarnaw => vec!["Darnaw's Landing"],
-            UniqueHeistContractOrBoss::TheSlaverKing => vec!["The Body Pit"],
-            UniqueHeistContractOrBoss::HeartOfGlory => vec!["Pillaged Camp"],
-            UniqueHeistContractOrBoss::TheTwins => vec!["The Den"],
-            UniqueHeistContractOrBoss::BreakingTheUnbreakable => {
-                vec!["Combat Capacity Test Chamber"]
-            }
-        }
-    }
-}
-
-impl FromStr for UniqueHeistContractOrBoss {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::dropsource::predefined::PredefinedSource;
 
     #[test]
     fn redeemer() {
         assert_eq!(
-            "Redeemer influenced maps".parse::<Area>().unwrap(),
-            Area::RedeemerInfluencedMaps
+            "Redeemer influenced maps"
+                .parse::<PredefinedSource>()
+                .unwrap(),
+            PredefinedSource::Area(Area::RedeemerInfluencedMaps)
         )
     }
 
     #[test]
     fn heist_aliases() {
         assert_eq!(
-            "The Den".parse::<Area>().unwrap(),
-            Area::UniqueHeistContractOrBoss(UniqueHeistContractOrBoss::TheTwins)
+            "The Den".parse::<PredefinedSource>().unwrap(),
+            PredefinedSource::Area(Area::UniqueHeistContractOrBoss(
+                UniqueHeistContractOrBoss::TheTwins
+            ))
         )
     }
 }
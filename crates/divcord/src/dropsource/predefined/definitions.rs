@@ -0,0 +1,223 @@
+//! Definitions of predefined sources: ids, types and aliases that the spreadsheet uses.
+//!
+//! The crate ships `definitions.json`. Sources missing from the Rust enums parse into
+//! [`PredefinedSource::Defined`], so a new unique monster or chest only needs a definition.
+//! Definitions can be replaced or extended at runtime with [`set_definitions`], which merges
+//! them into the shipped ones.
+
+use super::PredefinedSource;
+use crate::dropsource::id::Identified;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    fmt::Display,
+    sync::{Arc, LazyLock, RwLock},
+};
+
+const BUILTIN: &str = include_str!("definitions.json");
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Definition {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub source_type: String,
+    /// Group of related types, like `Unique Monster` for bosses and monster categories.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent: Option<String>,
+    /// Types of the sources a group source consists of, like `Rogue Exile` for `All Rogue Exiles`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub members: Vec<String>,
+    /// Other spellings used in the spreadsheet.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub area_level: Option<u32>,
+}
+
+/// Definitions with lookup by id and alias. The first definition of a name wins.
+#[derive(Debug, Clone, Default)]
+pub struct Definitions {
+    definitions: Vec<Definition>,
+    by_name: HashMap<String, usize>,
+}
+
+#[derive(Debug)]
+pub enum DefinitionsError {
+    Json(serde_json::Error),
+    Io(std::io::Error),
+}
+
+impl Display for DefinitionsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DefinitionsError::Json(err) => write!(f, "Invalid source definitions. {err}"),
+            DefinitionsError::Io(err) => write!(f, "Could not read source definitions. {err}"),
+        }
+    }
+}
+
+impl std::error::Error for DefinitionsError {}
+
+impl From<serde_json::Error> for DefinitionsError {
+    fn from(value: serde_json::Error) -> Self {
+        DefinitionsError::Json(value)
+    }
+}
+
+impl From<std::io::Error> for DefinitionsError {
+    fn from(value: std::io::Error) -> Self {
+        DefinitionsError::Io(value)
+    }
+}
+
+impl Definitions {
+    pub fn new(definitions: Vec<Definition>) -> Self {
+        let mut by_name: HashMap<String, usize> = HashMap::new();
+        for (i, definition) in definitions.iter().enumerate() {
+            for name in std::iter::once(&definition.id).chain(&definition.aliases) {
+                by_name.entry(name.clone()).or_insert(i);
+            }
+        }
+        Definitions {
+            definitions,
+            by_name,
+        }
+    }
+
+    /// Definitions shipped with the crate.
+    pub fn builtin() -> Self {
+        Self::from_json(BUILTIN).expect("Built-in source definitions should be valid")
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, DefinitionsError> {
+        Ok(Self::new(serde_json::from_str(json)?))
+    }
+
+    pub fn from_file(path: impl AsRef<std::path::Path>) -> Result<Self, DefinitionsError> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Replaces definitions with the same id and appends new ones.
+    pub fn merge(self, overrides: Definitions) -> Self {
+        let mut definitions = self.definitions;
+        for definition in overrides.definitions {
+            match definitions.iter_mut().find(|d| d.id == definition.id) {
+                Some(existing) => *existing = definition,
+                None => definitions.push(definition),
+            }
+        }
+        Self::new(definitions)
+    }
+
+    /// Definition by id or alias.
+    pub fn find(&self, name: &str) -> Option<&Definition> {
+        self.by_name.get(name).map(|&i| &self.definitions[i])
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Definition> {
+        self.definitions.iter()
+    }
+}
+
+static DEFINITIONS: LazyLock<RwLock<Arc<Definitions>>> =
+    LazyLock::new(|| RwLock::new(Arc::new(Definitions::builtin())));
+
+/// Variants of the Rust enums by id, for sources that code refers to.
+static VARIANTS: LazyLock<HashMap<String, PredefinedSource>> = LazyLock::new(|| {
+    let mut variants = HashMap::new();
+    for source in PredefinedSource::all() {
+        variants.entry(source.id().to_owned()).or_insert(source);
+    }
+    variants
+});
+
+/// Definitions used for parsing.
+pub fn definitions() -> Arc<Definitions> {
+    DEFINITIONS
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clone()
+}
+
+/// Merges `overrides` into the built-in definitions and uses the result for parsing. Overrides
+/// from earlier calls are dropped.
+pub fn set_definitions(overrides: Definitions) {
+    *DEFINITIONS
+        .write()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) =
+        Arc::new(Definitions::builtin().merge(overrides));
+}
+
+impl From<&Definition> for PredefinedSource {
+    fn from(definition: &Definition) -> Self {
+        match VARIANTS.get(&definition.id) {
+            Some(variant) => variant.clone(),
+            None => PredefinedSource::Defined {
+                id: definition.id.clone(),
+                source_type: definition.source_type.clone(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builtin_definitions_match_enums() {
+        let definitions = Definitions::builtin();
+        for variant in VARIANTS.values() {
+            let definition = definitions.find(variant.id()).unwrap();
+            assert_eq!(definition.source_type, variant._type(), "{}", variant.id());
+            assert_eq!(&PredefinedSource::from(definition), variant);
+        }
+    }
+
+    #[test]
+    fn merges_overrides() {
+        let overrides = Definitions::from_json(
+            r#"[
+                {"id": "Delirium", "type": "Delirium", "aliases": ["Delirium Mirror"]},
+                {"id": "Sanctum Lycia", "type": "Sanctum Boss", "parent": "Unique Monster", "areaLevel": 83}
+            ]"#,
+        )
+        .unwrap();
+        let definitions = Definitions::builtin().merge(overrides);
+
+        assert_eq!(
+            PredefinedSource::from(definitions.find("Delirium Mirror").unwrap()),
+            PredefinedSource::Delirium
+        );
+        let lycia = definitions.find("Sanctum Lycia").unwrap();
+        assert_eq!(lycia.area_level, Some(83));
+        let source = PredefinedSource::from(lycia);
+        assert_eq!(source.id(), "Sanctum Lycia");
+        assert_eq!(source._type(), "Sanctum Boss");
+    }
+
+    #[test]
+    fn set_definitions_keeps_builtin() {
+        set_definitions(
+            Definitions::from_json(
+                r#"[{"id": "Test Override Source", "type": "Test", "aliases": ["Test Alias"]}]"#,
+            )
+            .unwrap(),
+        );
+
+        assert_eq!(
+            "Test Alias".parse::<PredefinedSource>().unwrap().id(),
+            "Test Override Source"
+        );
+        assert_eq!(
+            "Delirium Currency reward"
+                .parse::<PredefinedSource>()
+                .unwrap(),
+            PredefinedSource::DeliriumCurrencyRewards
+        );
+
+        set_definitions(Definitions::default());
+        assert!("Test Alias".parse::<PredefinedSource>().is_err());
+    }
+}
@@ -8,6 +8,7 @@ use std::str::FromStr;
 use strum::IntoEnumIterator;
 
 pub mod area;
+pub mod definitions;
 pub mod monster;
 mod other;
 
@@ -27,18 +28,61 @@ pub enum PredefinedSource {
     DeliriumCurrencyRewards,
     #[default]
     Disabled,
+
+    /// Source from [definitions](definitions::Definitions) without its own variant.
+    Defined {
+        id: String,
+        source_type: String,
+    },
 }
 
 impl PredefinedSource {
+    /// Every source, including each variant of nested groups.
+    pub fn all() -> Vec<PredefinedSource> {
+        PredefinedSource::iter()
+            .flat_map(|source| -> Vec<PredefinedSource> {
+                match source {
+                    PredefinedSource::UniqueMonster(_) => UniqueMonster::all()
+                        .into_iter()
+                        .map(PredefinedSource::UniqueMonster)
+                        .collect(),
+                    PredefinedSource::Area(_) => Area::all()
+                        .into_iter()
+                        .map(PredefinedSource::Area)
+                        .collect(),
+                    PredefinedSource::Chest(_) => {
+                        Chest::iter().map(PredefinedSource::Chest).collect()
+                    }
+                    PredefinedSource::Strongbox(_) => {
+                        Strongbox::iter().map(PredefinedSource::Strongbox).collect()
+                    }
+                    PredefinedSource::Vendor(_) => {
+                        Vendor::iter().map(PredefinedSource::Vendor).collect()
+                    }
+                    PredefinedSource::Defined { .. } => vec![],
+                    source => vec![source],
+                }
+            })
+            .collect()
+    }
+
     pub fn types() -> Vec<String> {
         let mut vec: Vec<String> = vec![];
 
         PredefinedSource::iter().for_each(|variant| match variant {
             PredefinedSource::UniqueMonster(_) => vec.extend(UniqueMonster::_types()),
             PredefinedSource::Area(_) => vec.extend(Area::_types()),
+            PredefinedSource::Defined { .. } => {}
             _ => vec.push(variant._type().to_string()),
         });
 
+        // Types that only definitions have.
+        for definition in definitions::definitions().iter() {
+            if !vec.contains(&definition.source_type) {
+                vec.push(definition.source_type.clone());
+            }
+        }
+
         vec
     }
 }
@@ -52,24 +96,12 @@ pub struct UnknownPredefinedSource(pub String);
 impl FromStr for PredefinedSource {
     type Err = UnknownPredefinedSource;
 
+    /// Looks up ids and aliases in [definitions](definitions::definitions).
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Kirac Missions" => Ok(PredefinedSource::KiracMissions),
-            "Disabled" => Ok(PredefinedSource::Disabled),
-            "Delirium Currency Rewards" | "Delirium Currency reward" => {
-                Ok(PredefinedSource::DeliriumCurrencyRewards)
-            }
-            "Maelström of Chaos with Barrel Sextant" => {
-                Ok(PredefinedSource::MaelstromOfChaosWithBarrelSextant)
-            }
-            _ => UniqueMonster::from_str(s)
-                .map(Self::UniqueMonster)
-                .or_else(|_| Area::from_str(s).map(Self::Area))
-                .or_else(|_| Vendor::from_str(s).map(Self::Vendor))
-                .or_else(|_| Strongbox::from_str(s).map(Self::Strongbox))
-                .or_else(|_| Chest::from_str(s).map(Self::Chest))
-                .map_err(|_| UnknownPredefinedSource(s.to_owned())),
-        }
+        definitions::definitions()
+            .find(s)
+            .map(PredefinedSource::from)
+            .ok_or_else(|| UnknownPredefinedSource(s.to_owned()))
     }
 }
 
@@ -90,6 +122,7 @@ impl PredefinedSource {
                 "Maelström of Chaos with Barrel Sextant"
             }
             PredefinedSource::KiracMissions => "Kirac Missions",
+            PredefinedSource::Defined { source_type, .. } => source_type,
         }
     }
 }
@@ -111,6 +144,7 @@ impl Identified for PredefinedSource {
                 "Maelström of Chaos with Barrel Sextant"
             }
             PredefinedSource::KiracMissions => "Kirac Missions",
+            PredefinedSource::Defined { id, .. } => id,
         }
     }
 }
@@ -1,10 +1,9 @@
-use super::super::id::{parseid, Identified, UnknownVariant};
-use std::str::FromStr;
+use super::super::id::Identified;
 use strum::IntoEnumIterator;
 use strum_macros::EnumIter;
 
-// When adding new variant, do not forget to add a line to
-// impl FromStr for UniqueMonster fn from_str
+// Sources are parsed through definitions.json, which holds every id and alias. A new monster
+// only needs a definition there; add a variant when code has to refer to it.
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum UniqueMonster {
     HivebornMonsters,
@@ -51,72 +50,6 @@ pub enum UniqueMonster {
     ShaperMiniBoss(ShaperMiniBoss),
 }
 
-impl FromStr for UniqueMonster {
-    type Err = strum::ParseError;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
-            "Hiveborn monsters" => Ok(Self::HivebornMonsters),
-            "Stygian Spire" => Ok(Self::StygianSpire),
-            "Breach monsters" => Ok(Self::BreachMonsters),
-            "All T3/T4 (boss) Harvest monsters" | "All T3-T4 (boss) Harvest monsters" => {
-                Ok(Self::Allt3Andt4HarvestMonsters)
-            }
-            "Maven's Invitation: The Feared" | "The Feared" => Ok(Self::MavensInvitationTheFeared),
-            "Uul-Netol, Unburdened Flesh (in Breachstones)" => Ok(Self::UulNetolInBreachstones),
-            "The Vaal Omnitect" => Ok(Self::VaalOmnitect),
-            "Metamorph" => Ok(Self::Metamorph),
-            "Null Portal" => Ok(Self::NullPortal),
-            "Vaal Flesh Merchant" => Ok(Self::VaalFleshMerchant),
-            "All Incursion Architects in Alva missions/Alva's Memory"
-            | "All Incursion Architects in Alva missions or Alva's Memory" => {
-                Ok(Self::AllIncursionArchitectsInAlvaMission)
-            }
-            "All Incursion Architects (The Temple of Atzoatl)"
-            | "Architects in Temple of Atzoatl" => Ok(Self::AllIncursionArchitectsInTemple),
-            "All Abyss Monsters" => Ok(Self::AllAbyssMonsters),
-            "All Rogue Exiles" => Ok(Self::AllRogueExiles),
-            "All Invasion Bosses" | "Invasion bosses" => Ok(Self::AllInvasionBosses),
-            "All Vaal Side Area Bosses" | "Vaal side area bosses (including Vaal Temple)" => {
-                Ok(Self::AllVaalSideAreaBosses)
-            }
-            "All (Scourge) beyond demons"
-            | "All (Scourge) beyond demons, including from Settlers Crimson Iron/Orichalcum"
-            | "All (Scourge) beyond demons, including from Settlers (Crimson Iron/Orichalcum)" => {
-                Ok(Self::AllScourgeBeyondDemons)
-            }
-            "Venarius" => Ok(Self::CortexVenarius),
-            "Argus" => Ok(Self::Argus),
-            _ => BreachlordBossDomain::from_str(s)
-                .map(Self::BreachlordBossDomain)
-                .or_else(|_| Incarnation::from_str(s).map(Self::Incarnation))
-                .or_else(|_| Architect::from_str(s).map(Self::Architect))
-                .or_else(|_| ShaperGuardianBoss::from_str(s).map(Self::ShaperGuardianBoss))
-                .or_else(|_| SyndicateMember::from_str(s).map(Self::SyndicateMember))
-                .or_else(|_| Elderslayer::from_str(s).map(Self::Elderslayer))
-                .or_else(|_| ElderGuardianBoss::from_str(s).map(Self::ElderGuardianBoss))
-                .or_else(|_| RogueExile::from_str(s).map(Self::RogueExile))
-                .or_else(|_| FemaleRogueExile::from_str(s).map(Self::FemaleRogueExile))
-                .or_else(|_| AbyssLichBoss::from_str(s).map(Self::AbyssLichBoss))
-                .or_else(|_| MapsOnly::from_str(s).map(Self::MapsOnly))
-                .or_else(|_| HarbingerPortal::from_str(s).map(Self::HarbingerPortal))
-                .or_else(|_| EndgameBoss::from_str(s).map(Self::EndgameBoss))
-                .or_else(|_| DelveBoss::from_str(s).map(Self::DelveBoss))
-                .or_else(|_| BeastBoss::from_str(s).map(Self::BeastBoss))
-                .or_else(|_| HeistBoss::from_str(s).map(Self::HeistBoss))
-                .or_else(|_| BeyondBoss::from_str(s).map(Self::BeyondBoss))
-                .or_else(|_| ExpeditionLogbookBoss::from_str(s).map(Self::ExpeditionLogbookBoss))
-                .or_else(|_| ShaperMiniBoss::from_str(s).map(Self::ShaperMiniBoss))
-                .or_else(|_| BetrayalCatarina::from_str(s).map(Self::BetrayalCatarina))
-                .or_else(|_| OshabiBoss::from_str(s).map(Self::OshabiBoss))
-                .or_else(|_| {
-                    EldritchPerfectionMonster::from_str(s).map(Self::EldritchPerfectionMonster)
-                })
-                .map_err(|_| strum::ParseError::VariantNotFound),
-        }
-    }
-}
-
 impl Identified for UniqueMonster {
     fn id(&self) -> &str {
         match self {
@@ -178,6 +111,85 @@ impl std::fmt::Display for UniqueMonster {
 }
 
 impl UniqueMonster {
+    /// Every monster, including each variant of nested groups.
+    pub fn all() -> Vec<UniqueMonster> {
+        UniqueMonster::iter()
+            .flat_map(|monster| -> Vec<UniqueMonster> {
+                match monster {
+                    UniqueMonster::BreachlordBossDomain(_) => BreachlordBossDomain::iter()
+                        .map(UniqueMonster::BreachlordBossDomain)
+                        .collect(),
+                    UniqueMonster::Incarnation(_) => Incarnation::iter()
+                        .map(UniqueMonster::Incarnation)
+                        .collect(),
+                    UniqueMonster::Architect(_) => {
+                        Architect::iter().map(UniqueMonster::Architect).collect()
+                    }
+                    UniqueMonster::ShaperGuardianBoss(_) => ShaperGuardianBoss::iter()
+                        .map(UniqueMonster::ShaperGuardianBoss)
+                        .collect(),
+                    UniqueMonster::SyndicateMember(_) => SyndicateMember::iter()
+                        .map(UniqueMonster::SyndicateMember)
+                        .collect(),
+                    UniqueMonster::Elderslayer(_) => Elderslayer::iter()
+                        .map(UniqueMonster::Elderslayer)
+                        .collect(),
+                    UniqueMonster::ElderGuardianBoss(_) => ElderGuardianBoss::iter()
+                        .map(UniqueMonster::ElderGuardianBoss)
+                        .collect(),
+                    UniqueMonster::RogueExile(_) => {
+                        RogueExile::iter().map(UniqueMonster::RogueExile).collect()
+                    }
+                    UniqueMonster::FemaleRogueExile(_) => FemaleRogueExile::iter()
+                        .map(UniqueMonster::FemaleRogueExile)
+                        .collect(),
+                    UniqueMonster::AbyssLichBoss(_) => AbyssLichBoss::iter()
+                        .map(UniqueMonster::AbyssLichBoss)
+                        .collect(),
+                    UniqueMonster::MapsOnly(_) => {
+                        MapsOnly::iter().map(UniqueMonster::MapsOnly).collect()
+                    }
+                    UniqueMonster::HarbingerPortal(_) => HarbingerPortal::iter()
+                        .map(UniqueMonster::HarbingerPortal)
+                        .collect(),
+                    UniqueMonster::EndgameBoss(_) => EndgameBoss::iter()
+                        .map(UniqueMonster::EndgameBoss)
+                        .collect(),
+                    UniqueMonster::DelveBoss(_) => {
+                        DelveBoss::iter().map(UniqueMonster::DelveBoss).collect()
+                    }
+                    UniqueMonster::BeastBoss(_) => {
+                        BeastBoss::iter().map(UniqueMonster::BeastBoss).collect()
+                    }
+                    UniqueMonster::HeistBoss(_) => {
+                        HeistBoss::iter().map(UniqueMonster::HeistBoss).collect()
+                    }
+                    UniqueMonster::BeyondBoss(_) => {
+                        BeyondBoss::iter().map(UniqueMonster::BeyondBoss).collect()
+                    }
+                    UniqueMonster::ExpeditionLogbookBoss(_) => ExpeditionLogbookBoss::iter()
+                        .map(UniqueMonster::ExpeditionLogbookBoss)
+                        .collect(),
+                    UniqueMonster::ShaperMiniBoss(_) => ShaperMiniBoss::iter()
+                        .map(UniqueMonster::ShaperMiniBoss)
+                        .collect(),
+                    UniqueMonster::BetrayalCatarina(_) => BetrayalCatarina::iter()
+                        .map(UniqueMonster::BetrayalCatarina)
+                        .collect(),
+                    UniqueMonster::OshabiBoss(_) => {
+                        OshabiBoss::iter().map(UniqueMonster::OshabiBoss).collect()
+                    }
+                    UniqueMonster::EldritchPerfectionMonster(_) => {
+                        EldritchPerfectionMonster::iter()
+                            .map(UniqueMonster::EldritchPerfectionMonster)
+                            .collect()
+                    }
+                    monster => vec![monster],
+                }
+            })
+            .collect()
+    }
+
     pub fn _types() -> impl Iterator<Item = String> {
         UniqueMonster::iter().map(|a| a._type().to_string())
     }
@@ -253,14 +265,6 @@ impl Identified for Incarnation {
     }
 }
 
-impl FromStr for Incarnation {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum BreachlordBossDomain {
     #[default]
@@ -283,14 +287,6 @@ impl Identified for BreachlordBossDomain {
     }
 }
 
-impl FromStr for BreachlordBossDomain {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum Architect {
     #[default]
@@ -311,14 +307,6 @@ impl Identified for Architect {
     }
 }
 
-impl FromStr for Architect {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum ShaperGuardianBoss {
     #[default]
@@ -339,14 +327,6 @@ impl Identified for ShaperGuardianBoss {
     }
 }
 
-impl FromStr for ShaperGuardianBoss {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum SyndicateMember {
     #[default]
@@ -391,21 +371,6 @@ impl Identified for SyndicateMember {
             SyndicateMember::RikerMaloney => "Riker Maloney",
         }
     }
-
-    fn aliases(&self) -> Vec<&str> {
-        match self {
-            SyndicateMember::Haku => vec!["Haku"],
-            _ => vec![],
-        }
-    }
-}
-
-impl FromStr for SyndicateMember {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
 }
 
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
@@ -428,21 +393,6 @@ impl Identified for Elderslayer {
             Elderslayer::Sirus => "Sirus, Awakener of Worlds",
         }
     }
-
-    fn aliases(&self) -> Vec<&str> {
-        match self {
-            Self::Baran => vec!["Baran, the Crusader"],
-            _ => vec![],
-        }
-    }
-}
-
-impl FromStr for Elderslayer {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
 }
 
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
@@ -465,14 +415,6 @@ impl Identified for ElderGuardianBoss {
     }
 }
 
-impl FromStr for ElderGuardianBoss {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum RogueExile {
     #[default]
@@ -491,14 +433,6 @@ impl Identified for RogueExile {
     }
 }
 
-impl FromStr for RogueExile {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum FemaleRogueExile {
     #[default]
@@ -537,14 +471,6 @@ impl Identified for FemaleRogueExile {
     }
 }
 
-impl FromStr for FemaleRogueExile {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum AbyssLichBoss {
     #[default]
@@ -561,14 +487,6 @@ impl Identified for AbyssLichBoss {
     }
 }
 
-impl FromStr for AbyssLichBoss {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum MapsOnly {
     #[default]
@@ -585,14 +503,6 @@ impl Identified for MapsOnly {
     }
 }
 
-impl FromStr for MapsOnly {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum HarbingerPortal {
     #[default]
@@ -611,14 +521,6 @@ impl Identified for HarbingerPortal {
     }
 }
 
-impl FromStr for HarbingerPortal {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum EndgameBoss {
     #[default]
@@ -643,21 +545,6 @@ impl Identified for EndgameBoss {
             EndgameBoss::UberAtziri => "Atziri, Queen of the Vaal (Uber)",
         }
     }
-
-    fn aliases(&self) -> Vec<&str> {
-        match self {
-            Self::UberAtziri => vec!["Uber Atziri"],
-            _ => vec![],
-        }
-    }
-}
-
-impl FromStr for EndgameBoss {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
 }
 
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
@@ -676,14 +563,6 @@ impl Identified for DelveBoss {
     }
 }
 
-impl FromStr for DelveBoss {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum BeastBoss {
     #[default]
@@ -704,14 +583,6 @@ impl Identified for BeastBoss {
     }
 }
 
-impl FromStr for BeastBoss {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum HeistBoss {
     #[default]
@@ -728,14 +599,6 @@ impl Identified for HeistBoss {
     }
 }
 
-impl FromStr for HeistBoss {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum BeyondBoss {
     #[default]
@@ -752,14 +615,6 @@ impl Identified for BeyondBoss {
     }
 }
 
-impl FromStr for BeyondBoss {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum ExpeditionLogbookBoss {
     #[default]
@@ -774,14 +629,6 @@ impl Identified for ExpeditionLogbookBoss {
     }
 }
 
-impl FromStr for ExpeditionLogbookBoss {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum BetrayalCatarina {
     #[default]
@@ -796,14 +643,6 @@ impl Identified for BetrayalCatarina {
     }
 }
 
-impl FromStr for BetrayalCatarina {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum OshabiBoss {
     #[default]
@@ -814,18 +653,6 @@ impl Identified for OshabiBoss {
     fn id(&self) -> &str {
         "Oshabi, Avatar of the Grove"
     }
-
-    fn aliases(&self) -> Vec<&str> {
-        vec!["Oshabi"]
-    }
-}
-
-impl FromStr for OshabiBoss {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
 }
 
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
@@ -866,14 +693,6 @@ impl Identified for EldritchPerfectionMonster {
     }
 }
 
-impl FromStr for EldritchPerfectionMonster {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum ShaperMiniBoss {
     #[default]
@@ -886,24 +705,17 @@ impl Identified for ShaperMiniBoss {
     }
 }
 
-impl FromStr for ShaperMiniBoss {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::dropsource::predefined::PredefinedSource;
 
     #[test]
     fn test_unique_monster() {
-        let veritania: UniqueMonster = "Veritania, The Redeemer".parse().unwrap();
+        let veritania: PredefinedSource = "Veritania, The Redeemer".parse().unwrap();
         assert_eq!(
             veritania,
-            UniqueMonster::Elderslayer(Elderslayer::Veritania)
+            PredefinedSource::UniqueMonster(UniqueMonster::Elderslayer(Elderslayer::Veritania))
         );
     }
 }
@@ -1,5 +1,4 @@
-use super::super::id::{parseid, Identified, UnknownVariant};
-use std::str::FromStr;
+use super::super::id::Identified;
 use strum_macros::EnumIter;
 
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
@@ -16,14 +15,6 @@ impl Identified for Vendor {
     }
 }
 
-impl FromStr for Vendor {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum Strongbox {
     #[default]
@@ -48,14 +39,6 @@ impl Identified for Strongbox {
     }
 }
 
-impl FromStr for Strongbox {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
-}
-
 #[derive(Debug, Clone, Default, EnumIter, PartialEq, Eq, Hash)]
 pub enum Chest {
     #[default]
@@ -100,34 +83,4 @@ impl Identified for Chest {
             Chest::MercilessOrUberOrEnrichedLabChests => "Izaro's Treasure, Labyrinth Trove, Curious Lockbox (Merciless/Uber/Enriched Labyrinth)",
         }
     }
-
-    fn aliases(&self) -> Vec<&str> {
-        match self {
-            Chest::AbyssalTrove => vec![],
-            Chest::Delve => vec!["Delve Chest"],
-            Chest::DelveGemChests => vec![],
-            Chest::VoltaxicSulphite => vec![],
-            Chest::DelveInteractablesBehindFracturedWall => vec![],
-            Chest::DelveCityLightJewellery => vec![],
-            Chest::MavenCrucible => vec![],
-            Chest::HeistMap => vec![],
-            Chest::BreachClaspedHand => vec![],
-            Chest::IzaroTreasure => vec![],
-            Chest::VaalVessel => vec![],
-            Chest::UberlabChests => vec!["Uber Labyrinth/Enriched Labyrinth (Izaro's Treasure, Labyrinth Trove, Curious Lockbox)"],
-            Chest::MercilessChests => vec!["Merciless Labyrinth"],
-            Chest::HiddenCoffer => vec![],
-            Chest::Darkshrine => vec![],
-            Chest::BootyChestMaoKun => vec![],
-            Chest::MercilessOrUberOrEnrichedLabChests => vec!["Merciless Labyrinth/Uber Labyrinth/Enriched Labyrinth (Izaro's Treasure, Labyrinth Trove, Curious Lockbox)"],
-        }
-    }
-}
-
-impl FromStr for Chest {
-    type Err = UnknownVariant<Self>;
-
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        parseid(s)
-    }
 }
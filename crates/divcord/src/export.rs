@@ -0,0 +1,552 @@
+//! Records, sources and [`PoeData`] as flat tables, for SQL and spreadsheet tools.
+//!
+//! Every table is a `Vec` of one row type. Field names of the row types are the CSV headers and
+//! SQL column names, see [`SCHEMA`]. Sources are referenced by [`source_key`].
+//!
+//! ```text
+//! records ─< record_sources >─ sources ─< card_sources >─ cards ─< card_weights
+//! ```
+//!
+//! SQLite output needs the `sqlite` feature.
+
+use crate::{
+    cards::VerificationStatus, dropsource::id::Identified, index::DivcordIndex, PoeData, Record,
+    Source,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt::Display, path::Path};
+
+/// Tables of the SQLite database. CSV files have the same names and columns.
+pub const SCHEMA: &str = r#"
+CREATE TABLE records (
+    id INTEGER PRIMARY KEY,
+    card TEXT NOT NULL,
+    greynote TEXT NOT NULL,
+    tag_hypothesis TEXT,
+    confidence TEXT NOT NULL,
+    remaining_work TEXT NOT NULL,
+    notes TEXT
+);
+CREATE TABLE sources (
+    key TEXT PRIMARY KEY,
+    type TEXT NOT NULL,
+    id TEXT NOT NULL,
+    kind TEXT NOT NULL
+);
+CREATE TABLE record_sources (
+    record_id INTEGER NOT NULL REFERENCES records(id),
+    source_key TEXT NOT NULL REFERENCES sources(key),
+    status TEXT NOT NULL
+);
+CREATE TABLE card_sources (
+    card TEXT NOT NULL,
+    source_key TEXT NOT NULL REFERENCES sources(key),
+    status TEXT NOT NULL,
+    transitive_key TEXT REFERENCES sources(key)
+);
+CREATE TABLE cards (
+    name TEXT PRIMARY KEY,
+    slug TEXT NOT NULL,
+    min_level INTEGER,
+    max_level INTEGER,
+    price REAL,
+    league TEXT,
+    disabled INTEGER NOT NULL
+);
+CREATE TABLE card_weights (
+    card TEXT NOT NULL REFERENCES cards(name),
+    league TEXT NOT NULL,
+    weight REAL NOT NULL
+);
+CREATE TABLE maps (
+    name TEXT PRIMARY KEY,
+    slug TEXT NOT NULL,
+    tier INTEGER NOT NULL,
+    available INTEGER NOT NULL,
+    is_unique INTEGER NOT NULL
+);
+CREATE TABLE acts (
+    id TEXT PRIMARY KEY,
+    name TEXT NOT NULL,
+    act INTEGER NOT NULL,
+    area_level INTEGER NOT NULL,
+    is_town INTEGER NOT NULL
+);
+CREATE TABLE bosses (
+    name TEXT NOT NULL,
+    type TEXT NOT NULL,
+    area TEXT NOT NULL
+);
+CREATE INDEX record_sources_source ON record_sources(source_key);
+CREATE INDEX card_sources_card ON card_sources(card);
+CREATE INDEX card_sources_source ON card_sources(source_key);
+"#;
+
+/// Stable key of a source, `type-slug/id-slug`, e.g. `map/spider-forest-map`.
+pub fn source_key(source: &Source) -> String {
+    format!("{}/{}", source.type_slug(), source.slug())
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordRow {
+    pub id: usize,
+    pub card: String,
+    pub greynote: String,
+    pub tag_hypothesis: Option<String>,
+    pub confidence: String,
+    pub remaining_work: String,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SourceRow {
+    pub key: String,
+    #[serde(rename = "type")]
+    pub source_type: String,
+    pub id: String,
+    /// `category` for sources like `Delirium` that are their own type, `source` otherwise.
+    pub kind: String,
+}
+
+/// Source written in a record's sources or verify sources column.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecordSourceRow {
+    pub record_id: usize,
+    pub source_key: String,
+    pub status: VerificationStatus,
+}
+
+/// Card of a source, directly or through a boss of the map or act.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CardSourceRow {
+    pub card: String,
+    pub source_key: String,
+    pub status: VerificationStatus,
+    /// Boss that drops the card, for transitive sources.
+    pub transitive_key: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CardRow {
+    pub name: String,
+    pub slug: String,
+    pub min_level: Option<u32>,
+    pub max_level: Option<u32>,
+    pub price: Option<f32>,
+    /// Release version of the card's league.
+    pub league: Option<String>,
+    pub disabled: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightRow {
+    pub card: String,
+    pub league: String,
+    pub weight: f32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MapRow {
+    pub name: String,
+    pub slug: String,
+    pub tier: u32,
+    pub available: bool,
+    pub is_unique: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActRow {
+    pub id: String,
+    pub name: String,
+    pub act: u8,
+    pub area_level: u8,
+    pub is_town: bool,
+}
+
+/// Boss of a map (`Map Boss`, area is the map name) or act area (`Act Boss`, area is the act id).
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BossRow {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub boss_type: String,
+    pub area: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Export {
+    pub records: Vec<RecordRow>,
+    pub sources: Vec<SourceRow>,
+    pub record_sources: Vec<RecordSourceRow>,
+    pub card_sources: Vec<CardSourceRow>,
+    pub cards: Vec<CardRow>,
+    pub card_weights: Vec<WeightRow>,
+    pub maps: Vec<MapRow>,
+    pub acts: Vec<ActRow>,
+    pub bosses: Vec<BossRow>,
+}
+
+#[derive(Debug)]
+pub enum ExportError {
+    Io(std::io::Error),
+    Csv(csv::Error),
+    #[cfg(feature = "sqlite")]
+    Sqlite(rusqlite::Error),
+}
+
+impl Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExportError::Io(err) => write!(f, "Export io error. {err}"),
+            ExportError::Csv(err) => write!(f, "Export csv error. {err}"),
+            #[cfg(feature = "sqlite")]
+            ExportError::Sqlite(err) => write!(f, "Export sqlite error. {err}"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<std::io::Error> for ExportError {
+    fn from(value: std::io::Error) -> Self {
+        ExportError::Io(value)
+    }
+}
+
+impl From<csv::Error> for ExportError {
+    fn from(value: csv::Error) -> Self {
+        ExportError::Csv(value)
+    }
+}
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for ExportError {
+    fn from(value: rusqlite::Error) -> Self {
+        ExportError::Sqlite(value)
+    }
+}
+
+/// Serialized name of a unit variant, like `Monster-specific` for a greynote.
+fn variant_name<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => String::new(),
+    }
+}
+
+impl Export {
+    pub fn new(records: &[Record], poe_data: &PoeData) -> Self {
+        let mut sources: BTreeMap<String, SourceRow> = BTreeMap::new();
+        let mut add_source = |source: &Source| -> String {
+            let key = source_key(source);
+            sources.entry(key.clone()).or_insert_with(|| SourceRow {
+                key: key.clone(),
+                source_type: source._type().to_owned(),
+                id: source.id().to_owned(),
+                kind: match source._type() == source.id() {
+                    true => "category".to_owned(),
+                    false => "source".to_owned(),
+                },
+            });
+            key
+        };
+
+        let mut export = Export::default();
+        for record in records {
+            export.records.push(RecordRow {
+                id: record.id,
+                card: record.card.clone(),
+                greynote: variant_name(&record.greynote),
+                tag_hypothesis: record.tag_hypothesis.clone(),
+                confidence: variant_name(&record.confidence),
+                remaining_work: variant_name(&record.remaining_work),
+                notes: record.notes.clone(),
+            });
+            let statuses = record
+                .sources
+                .iter()
+                .map(|source| (source, VerificationStatus::Done))
+                .chain(
+                    record
+                        .verify_sources
+                        .iter()
+                        .map(|source| (source, VerificationStatus::Verify)),
+                );
+            for (source, status) in statuses {
+                export.record_sources.push(RecordSourceRow {
+                    record_id: record.id,
+                    source_key: add_source(source),
+                    status,
+                });
+            }
+        }
+        export.records.sort_by_key(|row| row.id);
+
+        let index = DivcordIndex::new(records, poe_data);
+        for card in index.cards() {
+            for source_of_card in index.sources_by_card(card) {
+                export.card_sources.push(CardSourceRow {
+                    card: card.to_owned(),
+                    source_key: add_source(&source_of_card.source),
+                    status: source_of_card.status,
+                    transitive_key: source_of_card.transitive.as_ref().map(&mut add_source),
+                });
+            }
+        }
+        export.card_sources.sort_by(|a, b| {
+            (&a.card, &a.source_key, &a.transitive_key)
+                .cmp(&(&b.card, &b.source_key, &b.transitive_key))
+                .then_with(|| variant_name(&a.status).cmp(&variant_name(&b.status)))
+        });
+        export.sources = sources.into_values().collect();
+
+        for card in poe_data.cards.0.values() {
+            export.cards.push(CardRow {
+                name: card.name.clone(),
+                slug: card.slug.clone(),
+                min_level: card.min_level,
+                max_level: card.max_level,
+                price: card.price,
+                league: card
+                    .league
+                    .as_ref()
+                    .map(|league| league.version.to_string()),
+                disabled: card.disabled,
+            });
+            export
+                .card_weights
+                .extend(card.weights.iter().map(|(league, &weight)| WeightRow {
+                    card: card.name.clone(),
+                    league: league.clone(),
+                    weight,
+                }));
+        }
+        export.cards.sort_by(|a, b| a.name.cmp(&b.name));
+        export
+            .card_weights
+            .sort_by(|a, b| (&a.card, &a.league).cmp(&(&b.card, &b.league)));
+
+        export.maps = poe_data
+            .maps
+            .iter()
+            .map(|map| MapRow {
+                name: map.name.clone(),
+                slug: map.slug.clone(),
+                tier: map.tier,
+                available: map.available,
+                is_unique: map.unique,
+            })
+            .collect();
+        export.maps.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for act in &poe_data.acts {
+            export.acts.push(ActRow {
+                id: act.id.as_str().to_owned(),
+                name: act.name.clone(),
+                act: act.act,
+                area_level: act.area_level,
+                is_town: act.is_town,
+            });
+            export
+                .bosses
+                .extend(act.bossfights.iter().map(|boss| BossRow {
+                    name: boss.name.clone(),
+                    boss_type: "Act Boss".to_owned(),
+                    area: act.id.as_str().to_owned(),
+                }));
+        }
+        for boss in &poe_data.mapbosses {
+            export.bosses.extend(boss.maps.iter().map(|map| BossRow {
+                name: boss.name.clone(),
+                boss_type: "Map Boss".to_owned(),
+                area: map.clone(),
+            }));
+        }
+
+        export
+    }
+
+    /// Writes `<table>.csv` for every table into the directory, creating it if needed.
+    pub fn write_csv(&self, dir: impl AsRef<Path>) -> Result<(), ExportError> {
+        let dir = dir.as_ref();
+        std::fs::create_dir_all(dir)?;
+        write_csv_table(dir, "records", &self.records)?;
+        write_csv_table(dir, "sources", &self.sources)?;
+        write_csv_table(dir, "record_sources", &self.record_sources)?;
+        write_csv_table(dir, "card_sources", &self.card_sources)?;
+        write_csv_table(dir, "cards", &self.cards)?;
+        write_csv_table(dir, "card_weights", &self.card_weights)?;
+        write_csv_table(dir, "maps", &self.maps)?;
+        write_csv_table(dir, "acts", &self.acts)?;
+        write_csv_table(dir, "bosses", &self.bosses)?;
+        Ok(())
+    }
+
+    /// Writes all tables into a new SQLite database. An existing file is replaced.
+    #[cfg(feature = "sqlite")]
+    pub fn write_sqlite(&self, path: impl AsRef<Path>) -> Result<(), ExportError> {
+        let path = path.as_ref();
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        let mut connection = rusqlite::Connection::open(path)?;
+        self.insert_sqlite(&mut connection)
+    }
+
+    /// Creates the [`SCHEMA`] tables in the connection and inserts all rows.
+    #[cfg(feature = "sqlite")]
+    pub fn insert_sqlite(&self, connection: &mut rusqlite::Connection) -> Result<(), ExportError> {
+        use rusqlite::params;
+
+        let tx = connection.transaction()?;
+        tx.execute_batch(SCHEMA)?;
+        {
+            let mut insert =
+                tx.prepare("INSERT INTO records VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)")?;
+            for r in &self.records {
+                insert.execute(params![
+                    r.id,
+                    r.card,
+                    r.greynote,
+                    r.tag_hypothesis,
+                    r.confidence,
+                    r.remaining_work,
+                    r.notes
+                ])?;
+            }
+            let mut insert = tx.prepare("INSERT INTO sources VALUES (?1, ?2, ?3, ?4)")?;
+            for s in &self.sources {
+                insert.execute(params![s.key, s.source_type, s.id, s.kind])?;
+            }
+            let mut insert = tx.prepare("INSERT INTO record_sources VALUES (?1, ?2, ?3)")?;
+            for e in &self.record_sources {
+                insert.execute(params![e.record_id, e.source_key, variant_name(&e.status)])?;
+            }
+            let mut insert = tx.prepare("INSERT INTO card_sources VALUES (?1, ?2, ?3, ?4)")?;
+            for e in &self.card_sources {
+                insert.execute(params![
+                    e.card,
+                    e.source_key,
+                    variant_name(&e.status),
+                    e.transitive_key
+                ])?;
+            }
+            let mut insert = tx.prepare("INSERT INTO cards VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)")?;
+            for c in &self.cards {
+                insert.execute(params![
+                    c.name,
+                    c.slug,
+                    c.min_level,
+                    c.max_level,
+                    c.price,
+                    c.league,
+                    c.disabled
+                ])?;
+            }
+            let mut insert = tx.prepare("INSERT INTO card_weights VALUES (?1, ?2, ?3)")?;
+            for w in &self.card_weights {
+                insert.execute(params![w.card, w.league, w.weight])?;
+            }
+            let mut insert = tx.prepare("INSERT INTO maps VALUES (?1, ?2, ?3, ?4, ?5)")?;
+            for m in &self.maps {
+                insert.execute(params![m.name, m.slug, m.tier, m.available, m.is_unique])?;
+            }
+            let mut insert = tx.prepare("INSERT INTO acts VALUES (?1, ?2, ?3, ?4, ?5)")?;
+            for a in &self.acts {
+                insert.execute(params![a.id, a.name, a.act, a.area_level, a.is_town])?;
+            }
+            let mut insert = tx.prepare("INSERT INTO bosses VALUES (?1, ?2, ?3)")?;
+            for b in &self.bosses {
+                insert.execute(params![b.name, b.boss_type, b.area])?;
+            }
+        }
+        tx.commit()?;
+        Ok(())
+    }
+}
+
+fn write_csv_table<T: Serialize>(dir: &Path, name: &str, rows: &[T]) -> Result<(), ExportError> {
+    let mut writer = csv::Writer::from_path(dir.join(format!("{name}.csv")))?;
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Spreadsheet;
+
+    fn export() -> Export {
+        let spreadsheet: Spreadsheet =
+            serde_json::from_str(&std::fs::read_to_string("benches/spreadsheet.json").unwrap())
+                .unwrap();
+        let poe_data: PoeData =
+            serde_json::from_str(&std::fs::read_to_string("benches/poeData.json").unwrap())
+                .unwrap();
+        let records = crate::records(&spreadsheet, &poe_data).unwrap();
+        Export::new(&records, &poe_data)
+    }
+
+    #[test]
+    fn exports_tables() {
+        let export = export();
+        assert!(!export.records.is_empty());
+        let keys: std::collections::HashSet<&str> =
+            export.sources.iter().map(|s| s.key.as_str()).collect();
+        assert!(export
+            .record_sources
+            .iter()
+            .all(|e| keys.contains(e.source_key.as_str())));
+        assert!(export
+            .card_sources
+            .iter()
+            .all(|e| keys.contains(e.source_key.as_str())
+                && e.transitive_key
+                    .as_deref()
+                    .is_none_or(|key| keys.contains(key))));
+        assert!(export
+            .card_sources
+            .iter()
+            .any(|e| e.transitive_key.is_some()));
+
+        let dir = std::env::temp_dir().join(format!("divcord-export-{}", std::process::id()));
+        export.write_csv(&dir).unwrap();
+        let mut reader = csv::Reader::from_path(dir.join("card_sources.csv")).unwrap();
+        assert_eq!(
+            reader.headers().unwrap(),
+            vec!["card", "source_key", "status", "transitive_key"]
+        );
+        let rows: Vec<CardSourceRow> = reader.deserialize().map(Result::unwrap).collect();
+        assert_eq!(rows, export.card_sources);
+
+        #[cfg(feature = "sqlite")]
+        {
+            let path = dir.join("divcord.sqlite");
+            export.write_sqlite(&path).unwrap();
+            let connection = rusqlite::Connection::open(&path).unwrap();
+            let count: usize = connection
+                .query_row("SELECT COUNT(*) FROM card_sources", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(count, export.card_sources.len());
+            let transitive: usize = connection
+                .query_row(
+                    "SELECT COUNT(*) FROM card_sources cs JOIN sources s ON s.key = cs.transitive_key",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap();
+            assert_eq!(
+                transitive,
+                export
+                    .card_sources
+                    .iter()
+                    .filter(|e| e.transitive_key.is_some())
+                    .count()
+            );
+        }
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,389 @@
+//! "Where to farm": drop sources ranked by the value of the target cards they drop.
+//!
+//! A source drops one card of its pool at a time, picked by [card weights](poe_data::cards::Card).
+//! The share of a card is its weight over the weight of the whole pool, and the value of the
+//! source is the sum of `share * price` over the target cards. Sources are compared per card
+//! drop, not per hour: a map with a small pool ranks above a map with a big one.
+//!
+//! Pools only hold cards that divcord lists for the source. Global drops are not in them, so
+//! values are upper bounds and compare sources rather than predict income.
+//!
+//! ```text
+//! value(source) = Σ target weight × price / Σ pool weight
+//! ```
+
+use crate::{
+    cards::{CardBySource, VerificationStatus},
+    dropsource::predefined::PredefinedSource,
+    index::DivcordIndex,
+    PoeData, Record, Source,
+};
+pub use divi::prices::Prices;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt::Display};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct FarmQuery {
+    /// Target cards. Empty with no `min_price` targets every card with a price.
+    pub cards: Vec<String>,
+    /// Cards worth at least this much are targets too.
+    pub min_price: Option<f32>,
+    /// Key of [`Card::weights`](poe_data::cards::Card::weights), the latest league by default.
+    pub league: Option<String>,
+    /// Count cards that are yet to be verified for the source.
+    pub include_verify: bool,
+    pub limit: Option<usize>,
+}
+
+/// Card of the pool of a source that counts toward its value.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CardValue {
+    pub card: String,
+    pub status: VerificationStatus,
+    /// Boss of the map or act that drops the card.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub transitive: Option<Source>,
+    pub weight: f32,
+    pub price: f32,
+    /// Chance for a card from this source to be this one.
+    pub share: f32,
+    /// `share * price`.
+    pub value: f32,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct SourceValue {
+    pub source: Source,
+    /// Chaos per card dropped by the source, from target cards.
+    pub value: f32,
+    /// Chance for a card from this source to be a target.
+    pub target_chance: f32,
+    /// Cards with known weights the source can drop.
+    pub pool_size: usize,
+    /// Target cards, most valuable first.
+    pub cards: Vec<CardValue>,
+}
+
+impl FarmQuery {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn card(mut self, card: impl Into<String>) -> Self {
+        self.cards.push(card.into());
+        self
+    }
+
+    pub fn min_price(mut self, min_price: f32) -> Self {
+        self.min_price = Some(min_price);
+        self
+    }
+
+    pub fn league(mut self, league: impl Into<String>) -> Self {
+        self.league = Some(league.into());
+        self
+    }
+
+    pub fn include_verify(mut self) -> Self {
+        self.include_verify = true;
+        self
+    }
+
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    fn is_target(&self, card: &str, price: f32) -> bool {
+        let untargeted = self.cards.is_empty() && self.min_price.is_none();
+        untargeted
+            || self.cards.iter().any(|target| target == card)
+            || self.min_price.is_some_and(|min| price >= min)
+    }
+
+    /// Sources with at least one target card, most valuable first. Cards missing from `prices`
+    /// fall back to the prices of [`PoeData`] cards.
+    pub fn run(
+        &self,
+        index: &DivcordIndex,
+        poe_data: &PoeData,
+        prices: Option<&Prices>,
+    ) -> Vec<SourceValue> {
+        let Some(league) = self.league.clone().or_else(|| latest_league(poe_data)) else {
+            return vec![];
+        };
+        let mut card_prices: HashMap<&str, f32> = poe_data
+            .cards
+            .0
+            .values()
+            .filter_map(|card| Some((card.name.as_str(), card.price?)))
+            .collect();
+        if let Some(prices) = prices {
+            card_prices.extend(
+                prices
+                    .0
+                    .iter()
+                    .filter_map(|p| Some((p.name.as_str(), p.price?))),
+            );
+        }
+        let weight = |card: &str| -> Option<f32> {
+            let card = poe_data.cards.0.get(card)?;
+            let weight = *card.weights.get(&league)?;
+            (!card.disabled && weight > 0.0).then_some(weight)
+        };
+
+        let mut values: Vec<SourceValue> = vec![];
+        for source in index.sources() {
+            if source == &Source::Predefined(PredefinedSource::Disabled) {
+                continue;
+            }
+
+            // A card can be in the pool several times: directly and through a boss, done and to
+            // verify. Count it once, with its best entry.
+            let mut pool: HashMap<&str, (&CardBySource, f32)> = HashMap::new();
+            for card in index.cards_by_source(source) {
+                if !self.include_verify && card.status() == VerificationStatus::Verify {
+                    continue;
+                }
+                let Some(weight) = weight(card.card()) else {
+                    continue;
+                };
+                pool.entry(card.card().as_str())
+                    .and_modify(|entry| {
+                        if rank(card) > rank(entry.0) {
+                            entry.0 = card;
+                        }
+                    })
+                    .or_insert((card, weight));
+            }
+            let pool_size = pool.len();
+            let pool_weight: f32 = pool.values().map(|(_, weight)| weight).sum();
+
+            let mut cards: Vec<CardValue> = pool
+                .into_iter()
+                .filter_map(|(name, (card, weight))| {
+                    let price = *card_prices.get(name)?;
+                    if !self.is_target(name, price) {
+                        return None;
+                    }
+                    let share = weight / pool_weight;
+                    Some(CardValue {
+                        card: name.to_owned(),
+                        status: card.status(),
+                        transitive: match card {
+                            CardBySource::Transitive(t) => Some(t.transitive.clone()),
+                            CardBySource::Direct(_) => None,
+                        },
+                        weight,
+                        price,
+                        share,
+                        value: share * price,
+                    })
+                })
+                .collect();
+            if cards.is_empty() {
+                continue;
+            }
+            cards.sort_by(|a, b| b.value.total_cmp(&a.value).then(a.card.cmp(&b.card)));
+
+            values.push(SourceValue {
+                source: source.clone(),
+                value: cards.iter().map(|card| card.value).sum(),
+                target_chance: cards.iter().map(|card| card.share).sum(),
+                pool_size,
+                cards,
+            });
+        }
+
+        values.sort_by(|a, b| {
+            b.value
+                .total_cmp(&a.value)
+                .then_with(|| a.source.to_string().cmp(&b.source.to_string()))
+        });
+        if let Some(limit) = self.limit {
+            values.truncate(limit);
+        }
+        values
+    }
+}
+
+/// Done before to verify, then direct before transitive.
+fn rank(card: &CardBySource) -> (bool, bool) {
+    (card.status() == VerificationStatus::Done, card.is_direct())
+}
+
+/// Newest league of card weights, like `3.26`.
+pub fn latest_league(poe_data: &PoeData) -> Option<String> {
+    let version = |league: &str| -> Vec<u32> {
+        league
+            .split('.')
+            .map(|part| part.parse().unwrap_or(0))
+            .collect()
+    };
+    poe_data
+        .cards
+        .0
+        .values()
+        .flat_map(|card| card.weights.keys())
+        .max_by_key(|league| version(league))
+        .cloned()
+}
+
+/// Builds the index of records and runs the query, see [`FarmQuery::run`].
+pub fn where_to_farm(
+    query: &FarmQuery,
+    records: &[Record],
+    poe_data: &PoeData,
+    prices: Option<&Prices>,
+) -> Vec<SourceValue> {
+    query.run(&DivcordIndex::new(records, poe_data), poe_data, prices)
+}
+
+impl Display for SourceValue {
+    /// `Spider Forest Map: 12.4c per card, 3.1% targets. The Doctor 10.2c (1.1%), ...`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {:.2}c per card, {:.2}% targets.",
+            self.source,
+            self.value,
+            self.target_chance * 100.0
+        )?;
+        for (i, card) in self.cards.iter().enumerate() {
+            let separator = if i == 0 { " " } else { ", " };
+            write!(
+                f,
+                "{separator}{} {:.2}c ({:.3}%)",
+                card.card,
+                card.value,
+                card.share * 100.0
+            )?;
+            if let Some(boss) = &card.transitive {
+                write!(f, " via {boss}")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spreadsheet::record::record;
+    use divi::prices::DivinationCardPrice;
+    use poe_data::cards::{Card, CardsData};
+
+    fn card(name: &str, weight: f32, price: f32) -> (String, Card) {
+        (
+            name.to_owned(),
+            Card {
+                slug: slug::slugify(name),
+                name: name.to_owned(),
+                min_level: None,
+                max_level: None,
+                weights: HashMap::from([
+                    ("3.25".to_owned(), weight * 2.0),
+                    ("3.26".to_owned(), weight),
+                ]),
+                price: Some(price),
+                league: None,
+                disabled: false,
+            },
+        )
+    }
+
+    #[test]
+    fn ranks_sources_by_value() {
+        let poe_data = PoeData {
+            acts: vec![],
+            cards: CardsData(HashMap::from([
+                card("The Doctor", 10.0, 1000.0),
+                card("Rain of Chaos", 990.0, 1.0),
+                card("The Fiend", 10.0, 1000.0),
+                card("Her Mask", 2000.0, 0.5),
+            ])),
+            maps: vec![],
+            mapbosses: vec![],
+        };
+        let burial = Source::Map("Burial Chambers Map".to_owned());
+        let crypt = Source::Map("Crypt Map".to_owned());
+        let records = vec![
+            record(1, "The Doctor", vec![burial.clone()]),
+            record(2, "Rain of Chaos", vec![burial.clone(), crypt.clone()]),
+            record(3, "The Fiend", vec![crypt.clone()]),
+            record(4, "Her Mask", vec![crypt.clone()]),
+        ];
+
+        let results = where_to_farm(
+            &FarmQuery::new().min_price(100.0),
+            &records,
+            &poe_data,
+            None,
+        );
+        assert_eq!(results.len(), 2);
+        // 10 / (10 + 990) * 1000 against 10 / (990 + 10 + 2000) * 1000.
+        assert_eq!(results[0].source, burial);
+        assert!((results[0].value - 10.0).abs() < 1e-4);
+        assert!((results[0].target_chance - 0.01).abs() < 1e-6);
+        assert_eq!(results[0].pool_size, 2);
+        assert_eq!(results[0].cards[0].card, "The Doctor");
+        assert_eq!(results[1].source, crypt);
+        assert_eq!(
+            results[1].to_string(),
+            "Crypt Map: 3.33c per card, 0.33% targets. The Fiend 3.33c (0.333%)"
+        );
+
+        let prices = Prices(vec![DivinationCardPrice {
+            name: "Her Mask".to_owned(),
+            price: Some(100.0),
+        }]);
+        let results = where_to_farm(
+            &FarmQuery::new().card("Her Mask").league("3.25"),
+            &records,
+            &poe_data,
+            Some(&prices),
+        );
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].source, crypt);
+        assert!((results[0].cards[0].share - 4000.0 / 6000.0).abs() < 1e-6);
+
+        // The Fiend is not in `prices` and keeps its PoeData price.
+        let results = FarmQuery::new().card("The Fiend").card("Her Mask").run(
+            &DivcordIndex::new(&records, &poe_data),
+            &poe_data,
+            Some(&prices),
+        );
+        let values: Vec<(&str, f32)> = results[0]
+            .cards
+            .iter()
+            .map(|card| (card.card.as_str(), card.price))
+            .collect();
+        assert_eq!(values, [("Her Mask", 100.0), ("The Fiend", 1000.0)]);
+    }
+
+    #[test]
+    fn prefers_done_entries() {
+        let poe_data = PoeData {
+            acts: vec![],
+            cards: CardsData(HashMap::from([card("The Doctor", 10.0, 1000.0)])),
+            maps: vec![],
+            mapbosses: vec![],
+        };
+        let burial = Source::Map("Burial Chambers Map".to_owned());
+        let mut to_verify = record(1, "The Doctor", vec![]);
+        to_verify.verify_sources = vec![burial.clone()];
+        let records = vec![to_verify, record(2, "The Doctor", vec![burial.clone()])];
+
+        let results = where_to_farm(
+            &FarmQuery::new().include_verify(),
+            &records,
+            &poe_data,
+            None,
+        );
+        assert_eq!(results[0].pool_size, 1);
+        assert_eq!(results[0].cards[0].status, VerificationStatus::Done);
+    }
+}
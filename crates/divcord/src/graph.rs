@@ -0,0 +1,500 @@
+//! Relationships between drop sources, as a graph with typed edges.
+//!
+//! - [`EdgeKind::BossOf`]: boss → map or act area it is fought in.
+//! - [`EdgeKind::Contains`]: group source → member, e.g. `All Rogue Exiles` → `Ash Lessard`.
+//! - [`EdgeKind::VariantOf`]: special case → source, e.g. Uul-Netol in Breachstones → Uul-Netol.
+//! - [`EdgeKind::CategoryMember`]: source → its type, and type → parent type.
+//!
+//! Areas also drop cards of their bosses and members drop cards of their groups,
+//! [`SourceGraph::transitive_sources`] follows [`EdgeKind::BossOf`] and [`EdgeKind::Contains`]
+//! edges backwards.
+
+use crate::{
+    dropsource::{
+        id::Identified,
+        predefined::{
+            definitions::definitions,
+            monster::{BreachlordBossDomain, UniqueMonster},
+            PredefinedSource,
+        },
+        Source,
+    },
+    export::source_key,
+    PoeData,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt::Write,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum EdgeKind {
+    Contains,
+    BossOf,
+    VariantOf,
+    CategoryMember,
+}
+
+impl EdgeKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            EdgeKind::Contains => "contains",
+            EdgeKind::BossOf => "boss-of",
+            EdgeKind::VariantOf => "variant-of",
+            EdgeKind::CategoryMember => "category-member",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Node {
+    Source(Source),
+    /// Source type, like `Map Boss`, or group of types, like `Unique Monster`.
+    Category(String),
+}
+
+impl Node {
+    /// Unique key, [`source_key`] for sources and `category/<slug>` for categories.
+    pub fn key(&self) -> String {
+        match self {
+            Node::Source(source) => source_key(source),
+            Node::Category(name) => format!("category/{}", slug::slugify(name)),
+        }
+    }
+
+    pub fn label(&self) -> &str {
+        match self {
+            Node::Source(source) => source.id(),
+            Node::Category(name) => name,
+        }
+    }
+
+    pub fn source(&self) -> Option<&Source> {
+        match self {
+            Node::Source(source) => Some(source),
+            Node::Category(_) => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Edge {
+    pub from: Node,
+    pub to: Node,
+    pub kind: EdgeKind,
+}
+
+/// Bosses fought in the map or act area.
+pub fn bosses_of(area: &Source, poe_data: &PoeData) -> Vec<Source> {
+    match area {
+        Source::Act(act) => poe_data
+            .act_area_id(act)
+            .map(|act_area| {
+                act_area
+                    .bossfights
+                    .iter()
+                    .map(|b| Source::from(b.to_owned()))
+                    .collect()
+            })
+            .unwrap_or_default(),
+        Source::Map(map) => poe_data
+            .bosses_of_map(map)
+            .into_iter()
+            .map(|b| Source::from(b.to_owned()))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// Members of a group source, sources of the types listed in its definition's `members`.
+pub fn members_of(source: &PredefinedSource) -> Vec<PredefinedSource> {
+    let definitions = definitions();
+    let Some(group) = definitions.find(source.id()) else {
+        return vec![];
+    };
+    definitions
+        .iter()
+        .filter(|definition| group.members.contains(&definition.source_type))
+        .map(PredefinedSource::from)
+        .collect()
+}
+
+/// Source that this one is a special case of.
+pub fn variant_of(source: &PredefinedSource, poe_data: &PoeData) -> Option<Source> {
+    match source {
+        PredefinedSource::UniqueMonster(UniqueMonster::UulNetolInBreachstones) => {
+            Some(Source::Predefined(PredefinedSource::UniqueMonster(
+                UniqueMonster::BreachlordBossDomain(BreachlordBossDomain::UulNetol),
+            )))
+        }
+        PredefinedSource::MaelstromOfChaosWithBarrelSextant => poe_data
+            .maps
+            .iter()
+            .find(|map| map.name == "Maelström of Chaos")
+            .map(|map| Source::from(map.clone())),
+        _ => None,
+    }
+}
+
+/// Sources of [`PoeData`] and predefined sources with all their relationships.
+#[derive(Debug, Clone, Default)]
+pub struct SourceGraph {
+    nodes: Vec<Node>,
+    edges: Vec<Edge>,
+    node_set: HashSet<Node>,
+    edge_set: HashSet<Edge>,
+    /// Positions in `edges` by their `from` node.
+    outgoing: HashMap<Node, Vec<usize>>,
+    /// Positions in `edges` by their `to` node.
+    incoming: HashMap<Node, Vec<usize>>,
+}
+
+impl SourceGraph {
+    pub fn new(poe_data: &PoeData) -> Self {
+        let mut graph = SourceGraph::default();
+        let definitions = definitions();
+
+        let areas = poe_data
+            .maps
+            .iter()
+            .map(|map| Source::from(map.clone()))
+            .chain(
+                poe_data
+                    .acts
+                    .iter()
+                    .filter(|act| !act.is_town)
+                    .map(|act| Source::from(act.clone())),
+            );
+        for area in areas {
+            graph.add_source(&area);
+            for boss in bosses_of(&area, poe_data) {
+                graph.add_source(&boss);
+                graph.add_edge(
+                    Node::Source(boss),
+                    Node::Source(area.clone()),
+                    EdgeKind::BossOf,
+                );
+            }
+        }
+        for boss in &poe_data.mapbosses {
+            let boss_source = Source::from(boss.clone());
+            graph.add_source(&boss_source);
+            // Maps missing from `poe_data.maps` still get their bosses.
+            let unknown_maps = boss.maps.iter().filter(|name| {
+                !poe_data
+                    .maps
+                    .iter()
+                    .any(|map| map.name.to_lowercase() == name.to_lowercase())
+            });
+            for name in unknown_maps {
+                let map = Source::Map(name.clone());
+                graph.add_source(&map);
+                graph.add_edge(
+                    Node::Source(boss_source.clone()),
+                    Node::Source(map),
+                    EdgeKind::BossOf,
+                );
+            }
+        }
+
+        let predefined = PredefinedSource::all().into_iter().chain(
+            definitions
+                .iter()
+                .map(PredefinedSource::from)
+                .filter(|source| matches!(source, PredefinedSource::Defined { .. })),
+        );
+        for source in predefined {
+            let node = Source::Predefined(source.clone());
+            graph.add_source(&node);
+            for member in members_of(&source) {
+                let member = Source::Predefined(member);
+                graph.add_source(&member);
+                graph.add_edge(
+                    Node::Source(node.clone()),
+                    Node::Source(member),
+                    EdgeKind::Contains,
+                );
+            }
+            if let Some(base) = variant_of(&source, poe_data) {
+                graph.add_source(&base);
+                graph.add_edge(
+                    Node::Source(node.clone()),
+                    Node::Source(base),
+                    EdgeKind::VariantOf,
+                );
+            }
+        }
+
+        for definition in definitions.iter() {
+            if let Some(parent) = definition
+                .parent
+                .as_ref()
+                .filter(|parent| **parent != definition.source_type)
+            {
+                graph.add_edge(
+                    Node::Category(definition.source_type.clone()),
+                    Node::Category(parent.clone()),
+                    EdgeKind::CategoryMember,
+                );
+            }
+        }
+
+        graph
+    }
+
+    /// Adds the source with an edge to its type.
+    fn add_source(&mut self, source: &Source) {
+        let node = Node::Source(source.clone());
+        if self.node_set.contains(&node) {
+            return;
+        }
+        self.add_node(node.clone());
+        // Categories like `Delirium` are their own type.
+        if source._type() != source.id() {
+            self.add_edge(
+                node,
+                Node::Category(source._type().to_owned()),
+                EdgeKind::CategoryMember,
+            );
+        }
+    }
+
+    fn add_node(&mut self, node: Node) {
+        if self.node_set.insert(node.clone()) {
+            self.nodes.push(node);
+        }
+    }
+
+    fn add_edge(&mut self, from: Node, to: Node, kind: EdgeKind) {
+        self.add_node(from.clone());
+        self.add_node(to.clone());
+        let edge = Edge { from, to, kind };
+        if self.edge_set.insert(edge.clone()) {
+            let i = self.edges.len();
+            self.outgoing.entry(edge.from.clone()).or_default().push(i);
+            self.incoming.entry(edge.to.clone()).or_default().push(i);
+            self.edges.push(edge);
+        }
+    }
+
+    pub fn nodes(&self) -> &[Node] {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &[Edge] {
+        &self.edges
+    }
+
+    pub fn edges_from(&self, node: &Node) -> impl Iterator<Item = &Edge> {
+        self.outgoing
+            .get(node)
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.edges[i])
+    }
+
+    pub fn edges_to(&self, node: &Node) -> impl Iterator<Item = &Edge> {
+        self.incoming
+            .get(node)
+            .into_iter()
+            .flatten()
+            .map(|&i| &self.edges[i])
+    }
+
+    /// Sources whose cards also drop in this one: bosses of a map or act and groups that contain
+    /// the source.
+    pub fn transitive_sources(&self, source: &Source) -> Vec<Source> {
+        self.edges_to(&Node::Source(source.clone()))
+            .filter(|edge| matches!(edge.kind, EdgeKind::BossOf | EdgeKind::Contains))
+            .filter_map(|edge| edge.from.source().cloned())
+            .collect()
+    }
+
+    /// Graphviz digraph. Sources are boxes, categories are ellipses.
+    pub fn to_dot(&self) -> String {
+        fn quote(s: &str) -> String {
+            format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+        }
+
+        let mut dot = String::from("digraph divcord {\n    rankdir=LR;\n");
+        for node in &self.nodes {
+            let shape = match node {
+                Node::Source(_) => "box",
+                Node::Category(_) => "ellipse",
+            };
+            let _ = writeln!(
+                dot,
+                "    {} [label={}, shape={shape}];",
+                quote(&node.key()),
+                quote(node.label())
+            );
+        }
+        for edge in &self.edges {
+            let _ = writeln!(
+                dot,
+                "    {} -> {} [label={}];",
+                quote(&edge.from.key()),
+                quote(&edge.to.key()),
+                quote(edge.kind.as_str())
+            );
+        }
+        dot.push_str("}\n");
+        dot
+    }
+
+    /// Nodes and edges by key, the shape most graph libraries take.
+    pub fn to_json(&self) -> GraphJson {
+        let nodes = self
+            .nodes
+            .iter()
+            .map(|node| NodeJson {
+                key: node.key(),
+                label: node.label().to_owned(),
+                source: node.source().cloned(),
+            })
+            .collect();
+        let edges = self
+            .edges
+            .iter()
+            .map(|edge| EdgeJson {
+                from: edge.from.key(),
+                to: edge.to.key(),
+                kind: edge.kind,
+            })
+            .collect();
+        GraphJson { nodes, edges }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GraphJson {
+    pub nodes: Vec<NodeJson>,
+    pub edges: Vec<EdgeJson>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeJson {
+    pub key: String,
+    pub label: String,
+    /// Empty for categories.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub source: Option<Source>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EdgeJson {
+    pub from: String,
+    pub to: String,
+    pub kind: EdgeKind,
+}
+
+impl GraphJson {
+    /// Keys of nodes by their position, for consumers that want indices.
+    pub fn node_indices(&self) -> HashMap<&str, usize> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .map(|(i, node)| (node.key.as_str(), i))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dropsource::predefined::monster::{FemaleRogueExile, RogueExile};
+
+    #[test]
+    fn builds_graph() {
+        let poe_data: PoeData =
+            serde_json::from_str(&std::fs::read_to_string("benches/poeData.json").unwrap())
+                .unwrap();
+        let graph = SourceGraph::new(&poe_data);
+
+        let has_edge = |from: &Node, to: &Node, kind: EdgeKind| {
+            graph
+                .edges_from(from)
+                .any(|edge| &edge.to == to && edge.kind == kind)
+        };
+        let source = |s: Source| Node::Source(s);
+        let monster =
+            |m: UniqueMonster| source(Source::Predefined(PredefinedSource::UniqueMonster(m)));
+
+        let map = source(Source::Map("Underground Sea Map".to_owned()));
+        let merveil = source(Source::MapBoss("Merveil, the Reflection".to_owned()));
+        assert!(has_edge(&merveil, &map, EdgeKind::BossOf));
+        assert!(has_edge(
+            &map,
+            &Node::Category("Map".to_owned()),
+            EdgeKind::CategoryMember
+        ));
+        assert!(has_edge(
+            &monster(UniqueMonster::AllRogueExiles),
+            &monster(UniqueMonster::RogueExile(RogueExile::Magnus)),
+            EdgeKind::Contains
+        ));
+        assert!(has_edge(
+            &monster(UniqueMonster::AllRogueExiles),
+            &monster(UniqueMonster::FemaleRogueExile(FemaleRogueExile::ThenaMoga)),
+            EdgeKind::Contains
+        ));
+        let all_rogue_exiles = Source::Predefined(PredefinedSource::UniqueMonster(
+            UniqueMonster::AllRogueExiles,
+        ));
+        assert_eq!(
+            graph.transitive_sources(&Source::Predefined(PredefinedSource::UniqueMonster(
+                UniqueMonster::RogueExile(RogueExile::Magnus)
+            ))),
+            vec![all_rogue_exiles]
+        );
+        assert!(has_edge(
+            &monster(UniqueMonster::UulNetolInBreachstones),
+            &monster(UniqueMonster::BreachlordBossDomain(
+                BreachlordBossDomain::UulNetol
+            )),
+            EdgeKind::VariantOf
+        ));
+        assert!(has_edge(
+            &Node::Category("Rogue Exile".to_owned()),
+            &Node::Category("Unique Monster".to_owned()),
+            EdgeKind::CategoryMember
+        ));
+
+        // Transitive lookup sees the same bosses as the graph.
+        for area in poe_data.maps.iter().map(|map| Source::from(map.clone())) {
+            let node = source(area.clone());
+            let from_graph: HashSet<&Node> = graph
+                .edges_to(&node)
+                .filter(|edge| edge.kind == EdgeKind::BossOf)
+                .map(|edge| &edge.from)
+                .collect();
+            let bosses: Vec<Node> = bosses_of(&area, &poe_data)
+                .into_iter()
+                .map(source)
+                .collect();
+            assert_eq!(from_graph, bosses.iter().collect());
+        }
+
+        let json = graph.to_json();
+        let indices = json.node_indices();
+        assert_eq!(indices.len(), json.nodes.len());
+        assert!(json
+            .edges
+            .iter()
+            .all(|edge| indices.contains_key(edge.from.as_str())
+                && indices.contains_key(edge.to.as_str())));
+        let value = serde_json::to_value(&json).unwrap();
+        assert_eq!(value["edges"][0]["kind"], "category-member");
+
+        let dot = graph.to_dot();
+        assert!(dot.starts_with("digraph divcord {"));
+        assert!(dot.contains(
+            "\"map-boss/merveil-the-reflection\" -> \"map/underground-sea-map\" [label=\"boss-of\"];"
+        ));
+    }
+}
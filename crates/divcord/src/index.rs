@@ -0,0 +1,275 @@
+//! Cards of every source and sources of every card, built once from records.
+//!
+//! [`cards_by_source`](crate::cards::cards_by_source) and
+//! [`cards_by_source_types`](crate::cards::cards_by_source_types) walk all records on every call,
+//! [`DivcordIndex`] answers the same questions with a lookup.
+
+use crate::{
+    cards::{CardBySource, Direct, SourceAndCards, Transitive, VerificationStatus},
+    graph::{Node, SourceGraph},
+    Record, Source,
+};
+use poe_data::PoeData;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Source of a card, the other side of [`CardBySource`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SourceOfCard {
+    pub source: Source,
+    pub status: VerificationStatus,
+    /// Boss of the map or act, or group of the monster, that drops the card.
+    #[serde(skip_serializing_if = "Option::is_none", default)]
+    pub transitive: Option<Source>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CardAndSources {
+    pub card: String,
+    pub sources: Vec<SourceOfCard>,
+}
+
+/// Sources are not strings, so the index serializes to lists of
+/// [`SourceAndCards`] and [`CardAndSources`].
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(from = "IndexJson", into = "IndexJson")]
+pub struct DivcordIndex {
+    by_source: HashMap<Source, Vec<CardBySource>>,
+    by_card: HashMap<String, Vec<SourceOfCard>>,
+    by_type: HashMap<String, Vec<Source>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct IndexJson {
+    sources: Vec<SourceAndCards>,
+    cards: Vec<CardAndSources>,
+}
+
+impl DivcordIndex {
+    pub fn new(records: &[Record], poe_data: &PoeData) -> Self {
+        let mut direct: HashMap<Source, HashSet<Direct>> = HashMap::new();
+        for record in records {
+            let statuses = record
+                .sources
+                .iter()
+                .map(|source| (source, VerificationStatus::Done))
+                .chain(
+                    record
+                        .verify_sources
+                        .iter()
+                        .map(|source| (source, VerificationStatus::Verify)),
+                );
+            for (source, status) in statuses {
+                direct.entry(source.clone()).or_default().insert(Direct {
+                    card: record.card.clone(),
+                    status,
+                });
+            }
+        }
+
+        // Maps and acts also drop cards of their bosses, group members the cards of their groups.
+        let graph = SourceGraph::new(poe_data);
+        let mut transitive: HashMap<Source, HashSet<Transitive>> = HashMap::new();
+        for source in graph.nodes().iter().filter_map(Node::source) {
+            for from in graph.transitive_sources(source) {
+                let Some(cards) = direct.get(&from) else {
+                    continue;
+                };
+                transitive
+                    .entry(source.clone())
+                    .or_default()
+                    .extend(cards.iter().map(|direct| Transitive {
+                        card: direct.card.clone(),
+                        status: direct.status,
+                        transitive: from.clone(),
+                    }));
+            }
+        }
+
+        let mut index = DivcordIndex::default();
+        for (source, cards) in direct {
+            for Direct { card, status } in &cards {
+                index
+                    .by_card
+                    .entry(card.clone())
+                    .or_default()
+                    .push(SourceOfCard {
+                        source: source.clone(),
+                        status: *status,
+                        transitive: None,
+                    });
+            }
+            index
+                .by_source
+                .entry(source)
+                .or_default()
+                .extend(cards.into_iter().map(CardBySource::Direct));
+        }
+        for (source, cards) in transitive {
+            for Transitive {
+                card,
+                status,
+                transitive,
+            } in &cards
+            {
+                index
+                    .by_card
+                    .entry(card.clone())
+                    .or_default()
+                    .push(SourceOfCard {
+                        source: source.clone(),
+                        status: *status,
+                        transitive: Some(transitive.clone()),
+                    });
+            }
+            index
+                .by_source
+                .entry(source)
+                .or_default()
+                .extend(cards.into_iter().map(CardBySource::Transitive));
+        }
+        index.index_types();
+        index
+    }
+
+    fn index_types(&mut self) {
+        self.by_type.clear();
+        for source in self.by_source.keys() {
+            self.by_type
+                .entry(source._type().to_owned())
+                .or_default()
+                .push(source.clone());
+        }
+    }
+
+    /// Cards of the source, directly and through its bosses.
+    pub fn cards_by_source(&self, source: &Source) -> &[CardBySource] {
+        self.by_source.get(source).map_or(&[], Vec::as_slice)
+    }
+
+    /// Sources of the card, including maps and acts of its bosses.
+    pub fn sources_by_card(&self, card: &str) -> &[SourceOfCard] {
+        self.by_card.get(card).map_or(&[], Vec::as_slice)
+    }
+
+    /// Sources of the type that drop any cards.
+    pub fn sources_of_type(&self, source_type: &str) -> &[Source] {
+        self.by_type.get(source_type).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn cards_by_source_types(&self, source_types: &[String]) -> Vec<SourceAndCards> {
+        source_types
+            .iter()
+            .flat_map(|source_type| self.sources_of_type(source_type))
+            .map(|source| SourceAndCards {
+                source: source.clone(),
+                cards: self.cards_by_source(source).to_vec(),
+            })
+            .collect()
+    }
+
+    pub fn sources(&self) -> impl Iterator<Item = &Source> {
+        self.by_source.keys()
+    }
+
+    pub fn cards(&self) -> impl Iterator<Item = &str> {
+        self.by_card.keys().map(String::as_str)
+    }
+}
+
+impl From<DivcordIndex> for IndexJson {
+    fn from(index: DivcordIndex) -> Self {
+        IndexJson {
+            sources: index
+                .by_source
+                .into_iter()
+                .map(|(source, cards)| SourceAndCards { source, cards })
+                .collect(),
+            cards: index
+                .by_card
+                .into_iter()
+                .map(|(card, sources)| CardAndSources { card, sources })
+                .collect(),
+        }
+    }
+}
+
+impl From<IndexJson> for DivcordIndex {
+    fn from(json: IndexJson) -> Self {
+        let mut index = DivcordIndex {
+            by_source: json
+                .sources
+                .into_iter()
+                .map(|SourceAndCards { source, cards }| (source, cards))
+                .collect(),
+            by_card: json
+                .cards
+                .into_iter()
+                .map(|CardAndSources { card, sources }| (card, sources))
+                .collect(),
+            by_type: HashMap::new(),
+        };
+        index.index_types();
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{cards, Spreadsheet};
+
+    fn load() -> (Vec<Record>, PoeData) {
+        let spreadsheet: Spreadsheet =
+            serde_json::from_str(&std::fs::read_to_string("benches/spreadsheet.json").unwrap())
+                .unwrap();
+        let poe_data: PoeData =
+            serde_json::from_str(&std::fs::read_to_string("benches/poeData.json").unwrap())
+                .unwrap();
+        let records = crate::records(&spreadsheet, &poe_data).unwrap();
+        (records, poe_data)
+    }
+
+    fn set(cards: &[CardBySource]) -> HashSet<&CardBySource> {
+        cards.iter().collect()
+    }
+
+    #[test]
+    fn index_matches_card_functions() {
+        let (records, poe_data) = load();
+        let index = DivcordIndex::new(&records, &poe_data);
+
+        let types = Source::types();
+        let graph = SourceGraph::new(&poe_data);
+        let expected = cards::cards_by_source_types(&types, &records, &graph);
+        let actual = index.cards_by_source_types(&types);
+        assert_eq!(expected.len(), actual.len());
+        for SourceAndCards { source, cards } in &expected {
+            assert_eq!(set(cards), set(index.cards_by_source(source)), "{source}");
+            assert_eq!(
+                set(&cards::cards_by_source(source, &records, &graph)),
+                set(index.cards_by_source(source)),
+                "{source}"
+            );
+        }
+
+        for card in index.cards() {
+            for source_of_card in index.sources_by_card(card) {
+                assert!(index
+                    .cards_by_source(&source_of_card.source)
+                    .iter()
+                    .any(|c| c.card() == card && c.status() == source_of_card.status));
+            }
+        }
+
+        let json = serde_json::to_string(&index).unwrap();
+        let deserialized: DivcordIndex = serde_json::from_str(&json).unwrap();
+        for source in index.sources() {
+            assert_eq!(
+                set(index.cards_by_source(source)),
+                set(deserialized.cards_by_source(source))
+            );
+        }
+    }
+}
@@ -1,15 +1,27 @@
 //! Loading and parsing the [divcord spreadsheet](https://docs.google.com/spreadsheets/d/1Pf2KNuGguZLyf6eu_R0E503U0QNyfMZqaRETsN5g6kU/edit?pli=1#gid=0) [`Spreadsheet`]
 
+pub mod annotation;
 pub mod cards;
+pub mod changelog;
 pub mod consts;
 pub mod dropsource;
+pub mod export;
+pub mod farm;
+pub mod graph;
+pub mod index;
+pub mod lint;
 pub mod parse;
+pub mod query;
 pub mod spreadsheet;
+pub mod suggest;
 
 pub use crate::{
     cards::{cards_by_source, cards_by_source_types, CardBySource},
+    changelog::Changelog,
     dropsource::Source,
+    index::DivcordIndex,
     parse::{records, records_iter, records_with_collect_all_errors, ParseRecordError},
+    query::{query_records, Query, QueryResult},
     spreadsheet::{record::Record, Spreadsheet},
 };
 
@@ -0,0 +1,478 @@
+//! Parse errors as serializable diagnostics, with JSON, SARIF and Markdown reports for CI and
+//! spreadsheet maintainers.
+
+use crate::{
+    consts::SPREADSHEET_URL,
+    parse::{
+        record_url, records_iter, DivcordColumn, ParseRecordError, ParseSourceError,
+        ParseSourceErrorKind,
+    },
+    spreadsheet::record::{ParseDumbErrKind, ParseDumbError},
+    suggest::Suggestion,
+    PoeData, Spreadsheet,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::{
+    collections::BTreeMap,
+    fmt::{Display, Write},
+    str::FromStr,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Severity {
+    /// Data is inconsistent, but the record parses.
+    Warning,
+    /// Part of the record could not be parsed.
+    Error,
+}
+
+impl Severity {
+    fn sarif_level(self) -> &'static str {
+        match self {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Json,
+    Sarif,
+    Markdown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownReportFormat(pub String);
+
+impl Display for UnknownReportFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Unknown report format `{}`. Expected json, sarif or markdown.",
+            self.0
+        )
+    }
+}
+
+impl std::error::Error for UnknownReportFormat {}
+
+impl FromStr for ReportFormat {
+    type Err = UnknownReportFormat;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "json" => Ok(ReportFormat::Json),
+            "sarif" => Ok(ReportFormat::Sarif),
+            "markdown" | "md" => Ok(ReportFormat::Markdown),
+            _ => Err(UnknownReportFormat(s.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// Kebab-case kind of the problem, e.g. `unknown-drop-source`.
+    pub code: String,
+    pub card: String,
+    pub record_id: usize,
+    /// Spreadsheet column letter.
+    pub column: char,
+    pub url: String,
+    /// Cell text the problem is about.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub text: Option<String>,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub suggestions: Vec<Suggestion>,
+}
+
+impl From<&ParseSourceError> for Diagnostic {
+    fn from(error: &ParseSourceError) -> Self {
+        use ParseSourceErrorKind as Kind;
+        let (severity, code, column, text, suggestions) = match &error.kind {
+            Kind::UnknownDropSource(drops_from, suggestions) => (
+                Severity::Error,
+                "unknown-drop-source",
+                DivcordColumn::Sources,
+                Some(drops_from.name.clone()),
+                suggestions.clone(),
+            ),
+            Kind::ActsMustBeItalic(drops_from) => (
+                Severity::Error,
+                "acts-must-be-italic",
+                DivcordColumn::Sources,
+                Some(drops_from.name.clone()),
+                vec![],
+            ),
+            Kind::SourceOrVerifyIsExpectedButEmpty => (
+                Severity::Warning,
+                "source-expected",
+                DivcordColumn::Sources,
+                None,
+                vec![],
+            ),
+            Kind::GreynoteDisabledButCardNotLegacy => (
+                Severity::Warning,
+                "disabled-but-not-legacy",
+                DivcordColumn::GreyNote,
+                None,
+                vec![],
+            ),
+            Kind::LegacyCardShouldBeMarkedAsDisabled => (
+                Severity::Warning,
+                "legacy-not-disabled",
+                DivcordColumn::GreyNote,
+                None,
+                vec![],
+            ),
+            Kind::ConfidenceNoneButHasSources => (
+                Severity::Warning,
+                "confidence-none-with-sources",
+                DivcordColumn::Sources,
+                None,
+                vec![],
+            ),
+            Kind::SourceIsDoneAndVerifyAtSameTime(source) => (
+                Severity::Warning,
+                "done-and-verify",
+                DivcordColumn::Sources,
+                Some(source.to_string()),
+                vec![],
+            ),
+            Kind::SourceLevelIsLowerThanCardMinLevel { source, column, .. } => (
+                Severity::Warning,
+                "source-below-card-min-level",
+                column.into(),
+                Some(source.to_string()),
+                vec![],
+            ),
+            Kind::SourceLevelIsHigherThanCardMaxLevel { source, column, .. } => (
+                Severity::Warning,
+                "source-above-card-max-level",
+                column.into(),
+                Some(source.to_string()),
+                vec![],
+            ),
+        };
+
+        Diagnostic {
+            severity,
+            code: code.to_owned(),
+            card: error.card.clone(),
+            record_id: error.record_id,
+            column: column.letter(),
+            url: record_url(error.record_id, column),
+            text,
+            message: error.to_string(),
+            suggestions,
+        }
+    }
+}
+
+impl From<&ParseDumbError> for Diagnostic {
+    fn from(error: &ParseDumbError) -> Self {
+        let (code, column, text) = match &error.kind {
+            ParseDumbErrKind::Greynote(_) => ("invalid-greynote", DivcordColumn::GreyNote, None),
+            ParseDumbErrKind::CardName(_) => (
+                "unknown-card",
+                DivcordColumn::Card,
+                Some(error.card.clone()),
+            ),
+            ParseDumbErrKind::Confidence(_) => {
+                ("invalid-confidence", DivcordColumn::Confidence, None)
+            }
+            ParseDumbErrKind::RemainingWork(_) => {
+                ("invalid-remaining-work", DivcordColumn::RemainingWork, None)
+            }
+            ParseDumbErrKind::StyledCell(_) => {
+                ("invalid-styled-cell", DivcordColumn::Sources, None)
+            }
+            ParseDumbErrKind::DuplicateDropSource(drops_from) => (
+                "duplicate-drop-source",
+                DivcordColumn::Sources,
+                Some(drops_from.name.clone()),
+            ),
+        };
+
+        Diagnostic {
+            severity: Severity::Error,
+            code: code.to_owned(),
+            card: error.card.clone(),
+            record_id: error.record_id,
+            column: column.letter(),
+            url: record_url(error.record_id, column),
+            text,
+            message: error.to_string(),
+            suggestions: vec![],
+        }
+    }
+}
+
+impl From<&ParseRecordError> for Vec<Diagnostic> {
+    fn from(error: &ParseRecordError) -> Self {
+        match error {
+            ParseRecordError::ParseDumb(error) => vec![Diagnostic::from(error)],
+            ParseRecordError::ParseDropSources(errors) => {
+                errors.iter().map(Diagnostic::from).collect()
+            }
+        }
+    }
+}
+
+/// All diagnostics of a spreadsheet, ordered by record.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LintReport {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl LintReport {
+    pub fn new(spreadsheet: &Spreadsheet, poe_data: &PoeData) -> Self {
+        let mut diagnostics: Vec<Diagnostic> = vec![];
+        for result in records_iter(spreadsheet, poe_data) {
+            match result {
+                Ok(result) => diagnostics.extend(result.errors.iter().map(Diagnostic::from)),
+                Err(error) => diagnostics.push(Diagnostic::from(&error)),
+            }
+        }
+        LintReport { diagnostics }
+    }
+
+    pub fn from_errors(errors: &[ParseRecordError]) -> Self {
+        LintReport {
+            diagnostics: errors.iter().flat_map(Vec::<Diagnostic>::from).collect(),
+        }
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .count()
+    }
+
+    pub fn has_errors(&self) -> bool {
+        self.count(Severity::Error) > 0
+    }
+
+    /// Diagnostics by code, errors first.
+    pub fn by_code(&self) -> Vec<(&str, Vec<&Diagnostic>)> {
+        let mut groups: BTreeMap<&str, Vec<&Diagnostic>> = BTreeMap::new();
+        for diagnostic in &self.diagnostics {
+            groups.entry(&diagnostic.code).or_default().push(diagnostic);
+        }
+        let mut groups: Vec<(&str, Vec<&Diagnostic>)> = groups.into_iter().collect();
+        groups.sort_by_key(|(_, diagnostics)| std::cmp::Reverse(diagnostics[0].severity));
+        groups
+    }
+
+    pub fn render(&self, format: ReportFormat) -> String {
+        match format {
+            ReportFormat::Json => self.to_json(),
+            ReportFormat::Sarif => {
+                serde_json::to_string_pretty(&self.to_sarif()).expect("SARIF log should serialize")
+            }
+            ReportFormat::Markdown => self.to_markdown(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Lint report should serialize")
+    }
+
+    /// SARIF 2.1.0 log with one run. Rows and columns of the spreadsheet are lines and columns.
+    pub fn to_sarif(&self) -> Value {
+        let rules: Vec<Value> = self
+            .by_code()
+            .into_iter()
+            .map(|(code, diagnostics)| {
+                json!({
+                    "id": code,
+                    "defaultConfiguration": { "level": diagnostics[0].severity.sarif_level() },
+                })
+            })
+            .collect();
+        let results: Vec<Value> = self
+            .diagnostics
+            .iter()
+            .map(|diagnostic| {
+                let mut result = json!({
+                    "ruleId": diagnostic.code,
+                    "level": diagnostic.severity.sarif_level(),
+                    "message": { "text": diagnostic.message },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": diagnostic.url },
+                            "region": {
+                                "startLine": diagnostic.record_id,
+                                "startColumn": u32::from(diagnostic.column) - u32::from('A') + 1,
+                            },
+                        },
+                        "logicalLocations": [{ "name": diagnostic.card }],
+                    }],
+                });
+                if !diagnostic.suggestions.is_empty() {
+                    result["properties"] = json!({ "suggestions": diagnostic.suggestions });
+                }
+                result
+            })
+            .collect();
+
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "divcord",
+                        "informationUri": SPREADSHEET_URL,
+                        "rules": rules,
+                    },
+                },
+                "results": results,
+            }],
+        })
+    }
+
+    /// Summary with a table per code.
+    pub fn to_markdown(&self) -> String {
+        let mut markdown = String::from("# Divcord lint report\n\n");
+        let _ = writeln!(
+            markdown,
+            "{} errors, {} warnings",
+            self.count(Severity::Error),
+            self.count(Severity::Warning)
+        );
+
+        let cell = |s: &str| s.replace('|', "\\|").replace('\n', " ");
+        for (code, diagnostics) in self.by_code() {
+            let _ = write!(
+                markdown,
+                "\n## {code} ({}, {})\n\n| Record | Card | Text | Suggestions |\n| --- | --- | --- | --- |\n",
+                diagnostics.len(),
+                diagnostics[0].severity.sarif_level()
+            );
+            for diagnostic in diagnostics {
+                let suggestions = diagnostic
+                    .suggestions
+                    .iter()
+                    .map(|s| format!("`{}`", cell(&s.text)))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                let _ = writeln!(
+                    markdown,
+                    "| [{}{}]({}) | {} | {} | {} |",
+                    diagnostic.column,
+                    diagnostic.record_id,
+                    diagnostic.url,
+                    cell(&diagnostic.card),
+                    cell(diagnostic.text.as_deref().unwrap_or_default()),
+                    suggestions
+                );
+            }
+        }
+        markdown
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        dropsource::Source,
+        parse::SourcesKind,
+        spreadsheet::rich::{DropsFrom, FontStyles, HexColor},
+    };
+
+    fn report() -> LintReport {
+        let drops_from = DropsFrom {
+            name: "Spidr Forest".to_owned(),
+            styles: FontStyles {
+                color: HexColor::White,
+                italic: false,
+                strikethrough: false,
+            },
+        };
+        let errors = vec![
+            ParseRecordError::ParseDropSources(vec![
+                ParseSourceError {
+                    card: "The Doctor".to_owned(),
+                    record_id: 267,
+                    kind: ParseSourceErrorKind::UnknownDropSource(
+                        drops_from,
+                        vec![Suggestion {
+                            text: "Spider Forest Map".to_owned(),
+                            source_type: "Map".to_owned(),
+                            distance: 5,
+                        }],
+                    ),
+                },
+                ParseSourceError {
+                    card: "The Doctor".to_owned(),
+                    record_id: 267,
+                    kind: ParseSourceErrorKind::SourceLevelIsLowerThanCardMinLevel {
+                        source: Source::Map("Sepulchre Map".to_owned()),
+                        area_level: 69,
+                        card_min_level: 75,
+                        column: SourcesKind::Verify,
+                    },
+                },
+            ]),
+            ParseRecordError::ParseDropSources(vec![ParseSourceError {
+                card: "Rain of Chaos".to_owned(),
+                record_id: 390,
+                kind: ParseSourceErrorKind::ConfidenceNoneButHasSources,
+            }]),
+        ];
+        LintReport::from_errors(&errors)
+    }
+
+    #[test]
+    fn renders_reports() {
+        let report = report();
+        assert_eq!(report.count(Severity::Error), 1);
+        assert_eq!(report.count(Severity::Warning), 2);
+
+        let unknown = &report.diagnostics[0];
+        assert_eq!(unknown.code, "unknown-drop-source");
+        assert_eq!(unknown.column, 'G');
+        assert_eq!(unknown.text.as_deref(), Some("Spidr Forest"));
+        assert_eq!(report.diagnostics[1].column, 'H');
+
+        let json: Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["diagnostics"][0]["severity"], "error");
+        assert_eq!(json["diagnostics"][0]["recordId"], 267);
+        assert_eq!(
+            json["diagnostics"][0]["suggestions"][0]["text"],
+            "Spider Forest Map"
+        );
+
+        let sarif = report.to_sarif();
+        let results = &sarif["runs"][0]["results"];
+        assert_eq!(results.as_array().unwrap().len(), 3);
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(
+            results[1]["locations"][0]["physicalLocation"]["region"]["startColumn"],
+            8
+        );
+        assert_eq!(
+            sarif["runs"][0]["tool"]["driver"]["rules"][0]["id"],
+            "unknown-drop-source"
+        );
+
+        let markdown = report.to_markdown();
+        assert!(markdown.contains("1 errors, 2 warnings"));
+        assert_eq!(markdown, report.render("md".parse().unwrap()));
+        assert!("xml".parse::<ReportFormat>().is_err());
+        assert!(
+            markdown.find("## unknown-drop-source").unwrap()
+                < markdown.find("## confidence").unwrap()
+        );
+        assert!(markdown.contains("| The Doctor | Spidr Forest | `Spider Forest Map` |"));
+    }
+}
@@ -1,5 +1,6 @@
 //! Parse drop sources.
 
+use crate::annotation::SourceNames;
 use crate::dropsource::predefined::PredefinedSource;
 use crate::dropsource::Source;
 use crate::spreadsheet::record::ParseDumbError;
@@ -9,6 +10,7 @@ use crate::spreadsheet::{
     rich::DropsFrom,
     Spreadsheet,
 };
+use crate::suggest::{suggestions, Suggestion};
 use divi::IsCard;
 use poe_data::act::ActArea;
 use poe_data::PoeData;
@@ -47,9 +49,10 @@ pub fn records_iter<'a>(
     spreadsheet: &'a Spreadsheet,
     poe_data: &'a PoeData,
 ) -> impl Iterator<Item = Result<ParseRecordResult, ParseDumbError>> + 'a {
+    let source_names = SourceNames::new(poe_data);
     spreadsheet
         .dumb_records()
-        .map(|dumb| Ok(parse_record(dumb?, poe_data)))
+        .map(move |dumb| Ok(parse_record(dumb?, poe_data, &source_names)))
 }
 
 /// Parse till first error. Maybe delete this one later.
@@ -111,8 +114,12 @@ pub struct ParseRecordResult {
     pub errors: Vec<ParseSourceError>,
 }
 
-/// [Dumb] -> [Record]
-pub fn parse_record(dumb: Dumb, poe_data: &PoeData) -> ParseRecordResult {
+/// [Dumb] -> [Record]. `source_names` are built once from `poe_data` with [`SourceNames::new`].
+pub fn parse_record(
+    dumb: Dumb,
+    poe_data: &PoeData,
+    source_names: &SourceNames,
+) -> ParseRecordResult {
     let (sources, mut errors) = parse_record_dropsources(&dumb, poe_data);
     let (mut verify_sources, errors_verify_drops_from) =
         parse_dropses_from(&dumb, poe_data, SourcesKind::Verify);
@@ -150,9 +157,28 @@ pub fn parse_record(dumb: Dumb, poe_data: &PoeData) -> ParseRecordResult {
             .into_iter()
             .map(ParseSourceError::from),
     );
+    errors.extend(check_source_levels(
+        &dumb,
+        &sources,
+        SourcesKind::Source,
+        poe_data,
+    ));
+    errors.extend(check_source_levels(
+        &dumb,
+        &verify_sources,
+        SourcesKind::Verify,
+        poe_data,
+    ));
+
+    let annotate = |text: Option<&str>| {
+        text.map(|text| source_names.annotate(text))
+            .unwrap_or_default()
+    };
 
     ParseRecordResult {
         record: Record {
+            tag_hypothesis_annotations: annotate(dumb.tag_hypothesis.as_deref()),
+            notes_annotations: annotate(dumb.notes.as_deref()),
             sources,
             verify_sources,
             id: dumb.id,
@@ -176,13 +202,25 @@ pub struct ParseSourceError {
 
 #[derive(Debug)]
 pub enum ParseSourceErrorKind {
-    UnknownDropSource(DropsFrom),
+    UnknownDropSource(DropsFrom, Vec<Suggestion>),
     ActsMustBeItalic(DropsFrom),
     SourceOrVerifyIsExpectedButEmpty,
     GreynoteDisabledButCardNotLegacy,
     LegacyCardShouldBeMarkedAsDisabled,
     ConfidenceNoneButHasSources,
     SourceIsDoneAndVerifyAtSameTime(Source),
+    SourceLevelIsLowerThanCardMinLevel {
+        source: Source,
+        area_level: u32,
+        card_min_level: u32,
+        column: SourcesKind,
+    },
+    SourceLevelIsHigherThanCardMaxLevel {
+        source: Source,
+        area_level: u32,
+        card_max_level: u32,
+        column: SourcesKind,
+    },
 }
 
 impl From<ParseDropsFromError> for ParseSourceError {
@@ -192,7 +230,7 @@ impl From<ParseDropsFromError> for ParseSourceError {
             record_id: value.record_id,
             kind: match value.kind {
                 ParseDropsFromErrorKind::Unknown => {
-                    ParseSourceErrorKind::UnknownDropSource(value.drops_from)
+                    ParseSourceErrorKind::UnknownDropSource(value.drops_from, value.suggestions)
                 }
                 ParseDropsFromErrorKind::ActsMustBeItalic => {
                     ParseSourceErrorKind::ActsMustBeItalic(value.drops_from)
@@ -217,12 +255,18 @@ impl Display for ParseSourceError {
             kind,
         } = self;
         match kind {
-            ParseSourceErrorKind::UnknownDropSource(drops_from) => write!(
-                                f,
-                                "{record_id}.{card}. Unknown variant of card source {}. {}",
-                                drops_from.name,
-                                record_url(*record_id, DivcordColumn::Sources)
-                            ),
+            ParseSourceErrorKind::UnknownDropSource(drops_from, suggestions) => {
+                                write!(f, "{record_id}.{card}. Unknown variant of card source {}.", drops_from.name)?;
+                                if !suggestions.is_empty() {
+                                    let names = suggestions
+                                        .iter()
+                                        .map(|s| format!("`{}`", s.text))
+                                        .collect::<Vec<_>>()
+                                        .join(", ");
+                                    write!(f, " Did you mean {names}?")?;
+                                }
+                                write!(f, " {}", record_url(*record_id, DivcordColumn::Sources))
+                            }
             ParseSourceErrorKind::ActsMustBeItalic(drops_from) => write!(
                                 f,
                                 "{record_id}.{card}. Spreadsheet styling error: If {} refers to acts, it's font-style must be italic. {}",
@@ -253,10 +297,23 @@ impl Display for ParseSourceError {
                                 f, "{record_id}.{card}. Source {source:?} is done and verify at same time {}", 
                                 record_url(*record_id, DivcordColumn::Sources)
                             ),
+            ParseSourceErrorKind::SourceLevelIsLowerThanCardMinLevel { source, area_level, card_min_level, column } => write!(
+                                f,
+                                "{record_id}.{card}. {} {source} has area level {area_level}, but the card drops from level {card_min_level}. {}",
+                                source._type(),
+                                record_url(*record_id, column.into())
+                            ),
+            ParseSourceErrorKind::SourceLevelIsHigherThanCardMaxLevel { source, area_level, card_max_level, column } => write!(
+                                f,
+                                "{record_id}.{card}. {} {source} has area level {area_level}, but the card drops only up to level {card_max_level}. {}",
+                                source._type(),
+                                record_url(*record_id, column.into())
+                            ),
         }
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum DivcordColumn {
     GreyNote,
     Card,
@@ -283,6 +340,15 @@ impl DivcordColumn {
     }
 }
 
+impl From<&SourcesKind> for DivcordColumn {
+    fn from(value: &SourcesKind) -> Self {
+        match value {
+            SourcesKind::Source => DivcordColumn::Sources,
+            SourcesKind::Verify => DivcordColumn::Verify,
+        }
+    }
+}
+
 pub fn record_url(id: usize, column: DivcordColumn) -> String {
     format!("https://docs.google.com/spreadsheets/d/1Pf2KNuGguZLyf6eu_R0E503U0QNyfMZqaRETsN5g6kU/edit?pli=1#gid=0&range={}{id}", column.letter())
 }
@@ -370,11 +436,61 @@ pub fn parse_dropses_from(
 #[derive(Debug, PartialEq)]
 pub enum ParseDropsFromErrorKind {
     Unknown,
-    ActsMustBeItalic, // DropSourceLevelisLowerThanCardMinLevel {
-                      //     level: u32,
-                      //     card: String,
-                      //     card_min_drop_level: u32,
-                      // },
+    ActsMustBeItalic,
+}
+
+/// Monsters of an area can be up to 2 levels higher than the area itself.
+pub const MONSTER_LEVEL_OVER_AREA_LEVEL: u32 = 2;
+
+/// Checks area levels of resolved sources against drop levels of the card.
+/// A source with several areas (bosses) is fine if any of them fits.
+pub fn check_source_levels(
+    dumb: &Dumb,
+    sources: &[Source],
+    column: SourcesKind,
+    poe_data: &PoeData,
+) -> Vec<ParseSourceError> {
+    let Some(card) = poe_data.cards.0.get(&dumb.card) else {
+        return vec![];
+    };
+
+    let mut errors = vec![];
+    for source in sources {
+        let Some(levels) = source.area_levels(poe_data) else {
+            continue;
+        };
+        let (lowest, highest) = (*levels.start(), *levels.end());
+
+        let kind = match (card.min_level, card.max_level) {
+            (Some(card_min_level), _)
+                if highest + MONSTER_LEVEL_OVER_AREA_LEVEL < card_min_level =>
+            {
+                ParseSourceErrorKind::SourceLevelIsLowerThanCardMinLevel {
+                    source: source.to_owned(),
+                    area_level: highest,
+                    card_min_level,
+                    column: column.clone(),
+                }
+            }
+            (_, Some(card_max_level))
+                if lowest + MONSTER_LEVEL_OVER_AREA_LEVEL > card_max_level =>
+            {
+                ParseSourceErrorKind::SourceLevelIsHigherThanCardMaxLevel {
+                    source: source.to_owned(),
+                    area_level: lowest,
+                    card_max_level,
+                    column: column.clone(),
+                }
+            }
+            _ => continue,
+        };
+        errors.push(ParseSourceError {
+            card: dumb.card.to_owned(),
+            record_id: dumb.id,
+            kind,
+        });
+    }
+    errors
 }
 
 #[derive(Debug)]
@@ -383,18 +499,21 @@ pub struct ParseDropsFromError {
     pub record_id: usize,
     pub drops_from: DropsFrom,
     pub kind: ParseDropsFromErrorKind,
+    /// Known sources with similar names, for [`ParseDropsFromErrorKind::Unknown`].
+    pub suggestions: Vec<Suggestion>,
 }
 
 pub fn parse_one_drops_from(
     d: &DropsFrom,
     dumb: &Dumb,
-    PoeData {
+    poe_data: &PoeData,
+) -> Result<Vec<Source>, ParseDropsFromError> {
+    let PoeData {
         acts,
         cards,
         maps,
         mapbosses,
-    }: &PoeData,
-) -> Result<Vec<Source>, ParseDropsFromError> {
+    } = poe_data;
     if d.styles.strikethrough {
         return Ok(vec![]);
     }
@@ -439,6 +558,7 @@ pub fn parse_one_drops_from(
             record_id: dumb.id,
             drops_from: d.to_owned(),
             kind: ParseDropsFromErrorKind::ActsMustBeItalic,
+            suggestions: vec![],
         });
     }
 
@@ -447,6 +567,7 @@ pub fn parse_one_drops_from(
         record_id: dumb.id,
         drops_from: d.to_owned(),
         kind: ParseDropsFromErrorKind::Unknown,
+        suggestions: suggestions(&strip_comment(&d.name), poe_data),
     })
 }
 
@@ -466,7 +587,9 @@ fn find_in_acts_or_act_bosses(
                 return false;
             }
 
-            if (a.area_level + 2) < card_min_drop_level {
+            if u32::from(a.area_level) + MONSTER_LEVEL_OVER_AREA_LEVEL
+                < u32::from(card_min_drop_level)
+            {
                 println!("Monster level is lower than card drop requirement");
                 return false;
             }
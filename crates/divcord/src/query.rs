@@ -0,0 +1,503 @@
+//! Filtering, sorting and paging of records, built with setters or parsed from a text query.
+//!
+//! The text syntax is a list of `key:value` terms separated by spaces. Comma-separated values
+//! match any of them, values with spaces go in double quotes and words without a key match the
+//! card name:
+//!
+//! ```text
+//! doctor confidence:low,none type:"Map Boss" verify level:68..83 price:10.. sort:-price limit:20
+//! ```
+//!
+//! Source types and ids compare by slug, so `type:map-boss` and `source:spider-forest` work too.
+//! `tag:snow_area` matches tags of the tag hypothesis column.
+
+use crate::{
+    dropsource::Source,
+    spreadsheet::record::{Confidence, GreyNote, Record, RemainingWork},
+};
+use poe_data::{cards::Card, PoeData};
+use serde::{Deserialize, Serialize};
+use std::{cmp::Ordering, fmt::Display, str::FromStr};
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum SortBy {
+    /// Spreadsheet row order.
+    #[default]
+    Id,
+    Card,
+    Price,
+    /// Minimum card level.
+    Level,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase", default)]
+pub struct Query {
+    /// Case-insensitive parts of the card name.
+    pub card: Vec<String>,
+    pub confidence: Vec<Confidence>,
+    pub greynote: Vec<GreyNote>,
+    pub remaining_work: Vec<RemainingWork>,
+    pub source_types: Vec<String>,
+    pub source_ids: Vec<String>,
+    /// Tags of the tag hypothesis, any of them.
+    pub tags: Vec<String>,
+    /// Only records with sources to verify. Source filters then look at these sources only.
+    pub verify_only: bool,
+    /// Cards that drop at this area level or higher.
+    pub min_level: Option<u32>,
+    /// Cards that drop at this area level or lower.
+    pub max_level: Option<u32>,
+    pub min_price: Option<f32>,
+    pub max_price: Option<f32>,
+    pub sort_by: SortBy,
+    pub descending: bool,
+    pub offset: usize,
+    pub limit: Option<usize>,
+}
+
+/// One page of matched records.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct QueryResult {
+    /// Number of matched records before paging.
+    pub total: usize,
+    pub records: Vec<Record>,
+}
+
+impl Query {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn card(mut self, card: impl Into<String>) -> Self {
+        self.card.push(card.into());
+        self
+    }
+
+    pub fn confidence(mut self, confidence: Confidence) -> Self {
+        self.confidence.push(confidence);
+        self
+    }
+
+    pub fn greynote(mut self, greynote: GreyNote) -> Self {
+        self.greynote.push(greynote);
+        self
+    }
+
+    pub fn remaining_work(mut self, remaining_work: RemainingWork) -> Self {
+        self.remaining_work.push(remaining_work);
+        self
+    }
+
+    pub fn source_type(mut self, source_type: impl Into<String>) -> Self {
+        self.source_types.push(source_type.into());
+        self
+    }
+
+    pub fn source_id(mut self, id: impl Into<String>) -> Self {
+        self.source_ids.push(id.into());
+        self
+    }
+
+    pub fn tag(mut self, tag: impl Into<String>) -> Self {
+        self.tags.push(tag.into());
+        self
+    }
+
+    pub fn verify_only(mut self) -> Self {
+        self.verify_only = true;
+        self
+    }
+
+    pub fn levels(mut self, min: Option<u32>, max: Option<u32>) -> Self {
+        self.min_level = min;
+        self.max_level = max;
+        self
+    }
+
+    pub fn prices(mut self, min: Option<f32>, max: Option<f32>) -> Self {
+        self.min_price = min;
+        self.max_price = max;
+        self
+    }
+
+    pub fn sort(mut self, sort_by: SortBy, descending: bool) -> Self {
+        self.sort_by = sort_by;
+        self.descending = descending;
+        self
+    }
+
+    pub fn page(mut self, offset: usize, limit: usize) -> Self {
+        self.offset = offset;
+        self.limit = Some(limit);
+        self
+    }
+
+    fn matches_source(&self, source: &Source) -> bool {
+        let slug_matches = |values: &[String], slug: String| {
+            values.is_empty() || values.iter().any(|value| slug::slugify(value) == slug)
+        };
+        slug_matches(&self.source_types, source.type_slug())
+            && slug_matches(&self.source_ids, source.slug())
+    }
+
+    pub fn matches(&self, record: &Record, poe_data: &PoeData) -> bool {
+        let card_name = record.card.to_lowercase();
+        if !self
+            .card
+            .iter()
+            .all(|part| card_name.contains(&part.to_lowercase()))
+        {
+            return false;
+        }
+        if !self.confidence.is_empty() && !self.confidence.contains(&record.confidence) {
+            return false;
+        }
+        if !self.greynote.is_empty() && !self.greynote.contains(&record.greynote) {
+            return false;
+        }
+        if !self.remaining_work.is_empty() && !self.remaining_work.contains(&record.remaining_work)
+        {
+            return false;
+        }
+        if !self.tags.is_empty()
+            && !record
+                .tag_hypothesis_annotations
+                .tags
+                .iter()
+                .any(|tag| self.tags.contains(tag))
+        {
+            return false;
+        }
+        if self.verify_only && record.verify_sources.is_empty() {
+            return false;
+        }
+
+        if !self.source_types.is_empty() || !self.source_ids.is_empty() {
+            let mut sources = record.verify_sources.iter();
+            let found = match self.verify_only {
+                true => sources.any(|s| self.matches_source(s)),
+                false => sources
+                    .chain(record.sources.iter())
+                    .any(|s| self.matches_source(s)),
+            };
+            if !found {
+                return false;
+            }
+        }
+
+        if self.min_level.is_some() || self.max_level.is_some() {
+            let Some(card) = poe_data.cards.0.get(&record.card) else {
+                return false;
+            };
+            // Level ranges overlap. Cards without levels drop everywhere.
+            if let (Some(min), Some(card_max)) = (self.min_level, card.max_level) {
+                if card_max < min {
+                    return false;
+                }
+            }
+            if let (Some(max), Some(card_min)) = (self.max_level, card.min_level) {
+                if card_min > max {
+                    return false;
+                }
+            }
+        }
+
+        if self.min_price.is_some() || self.max_price.is_some() {
+            let Some(price) = poe_data
+                .cards
+                .0
+                .get(&record.card)
+                .and_then(|card| card.price)
+            else {
+                return false;
+            };
+            if self.min_price.is_some_and(|min| price < min)
+                || self.max_price.is_some_and(|max| price > max)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Matched records sorted and paged. Records with missing price or level go last.
+    pub fn run(&self, records: &[Record], poe_data: &PoeData) -> QueryResult {
+        let mut matched: Vec<&Record> = records
+            .iter()
+            .filter(|record| self.matches(record, poe_data))
+            .collect();
+        let total = matched.len();
+
+        let card = |record: &Record| poe_data.cards.0.get(&record.card);
+        let direction = |ordering: Ordering| match self.descending {
+            true => ordering.reverse(),
+            false => ordering,
+        };
+        let by_option = |a: Option<f32>, b: Option<f32>| match (a, b) {
+            (Some(a), Some(b)) => direction(a.total_cmp(&b)),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        matched.sort_by(|a, b| match self.sort_by {
+            SortBy::Id => direction(a.id.cmp(&b.id)),
+            SortBy::Card => direction(a.card.cmp(&b.card)),
+            SortBy::Price => {
+                by_option(card(a).and_then(|c| c.price), card(b).and_then(|c| c.price))
+            }
+            SortBy::Level => {
+                let level = |card: Option<&Card>| card.and_then(|c| c.min_level).map(|l| l as f32);
+                by_option(level(card(a)), level(card(b)))
+            }
+        });
+
+        let records = matched
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .cloned()
+            .collect();
+        QueryResult { total, records }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum QueryParseError {
+    UnknownKey(String),
+    InvalidValue { key: String, value: String },
+    UnterminatedQuote,
+}
+
+impl Display for QueryParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            QueryParseError::UnknownKey(key) => write!(
+                f,
+                "Unknown query key `{key}`, expected one of `card`, `confidence`, `greynote`, `work`, `type`, `source`, `tag`, `verify`, `level`, `price`, `sort`, `offset`, `limit`"
+            ),
+            QueryParseError::InvalidValue { key, value } => {
+                write!(f, "Invalid value `{value}` for `{key}`")
+            }
+            QueryParseError::UnterminatedQuote => f.write_str("Unterminated quote in query"),
+        }
+    }
+}
+
+impl std::error::Error for QueryParseError {}
+
+/// Splits the query into terms on spaces outside of double quotes, quotes are removed.
+fn terms(s: &str) -> Result<Vec<String>, QueryParseError> {
+    let mut terms = vec![];
+    let mut term = String::new();
+    let mut quoted = false;
+    for ch in s.chars() {
+        match ch {
+            '"' => quoted = !quoted,
+            ch if ch.is_whitespace() && !quoted => {
+                if !term.is_empty() {
+                    terms.push(std::mem::take(&mut term));
+                }
+            }
+            ch => term.push(ch),
+        }
+    }
+    if quoted {
+        return Err(QueryParseError::UnterminatedQuote);
+    }
+    if !term.is_empty() {
+        terms.push(term);
+    }
+    Ok(terms)
+}
+
+/// `min..max`, either side can be left out. A single number is both bounds.
+fn range<T: FromStr + Copy>(value: &str) -> Option<(Option<T>, Option<T>)> {
+    let bound = |s: &str| match s.is_empty() {
+        true => Some(None),
+        false => s.parse::<T>().ok().map(Some),
+    };
+    match value.split_once("..") {
+        Some((min, max)) => Some((bound(min)?, bound(max)?)),
+        None => {
+            let n = value.parse::<T>().ok()?;
+            Some((Some(n), Some(n)))
+        }
+    }
+}
+
+/// All values parsed or `None` if any of them is invalid.
+fn parse_all<'a, T: FromStr>(values: impl Iterator<Item = &'a str>) -> Option<Vec<T>> {
+    values.map(|v| v.parse().ok()).collect()
+}
+
+impl FromStr for Query {
+    type Err = QueryParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut query = Query::new();
+        for term in terms(s)? {
+            let Some((key, value)) = term.split_once(':') else {
+                match term.as_str() {
+                    "verify" => query.verify_only = true,
+                    _ => query.card.push(term),
+                }
+                continue;
+            };
+
+            let invalid = || QueryParseError::InvalidValue {
+                key: key.to_owned(),
+                value: value.to_owned(),
+            };
+            let values = || value.split(',').filter(|v| !v.is_empty());
+
+            match key {
+                "card" => query.card.extend(values().map(str::to_owned)),
+                "confidence" => {
+                    query
+                        .confidence
+                        .extend(parse_all::<Confidence>(values()).ok_or_else(invalid)?);
+                }
+                "greynote" => {
+                    query
+                        .greynote
+                        .extend(parse_all::<GreyNote>(values()).ok_or_else(invalid)?);
+                }
+                "work" => {
+                    query
+                        .remaining_work
+                        .extend(parse_all::<RemainingWork>(values()).ok_or_else(invalid)?);
+                }
+                "type" => query.source_types.extend(values().map(str::to_owned)),
+                "source" => query.source_ids.extend(values().map(str::to_owned)),
+                "tag" => query.tags.extend(values().map(str::to_owned)),
+                "verify" => {
+                    query.verify_only = value.parse().map_err(|_| invalid())?;
+                }
+                "level" => {
+                    (query.min_level, query.max_level) = range(value).ok_or_else(invalid)?;
+                }
+                "price" => {
+                    (query.min_price, query.max_price) = range(value).ok_or_else(invalid)?;
+                }
+                "sort" => {
+                    let (descending, field) = match value.strip_prefix('-') {
+                        Some(field) => (true, field),
+                        None => (false, value),
+                    };
+                    query.descending = descending;
+                    query.sort_by = match field {
+                        "id" => SortBy::Id,
+                        "card" => SortBy::Card,
+                        "price" => SortBy::Price,
+                        "level" => SortBy::Level,
+                        _ => return Err(invalid()),
+                    };
+                }
+                "offset" => query.offset = value.parse().map_err(|_| invalid())?,
+                "limit" => query.limit = Some(value.parse().map_err(|_| invalid())?),
+                _ => return Err(QueryParseError::UnknownKey(key.to_owned())),
+            }
+        }
+        Ok(query)
+    }
+}
+
+/// Returns all records that the text query matches.
+pub fn query_records(
+    query: &str,
+    records: &[Record],
+    poe_data: &PoeData,
+) -> Result<QueryResult, QueryParseError> {
+    Ok(query.parse::<Query>()?.run(records, poe_data))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spreadsheet::record::record;
+    use poe_data::cards::CardsData;
+    use std::collections::HashMap;
+
+    fn card(name: &str, levels: (Option<u32>, Option<u32>), price: Option<f32>) -> (String, Card) {
+        (
+            name.to_owned(),
+            Card {
+                slug: slug::slugify(name),
+                name: name.to_owned(),
+                min_level: levels.0,
+                max_level: levels.1,
+                weights: HashMap::new(),
+                price,
+                league: None,
+                disabled: false,
+            },
+        )
+    }
+
+    #[test]
+    fn parses_and_runs_queries() {
+        let poe_data = PoeData {
+            acts: vec![],
+            cards: CardsData(HashMap::from([
+                card("The Doctor", (Some(68), None), Some(900.0)),
+                card("Rain of Chaos", (None, None), Some(0.1)),
+                card("The Fiend", (Some(68), None), Some(1200.0)),
+                card("Her Mask", (Some(1), Some(40)), None),
+            ])),
+            maps: vec![],
+            mapbosses: vec![],
+        };
+        let burial = Source::Map("Burial Chambers".to_owned());
+        let mut fiend = record(3, "The Fiend", vec![]);
+        fiend.confidence = Confidence::Low;
+        fiend.verify_sources = vec![Source::MapBoss("Shavronne".to_owned())];
+        fiend.tag_hypothesis_annotations.tags = vec!["prison_area".to_owned()];
+        let records = vec![
+            record(1, "The Doctor", vec![burial.clone()]),
+            record(2, "Rain of Chaos", vec![burial]),
+            fiend,
+            Record {
+                confidence: Confidence::None,
+                ..record(4, "Her Mask", vec![])
+            },
+        ];
+
+        let cards = |query: &str| -> Vec<String> {
+            query_records(query, &records, &poe_data)
+                .unwrap()
+                .records
+                .into_iter()
+                .map(|r| r.card)
+                .collect()
+        };
+
+        assert_eq!(
+            cards("source:burial-chambers sort:-price"),
+            ["The Doctor", "Rain of Chaos"]
+        );
+        assert_eq!(cards("confidence:low,none"), ["The Fiend", "Her Mask"]);
+        assert_eq!(cards("tag:prison_area,snow_area"), ["The Fiend"]);
+        assert_eq!(cards(r#"verify type:"Map Boss""#), ["The Fiend"]);
+        assert_eq!(cards("level:..50"), ["Rain of Chaos", "Her Mask"]);
+        assert_eq!(cards("price:100.. sort:price"), ["The Doctor", "The Fiend"]);
+        assert_eq!(cards("the sort:card offset:1 limit:1"), ["The Fiend"]);
+
+        let result = Query::new()
+            .confidence(Confidence::Done)
+            .page(0, 1)
+            .run(&records, &poe_data);
+        assert_eq!(result.total, 2);
+        assert_eq!(result.records.len(), 1);
+
+        assert_eq!(
+            "color:red".parse::<Query>(),
+            Err(QueryParseError::UnknownKey("color".to_owned()))
+        );
+        assert!("confidence:maybe".parse::<Query>().is_err());
+        assert!(r#"source:"Burial"#.parse::<Query>().is_err());
+    }
+}
@@ -1,7 +1,7 @@
 #![cfg(feature = "fs_cache_fetcher")]
 
 use super::Spreadsheet;
-pub use fs_cache_fetcher::{Config, DataFetcher, Stale};
+pub use fs_cache_fetcher::{Codec, Config, DataFetcher, Stale};
 
 pub struct SpreadsheetFetcher(pub Config);
 
@@ -9,8 +9,10 @@ impl Default for SpreadsheetFetcher {
     fn default() -> Self {
         Self(Config {
             save: true,
-            filename: "spreadsheet.json",
+            filename: "spreadsheet.json".to_owned(),
             stale: Stale::ReloadEveryTime,
+            codec: Codec::Cbor,
+            ..Default::default()
         })
     }
 }
@@ -35,6 +37,7 @@ pub enum FetcherError {
     Serde(serde_json::Error),
     Io(std::io::Error),
     Reqwest(reqwest::Error),
+    GoogleSheets(googlesheets::error::Error),
 }
 
 impl From<serde_json::Error> for FetcherError {
@@ -54,3 +57,9 @@ impl From<reqwest::Error> for FetcherError {
         FetcherError::Reqwest(value)
     }
 }
+
+impl From<googlesheets::error::Error> for FetcherError {
+    fn from(value: googlesheets::error::Error) -> Self {
+        FetcherError::GoogleSheets(value)
+    }
+}
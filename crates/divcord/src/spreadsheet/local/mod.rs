@@ -0,0 +1,462 @@
+//! Loads the [`Spreadsheet`] from a downloaded copy instead of the Sheets API, so records can be
+//! parsed without network access or an API key.
+//!
+//! Supported copies:
+//! - XLSX or ODS export of the whole spreadsheet (File > Download)
+//! - CSV export of the records sheet, with an optional style sidecar: the `spreadsheets.get`
+//!   response for the drops columns, e.g.
+//!   `GET https://sheets.googleapis.com/v4/spreadsheets/{id}?ranges=Cards_and_Hypotheses!G3:I&includeGridData=true`
+
+mod ods;
+mod xlsx;
+mod xml;
+
+use super::{
+    rich::{ProtobufColor, RichColumn},
+    Spreadsheet, StyledDropsColumns, DROPS_COLUMN_LETTER, DROPS_DATAMINED_COLUMN_LETTER,
+    DROPS_VERIFY_COLUMN_LETTER, FIRST_RECORD_ROW, SHEET_TITLE,
+};
+use googlesheets::{
+    format::Color,
+    range::column_index,
+    sheet::{Dimension, ValueRange},
+    spreadsheet::{
+        self, CellData, CellFormat, GridData, RowData, Sheet, SheetProperties, TextFormat,
+        TextFormatRun,
+    },
+    A1Range, CellRef,
+};
+use serde_json::Value;
+use std::{fmt::Display, path::Path};
+
+/// Records sheet columns read into [`Spreadsheet::sheet`], `A` to `Z`.
+const COLUMNS: usize = 26;
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(std::io::Error),
+    Zip(zip::result::ZipError),
+    Xml(String, xml::XmlError),
+    Csv(csv::Error),
+    Serde(serde_json::Error),
+    /// A file expected inside the archive is missing.
+    MissingPart(String),
+    MissingSheet(String),
+    UnsupportedFormat(String),
+}
+
+impl Display for LoadError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LoadError::Io(err) => err.fmt(f),
+            LoadError::Zip(err) => err.fmt(f),
+            LoadError::Xml(part, err) => write!(f, "Could not parse {part}: {err}"),
+            LoadError::Csv(err) => err.fmt(f),
+            LoadError::Serde(err) => err.fmt(f),
+            LoadError::MissingPart(part) => write!(f, "{part} is missing in the archive"),
+            LoadError::MissingSheet(title) => write!(f, "Sheet {title} not found"),
+            LoadError::UnsupportedFormat(extension) => write!(
+                f,
+                "Unsupported file format {extension}. Expected xlsx, ods or csv"
+            ),
+        }
+    }
+}
+
+impl From<std::io::Error> for LoadError {
+    fn from(value: std::io::Error) -> Self {
+        LoadError::Io(value)
+    }
+}
+
+impl From<zip::result::ZipError> for LoadError {
+    fn from(value: zip::result::ZipError) -> Self {
+        LoadError::Zip(value)
+    }
+}
+
+impl From<csv::Error> for LoadError {
+    fn from(value: csv::Error) -> Self {
+        LoadError::Csv(value)
+    }
+}
+
+impl From<serde_json::Error> for LoadError {
+    fn from(value: serde_json::Error) -> Self {
+        LoadError::Serde(value)
+    }
+}
+
+/// Reads a file of a zip archive to string.
+fn read_part<R: std::io::Read + std::io::Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Result<String, LoadError> {
+    use std::io::Read;
+    let mut file = match archive.by_name(name) {
+        Ok(file) => file,
+        Err(zip::result::ZipError::FileNotFound) => {
+            return Err(LoadError::MissingPart(name.to_owned()))
+        }
+        Err(err) => return Err(err.into()),
+    };
+    let mut s = String::new();
+    file.read_to_string(&mut s)?;
+    Ok(s)
+}
+
+fn parse_part(name: &str, xml: &str) -> Result<xml::Element, LoadError> {
+    xml::parse(xml).map_err(|err| LoadError::Xml(name.to_owned(), err))
+}
+
+/// Sets the fields that `over` has.
+fn overlay(base: &mut TextFormat, over: &TextFormat) {
+    if over.foreground_color.is_some() {
+        base.foreground_color = over.foreground_color;
+    }
+    if over.font_family.is_some() {
+        base.font_family.clone_from(&over.font_family);
+    }
+    if over.font_size.is_some() {
+        base.font_size = over.font_size;
+    }
+    if over.bold.is_some() {
+        base.bold = over.bold;
+    }
+    if over.italic.is_some() {
+        base.italic = over.italic;
+    }
+    if over.strikethrough.is_some() {
+        base.strikethrough = over.strikethrough;
+    }
+    if over.underline.is_some() {
+        base.underline = over.underline;
+    }
+}
+
+/// Builds a cell in the shape the Sheets API returns: adjacent fragments with the same format are
+/// merged, and a cell with a single fragment gets its format instead of format runs.
+fn cell_data(mut format: TextFormat, fragments: Vec<(String, Option<TextFormat>)>) -> CellData {
+    // Unset fields of the cell format have the defaults of the Sheets API.
+    format.foreground_color = format.foreground_color.or(Some(Color::default()));
+    format.italic = format.italic.or(Some(false));
+    format.strikethrough = format.strikethrough.or(Some(false));
+
+    let mut merged: Vec<(String, Option<TextFormat>)> = vec![];
+    for (text, fragment_format) in fragments {
+        if text.is_empty() {
+            continue;
+        }
+        match merged.last_mut() {
+            Some((last_text, last_format)) if *last_format == fragment_format => {
+                last_text.push_str(&text);
+            }
+            _ => merged.push((text, fragment_format)),
+        }
+    }
+
+    let mut text_format_runs = vec![];
+    if merged.len() == 1 {
+        if let Some(fragment_format) = &merged[0].1 {
+            overlay(&mut format, fragment_format);
+        }
+    } else {
+        let mut start_index = 0;
+        for (text, fragment_format) in &merged {
+            text_format_runs.push(TextFormatRun {
+                start_index,
+                format: fragment_format.clone(),
+            });
+            start_index += text.encode_utf16().count();
+        }
+    }
+
+    let text: String = merged.into_iter().map(|(text, _)| text).collect();
+    CellData {
+        formatted_value: (!text.is_empty()).then_some(text),
+        effective_format: Some(CellFormat {
+            text_format: Some(format),
+            ..Default::default()
+        }),
+        text_format_runs,
+        ..Default::default()
+    }
+}
+
+fn is_empty(cell: &CellData) -> bool {
+    cell.formatted_value
+        .as_deref()
+        .unwrap_or_default()
+        .is_empty()
+}
+
+/// Puts a cell at zero-based row and column, growing the grid as needed.
+fn set_cell(rows: &mut Vec<Vec<CellData>>, row: usize, column: usize, cell: CellData) {
+    if rows.len() <= row {
+        rows.resize_with(row + 1, Vec::new);
+    }
+    let cells = &mut rows[row];
+    if cells.len() <= column {
+        cells.resize_with(column + 1, CellData::default);
+    }
+    cells[column] = cell;
+}
+
+/// One-cell-wide rich column in the shape of [`googlesheets::read_grid`] results.
+fn rich_column(rows: &[Vec<CellData>], letter: char) -> RichColumn {
+    let column = column_index(&letter.to_string()).unwrap_or_default();
+    let row_data = rows
+        .iter()
+        .map(|row| RowData {
+            values: vec![row.get(column as usize).cloned().unwrap_or_default()],
+        })
+        .collect();
+
+    let mut rich = RichColumn::from(spreadsheet::Spreadsheet {
+        spreadsheet_id: String::new(),
+        spreadsheet_url: None,
+        properties: Default::default(),
+        sheets: vec![Sheet {
+            properties: SheetProperties {
+                sheet_id: 0.into(),
+                title: SHEET_TITLE.to_owned(),
+                index: 0,
+                sheet_type: Some("GRID".to_owned()),
+                grid_properties: None,
+            },
+            data: vec![GridData {
+                start_row: FIRST_RECORD_ROW - 1,
+                start_column: column,
+                row_data,
+            }],
+            charts: Vec::new(),
+        }],
+    });
+
+    // Run colors are kept for local files only, see `rich::Format::foreground_color`.
+    let cells = rich.sheets[0].data[0].row_data.iter_mut();
+    for (rich_row, row) in cells.zip(rows) {
+        let (Some(runs), Some(cell)) = (
+            rich_row.values[0].text_format_runs.as_mut(),
+            row.get(column as usize),
+        ) else {
+            continue;
+        };
+        for (run, source) in runs.iter_mut().zip(&cell.text_format_runs) {
+            if let Some(format) = run.format.as_mut() {
+                format.foreground_color = source
+                    .format
+                    .as_ref()
+                    .and_then(|format| format.foreground_color)
+                    .map(|color| ProtobufColor::from(Some(color)));
+            }
+        }
+    }
+    rich
+}
+
+/// Spreadsheet from all rows of the records sheet, starting with the first header row.
+fn from_rows(rows: Vec<Vec<CellData>>) -> Spreadsheet {
+    let mut rows: Vec<Vec<CellData>> = rows
+        .into_iter()
+        .skip(FIRST_RECORD_ROW as usize - 1)
+        .collect();
+    while rows.last().is_some_and(|row| row.iter().all(is_empty)) {
+        rows.pop();
+    }
+
+    // Like the values API: formatted strings, trailing empty cells trimmed.
+    let values = rows
+        .iter()
+        .map(|row| {
+            let mut values: Vec<Value> = row
+                .iter()
+                .take(COLUMNS)
+                .map(|cell| Value::from(cell.formatted_value.clone().unwrap_or_default()))
+                .collect();
+            while values.last().is_some_and(|value| value == "") {
+                values.pop();
+            }
+            values
+        })
+        .collect();
+    let last_column = u32::try_from(COLUMNS).unwrap_or_default() - 1;
+    let range = A1Range::sheet(SHEET_TITLE)
+        .start(CellRef::new(0, FIRST_RECORD_ROW))
+        .end(CellRef::column(last_column));
+
+    Spreadsheet {
+        sheet: ValueRange::new(Dimension::Rows, range, values),
+        styled_columns: StyledDropsColumns {
+            drops: rich_column(&rows, DROPS_COLUMN_LETTER),
+            drops_datamined: rich_column(&rows, DROPS_DATAMINED_COLUMN_LETTER),
+            drops_verify: rich_column(&rows, DROPS_VERIFY_COLUMN_LETTER),
+        },
+    }
+}
+
+impl Spreadsheet {
+    /// Loads an XLSX export of the spreadsheet.
+    pub fn from_xlsx(bytes: &[u8]) -> Result<Spreadsheet, LoadError> {
+        Ok(from_rows(xlsx::sheet_rows(bytes, SHEET_TITLE)?))
+    }
+
+    /// Loads an ODS export of the spreadsheet.
+    pub fn from_ods(bytes: &[u8]) -> Result<Spreadsheet, LoadError> {
+        Ok(from_rows(ods::sheet_rows(bytes, SHEET_TITLE)?))
+    }
+
+    /// Loads a CSV export of the records sheet, headers included. Font styles of the drops
+    /// columns come from `styles`, a `spreadsheets.get` response with grid data. Without it all
+    /// drops are treated as plain text.
+    pub fn from_csv(csv: &str, styles: Option<&str>) -> Result<Spreadsheet, LoadError> {
+        let mut rows: Vec<Vec<CellData>> = vec![];
+        let mut reader = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .from_reader(csv.as_bytes());
+        for record in reader.records() {
+            rows.push(
+                record?
+                    .iter()
+                    .map(|text| CellData {
+                        formatted_value: Some(text.to_owned()),
+                        ..Default::default()
+                    })
+                    .collect(),
+            );
+        }
+
+        if let Some(styles) = styles {
+            let styles: spreadsheet::Spreadsheet = serde_json::from_str(styles)?;
+            let sheet = styles
+                .sheet(SHEET_TITLE)
+                .or(styles.sheets.first())
+                .ok_or_else(|| LoadError::MissingSheet(SHEET_TITLE.to_owned()))?;
+            for data in &sheet.data {
+                for (row_offset, row) in data.row_data.iter().enumerate() {
+                    for (column_offset, styled) in row.values.iter().enumerate() {
+                        let row = data.start_row as usize + row_offset;
+                        let column = data.start_column as usize + column_offset;
+                        // The CSV is the source of values, the sidecar only adds formats.
+                        let text = rows
+                            .get(row)
+                            .and_then(|cells| cells.get(column))
+                            .and_then(|cell| cell.formatted_value.clone());
+                        let mut cell = styled.clone();
+                        if text.is_some() {
+                            cell.formatted_value = text;
+                        }
+                        set_cell(&mut rows, row, column, cell);
+                    }
+                }
+            }
+        }
+
+        Ok(from_rows(rows))
+    }
+
+    /// Loads a local copy by its extension: `xlsx`, `ods` or `csv`. A CSV file picks up its style
+    /// sidecar from the same path with `styles.json` extension, if there is one.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Spreadsheet, LoadError> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default()
+            .to_lowercase();
+        match extension.as_str() {
+            "xlsx" => Spreadsheet::from_xlsx(&std::fs::read(path)?),
+            "ods" => Spreadsheet::from_ods(&std::fs::read(path)?),
+            "csv" => {
+                let csv = std::fs::read_to_string(path)?;
+                let styles = std::fs::read_to_string(path.with_extension("styles.json")).ok();
+                Spreadsheet::from_csv(&csv, styles.as_deref())
+            }
+            _ => Err(LoadError::UnsupportedFormat(extension)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spreadsheet::rich::{FontStyles, HexColor};
+    use std::io::Write;
+
+    pub(super) fn archive(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut writer = zip::ZipWriter::new(std::io::Cursor::new(vec![]));
+        for (name, content) in files {
+            writer
+                .start_file(*name, zip::write::SimpleFileOptions::default())
+                .unwrap();
+            writer.write_all(content.as_bytes()).unwrap();
+        }
+        writer.finish().unwrap().into_inner()
+    }
+
+    /// Text fragments with their fonts for each record in the drops column (G).
+    pub(super) fn drops_styles(spreadsheet: &Spreadsheet) -> Vec<Vec<(String, FontStyles)>> {
+        spreadsheet
+            .styled_columns
+            .drops
+            .cells()
+            .map(|cell| {
+                cell.text_fragments()
+                    .unwrap()
+                    .into_iter()
+                    .map(|text| (text.content, text.styles))
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub(super) fn italic() -> FontStyles {
+        FontStyles {
+            color: HexColor::Other("#000000".to_owned()),
+            italic: true,
+            strikethrough: false,
+        }
+    }
+
+    pub(super) fn plain() -> FontStyles {
+        FontStyles {
+            color: HexColor::Other("#000000".to_owned()),
+            italic: false,
+            strikethrough: false,
+        }
+    }
+
+    #[test]
+    fn loads_csv_with_styles_sidecar() {
+        let csv =
+            "header\nsubheader\nFALSE,The Doctor,,,,,Vault; Ossuary\nFALSE,Humility,,,,,Act 1\n";
+        let styles = r#"{
+            "spreadsheetId": "abc",
+            "sheets": [{
+                "properties": { "sheetId": 0, "title": "Cards_and_Hypotheses" },
+                "data": [{
+                    "startRow": 2,
+                    "startColumn": 6,
+                    "rowData": [{ "values": [{
+                        "formattedValue": "Vault; Ossuary",
+                        "effectiveFormat": { "textFormat": { "foregroundColor": {}, "italic": false, "strikethrough": false } },
+                        "textFormatRuns": [{ "format": {} }, { "startIndex": 7, "format": { "italic": true } }]
+                    }] }]
+                }]
+            }]
+        }"#;
+
+        let spreadsheet = Spreadsheet::from_csv(csv, Some(styles)).unwrap();
+        assert_eq!(spreadsheet.sheet.values.len(), 2);
+        let styles = drops_styles(&spreadsheet);
+        assert_eq!(styles[0][1], ("Ossuary".to_owned(), italic()));
+        assert_eq!(styles[1], [("Act 1".to_owned(), plain())]);
+
+        let plain_csv = Spreadsheet::from_csv(csv, None).unwrap();
+        assert_eq!(
+            drops_styles(&plain_csv)[0],
+            [("Vault; Ossuary".to_owned(), plain())]
+        );
+    }
+}
@@ -0,0 +1,305 @@
+//! OpenDocument spreadsheets (`.ods`): automatic cell and text styles and cells of one table
+//! from `content.xml`.
+
+use super::{cell_data, overlay, parse_part, read_part, xml::Element, xml::Node, LoadError};
+use googlesheets::{
+    format::Color,
+    spreadsheet::{CellData, TextFormat},
+};
+use std::{collections::HashMap, io::Cursor};
+
+/// Repeated columns past this are not expanded. Exports pad tables to the maximum width.
+const MAX_COLUMNS: usize = 1024;
+
+/// `#rrggbb`
+fn hex_color(hex: &str) -> Option<Color> {
+    let hex = hex.strip_prefix('#')?;
+    let component = |i: usize| {
+        u8::from_str_radix(hex.get(i..i + 2)?, 16)
+            .ok()
+            .map(|c| f32::from(c) / 255.0)
+    };
+    Some(Color::rgb(component(0)?, component(2)?, component(4)?))
+}
+
+/// Only properties that the style sets, the rest is inherited.
+fn text_properties(properties: &Element) -> TextFormat {
+    TextFormat {
+        foreground_color: properties.attr("fo:color").and_then(hex_color),
+        font_family: properties.attr("style:font-name").map(str::to_owned),
+        font_size: properties
+            .attr("fo:font-size")
+            .and_then(|size| size.trim_end_matches("pt").parse::<f32>().ok())
+            .map(|size| size.round() as u32),
+        bold: properties
+            .attr("fo:font-weight")
+            .map(|weight| weight == "bold" || weight.parse::<u32>().is_ok_and(|w| w >= 600)),
+        italic: properties
+            .attr("fo:font-style")
+            .map(|style| style == "italic" || style == "oblique"),
+        strikethrough: properties
+            .attr("style:text-line-through-style")
+            .map(|style| style != "none"),
+        underline: properties
+            .attr("style:text-underline-style")
+            .map(|style| style != "none"),
+        link: None,
+    }
+}
+
+/// Text properties of automatic styles by style name, both cell and text styles.
+fn automatic_styles(content: &Element) -> HashMap<String, TextFormat> {
+    content
+        .child("automatic-styles")
+        .map(|styles| {
+            styles
+                .children_named("style")
+                .filter_map(|style| {
+                    let properties = style.child("text-properties")?;
+                    Some((
+                        style.attr("style:name")?.to_owned(),
+                        text_properties(properties),
+                    ))
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn repeated(element: &Element, attr: &str) -> usize {
+    element.attr(attr).and_then(|n| n.parse().ok()).unwrap_or(1)
+}
+
+/// Appends text of a paragraph's children, spans get their style on top of `format`.
+fn paragraph_fragments(
+    element: &Element,
+    format: Option<&TextFormat>,
+    styles: &HashMap<String, TextFormat>,
+    fragments: &mut Vec<(String, Option<TextFormat>)>,
+) {
+    for node in &element.children {
+        match node {
+            Node::Text(text) => fragments.push((text.clone(), format.cloned())),
+            Node::Element(child) => match child.name.as_str() {
+                "text:s" => {
+                    fragments.push((" ".repeat(repeated(child, "text:c")), format.cloned()));
+                }
+                "text:tab" => fragments.push(("\t".to_owned(), format.cloned())),
+                "text:line-break" => fragments.push(("\n".to_owned(), format.cloned())),
+                "text:span" => {
+                    let span_format = child
+                        .attr("text:style-name")
+                        .and_then(|name| styles.get(name))
+                        .map(|span| {
+                            let mut merged = format.cloned().unwrap_or_default();
+                            overlay(&mut merged, span);
+                            merged
+                        });
+                    paragraph_fragments(child, span_format.as_ref().or(format), styles, fragments);
+                }
+                // Links and other inline elements keep the surrounding format.
+                _ => paragraph_fragments(child, format, styles, fragments),
+            },
+        }
+    }
+}
+
+fn cell(
+    element: &Element,
+    column_style: Option<&str>,
+    styles: &HashMap<String, TextFormat>,
+) -> CellData {
+    let format = element
+        .attr("table:style-name")
+        .or(column_style)
+        .and_then(|name| styles.get(name))
+        .cloned()
+        .unwrap_or_default();
+
+    let mut fragments = vec![];
+    // Paragraphs are lines, annotations (comments) are skipped.
+    for (i, paragraph) in element
+        .elements()
+        .filter(|child| child.name == "text:p" || child.name == "text:h")
+        .enumerate()
+    {
+        if i > 0 {
+            fragments.push(("\n".to_owned(), None));
+        }
+        paragraph_fragments(paragraph, None, styles, &mut fragments);
+    }
+
+    cell_data(format, fragments)
+}
+
+/// Default cell style of each column.
+fn column_styles(table: &Element) -> Vec<Option<String>> {
+    let mut styles = vec![];
+    for element in table.elements() {
+        match element.name.as_str() {
+            "table:table-column" => {
+                let style = element
+                    .attr("table:default-cell-style-name")
+                    .map(str::to_owned);
+                let n = repeated(element, "table:number-columns-repeated")
+                    .min(MAX_COLUMNS.saturating_sub(styles.len()));
+                styles.extend(std::iter::repeat_n(style, n));
+            }
+            "table:table-columns" | "table:table-header-columns" | "table:table-column-group" => {
+                styles.extend(column_styles(element));
+            }
+            _ => {}
+        }
+    }
+    styles
+}
+
+/// Rows of a table, including header rows and row groups.
+fn row_elements(table: &Element) -> Vec<&Element> {
+    let mut rows = vec![];
+    for element in table.elements() {
+        match element.name.as_str() {
+            "table:table-row" => rows.push(element),
+            "table:table-header-rows" | "table:table-rows" | "table:table-row-group" => {
+                rows.extend(row_elements(element));
+            }
+            _ => {}
+        }
+    }
+    rows
+}
+
+/// All cells of the table by zero-based row and column.
+pub(super) fn sheet_rows(bytes: &[u8], title: &str) -> Result<Vec<Vec<CellData>>, LoadError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+    let content = parse_part("content.xml", &read_part(&mut archive, "content.xml")?)?;
+    let styles = automatic_styles(&content);
+
+    let table = content
+        .child("body")
+        .and_then(|body| body.child("spreadsheet"))
+        .and_then(|spreadsheet| {
+            spreadsheet
+                .children_named("table")
+                .find(|table| table.attr("table:name") == Some(title))
+        })
+        .ok_or_else(|| LoadError::MissingSheet(title.to_owned()))?;
+    let column_styles = column_styles(table);
+
+    let mut rows: Vec<Vec<CellData>> = vec![];
+    // Repeated empty rows and cells are only added once something follows them,
+    // exports end tables with a million empty rows.
+    let mut pending_rows = 0;
+    for row in row_elements(table) {
+        let mut cells: Vec<CellData> = vec![];
+        let mut pending_cells = 0;
+        for element in row.elements().filter(|child| {
+            child.name == "table:table-cell" || child.name == "table:covered-table-cell"
+        }) {
+            let column = cells.len() + pending_cells;
+            let column_style = column_styles.get(column).and_then(Option::as_deref);
+            let cell = cell(element, column_style, &styles);
+            let n = repeated(element, "table:number-columns-repeated");
+            if super::is_empty(&cell) {
+                pending_cells += n;
+            } else {
+                cells.extend(std::iter::repeat_n(CellData::default(), pending_cells));
+                pending_cells = 0;
+                cells.extend(std::iter::repeat_n(cell, n.min(MAX_COLUMNS)));
+            }
+        }
+
+        let n = repeated(row, "table:number-rows-repeated");
+        if cells.is_empty() {
+            pending_rows += n;
+        } else {
+            rows.extend(std::iter::repeat_n(vec![], pending_rows));
+            pending_rows = 0;
+            rows.extend(std::iter::repeat_n(cells, n));
+        }
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::{archive, drops_styles, italic, plain};
+    use crate::{
+        spreadsheet::rich::{FontStyles, HexColor},
+        Spreadsheet,
+    };
+
+    #[test]
+    fn loads_ods() {
+        let ods = archive(&[(
+            "content.xml",
+            r##"<office:document-content>
+                <office:automatic-styles>
+                    <style:style style:name="ce1" style:family="table-cell">
+                        <style:text-properties style:text-line-through-style="solid"/>
+                    </style:style>
+                    <style:style style:name="T1" style:family="text">
+                        <style:text-properties fo:font-style="italic"/>
+                    </style:style>
+                    <style:style style:name="T2" style:family="text">
+                        <style:text-properties fo:color="#00ff00" style:text-line-through-style="solid"/>
+                    </style:style>
+                </office:automatic-styles>
+                <office:body><office:spreadsheet>
+                    <table:table table:name="Cards_and_Hypotheses">
+                        <table:table-column table:number-columns-repeated="26"/>
+                        <table:table-header-rows>
+                            <table:table-row><table:table-cell><text:p>header</text:p></table:table-cell></table:table-row>
+                        </table:table-header-rows>
+                        <table:table-row table:number-rows-repeated="1"><table:table-cell table:number-columns-repeated="26"/></table:table-row>
+                        <table:table-row>
+                            <table:table-cell/>
+                            <table:table-cell office:value-type="string"><text:p>The Doctor</text:p></table:table-cell>
+                            <table:table-cell table:number-columns-repeated="4"/>
+                            <table:table-cell><office:annotation><text:p>note</text:p></office:annotation><text:p>Vault;<text:s/><text:span text:style-name="T1">Ossuary</text:span></text:p></table:table-cell>
+                        </table:table-row>
+                        <table:table-row>
+                            <table:table-cell table:number-columns-repeated="6"/>
+                            <table:table-cell table:style-name="ce1"><text:p>Act 1</text:p></table:table-cell>
+                        </table:table-row>
+                        <table:table-row>
+                            <table:table-cell table:number-columns-repeated="6"/>
+                            <table:table-cell><text:p>Dunes;<text:s/><text:span text:style-name="T2">Act 2</text:span></text:p></table:table-cell>
+                        </table:table-row>
+                        <table:table-row table:number-rows-repeated="1048570"><table:table-cell table:number-columns-repeated="1024"/></table:table-row>
+                    </table:table>
+                </office:spreadsheet></office:body>
+            </office:document-content>"##,
+        )]);
+
+        let spreadsheet = Spreadsheet::from_ods(&ods).unwrap();
+        assert_eq!(spreadsheet.sheet.values.len(), 3);
+        assert_eq!(spreadsheet.sheet.values[0][1], "The Doctor");
+        assert_eq!(spreadsheet.sheet.values[0][6], "Vault; Ossuary");
+
+        let styles = drops_styles(&spreadsheet);
+        assert_eq!(
+            styles[0],
+            [
+                ("Vault; ".to_owned(), plain()),
+                ("Ossuary".to_owned(), italic())
+            ]
+        );
+        assert!(styles[1][0].1.strikethrough);
+        assert_eq!(
+            styles[2],
+            [
+                ("Dunes; ".to_owned(), plain()),
+                (
+                    "Act 2".to_owned(),
+                    FontStyles {
+                        color: HexColor::Other("#00FF00".to_owned()),
+                        strikethrough: true,
+                        ..plain()
+                    }
+                ),
+            ]
+        );
+    }
+}
@@ -0,0 +1,304 @@
+//! Office Open XML workbooks (`.xlsx`): shared strings with rich text runs, cell fonts from
+//! `styles.xml` and cells of one worksheet.
+
+use super::{cell_data, parse_part, read_part, set_cell, xml::Element, LoadError};
+use googlesheets::{
+    format::Color,
+    range::column_index,
+    spreadsheet::{CellData, TextFormat},
+};
+use std::io::Cursor;
+
+/// Text with formatted fragments.
+type RichText = Vec<(String, Option<TextFormat>)>;
+
+/// `FFRRGGBB` or `RRGGBB`. Theme and indexed colors are not resolved.
+fn argb(hex: &str) -> Option<Color> {
+    let rgb = hex.get(hex.len().checked_sub(6)?..)?;
+    let component = |i: usize| {
+        u8::from_str_radix(rgb.get(i..i + 2)?, 16)
+            .ok()
+            .map(|c| f32::from(c) / 255.0)
+    };
+    Some(Color::rgb(component(0)?, component(2)?, component(4)?))
+}
+
+/// Font of a `<font>` in styles or `<rPr>` of a run. Missing flags are off.
+fn font(element: &Element) -> TextFormat {
+    let flag = |name: &str| {
+        Some(
+            element
+                .child(name)
+                .is_some_and(|flag| !matches!(flag.attr("val"), Some("0" | "false"))),
+        )
+    };
+    let val = |name: &str| element.child(name).and_then(|child| child.attr("val"));
+
+    TextFormat {
+        foreground_color: Some(
+            element
+                .child("color")
+                .and_then(|color| color.attr("rgb"))
+                .and_then(argb)
+                .unwrap_or_default(),
+        ),
+        font_family: val("name").or_else(|| val("rFont")).map(str::to_owned),
+        font_size: val("sz")
+            .and_then(|size| size.parse::<f32>().ok())
+            .map(|size| size.round() as u32),
+        bold: flag("b"),
+        italic: flag("i"),
+        strikethrough: flag("strike"),
+        underline: Some(
+            element
+                .child("u")
+                .is_some_and(|underline| underline.attr("val") != Some("none")),
+        ),
+        link: None,
+    }
+}
+
+/// `<si>` of shared strings or `<is>` of an inline string.
+fn rich_text(element: &Element) -> RichText {
+    element
+        .elements()
+        .filter_map(|child| match child.local_name() {
+            "t" => Some((child.text(), None)),
+            "r" => Some((
+                child.child("t").map(Element::text).unwrap_or_default(),
+                child.child("rPr").map(font),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Font of every cell format (`s` attribute of a cell).
+fn cell_fonts(styles: &Element) -> Vec<TextFormat> {
+    let fonts: Vec<TextFormat> = styles
+        .child("fonts")
+        .map(|fonts| fonts.children_named("font").map(font).collect())
+        .unwrap_or_default();
+    styles
+        .child("cellXfs")
+        .map(|xfs| {
+            xfs.children_named("xf")
+                .map(|xf| {
+                    xf.attr("fontId")
+                        .and_then(|id| id.parse::<usize>().ok())
+                        .and_then(|id| fonts.get(id).cloned())
+                        .unwrap_or_default()
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// Path of the worksheet in the archive.
+fn sheet_path(
+    archive: &mut zip::ZipArchive<Cursor<&[u8]>>,
+    title: &str,
+) -> Result<String, LoadError> {
+    let workbook = parse_part("workbook", &read_part(archive, "xl/workbook.xml")?)?;
+    let relationship_id = workbook
+        .child("sheets")
+        .and_then(|sheets| {
+            sheets
+                .children_named("sheet")
+                .find(|sheet| sheet.attr("name") == Some(title))
+        })
+        .and_then(|sheet| {
+            sheet
+                .attributes
+                .iter()
+                .find(|(name, _)| name.ends_with(":id"))
+                .map(|(_, id)| id.clone())
+        })
+        .ok_or_else(|| LoadError::MissingSheet(title.to_owned()))?;
+
+    let relationships = parse_part(
+        "workbook relationships",
+        &read_part(archive, "xl/_rels/workbook.xml.rels")?,
+    )?;
+    let target = relationships
+        .children_named("Relationship")
+        .find(|relationship| relationship.attr("Id") == Some(relationship_id.as_str()))
+        .and_then(|relationship| relationship.attr("Target"))
+        .ok_or_else(|| LoadError::MissingSheet(title.to_owned()))?;
+
+    Ok(match target.strip_prefix('/') {
+        Some(absolute) => absolute.to_owned(),
+        None => format!("xl/{target}"),
+    })
+}
+
+/// All cells of the sheet by zero-based row and column.
+pub(super) fn sheet_rows(bytes: &[u8], title: &str) -> Result<Vec<Vec<CellData>>, LoadError> {
+    let mut archive = zip::ZipArchive::new(Cursor::new(bytes))?;
+    let path = sheet_path(&mut archive, title)?;
+
+    let shared_strings: Vec<RichText> = match read_part(&mut archive, "xl/sharedStrings.xml") {
+        Ok(xml) => parse_part("shared strings", &xml)?
+            .children_named("si")
+            .map(rich_text)
+            .collect(),
+        // Workbooks with inline strings only have no shared strings.
+        Err(LoadError::MissingPart(_)) => vec![],
+        Err(err) => return Err(err),
+    };
+    let fonts = match read_part(&mut archive, "xl/styles.xml") {
+        Ok(xml) => cell_fonts(&parse_part("styles", &xml)?),
+        Err(LoadError::MissingPart(_)) => vec![],
+        Err(err) => return Err(err),
+    };
+    let worksheet = parse_part(&path, &read_part(&mut archive, &path)?)?;
+
+    let mut rows: Vec<Vec<CellData>> = vec![];
+    let Some(sheet_data) = worksheet.child("sheetData") else {
+        return Ok(rows);
+    };
+    let mut row_index = 0;
+    for row in sheet_data.children_named("row") {
+        // `r` is optional, rows without it follow the previous one.
+        row_index = row
+            .attr("r")
+            .and_then(|r| r.parse::<usize>().ok())
+            .filter(|r| *r > 0)
+            .map_or(row_index + 1, |r| r);
+        let mut column = 0;
+        for cell in row.children_named("c") {
+            column = cell
+                .attr("r")
+                .and_then(|r| column_index(r.trim_end_matches(|ch: char| ch.is_ascii_digit())))
+                .map_or(column + 1, |index| index as usize + 1);
+
+            let value = || cell.child("v").map(Element::text).unwrap_or_default();
+            let text: RichText = match cell.attr("t") {
+                Some("s") => value()
+                    .parse::<usize>()
+                    .ok()
+                    .and_then(|index| shared_strings.get(index).cloned())
+                    .unwrap_or_default(),
+                Some("inlineStr") => cell.child("is").map(rich_text).unwrap_or_default(),
+                Some("b") => vec![(
+                    if value() == "1" { "TRUE" } else { "FALSE" }.to_owned(),
+                    None,
+                )],
+                _ => vec![(value(), None)],
+            };
+            // Cells without `s` have the first (default) format.
+            let format = cell
+                .attr("s")
+                .map_or(Some(0), |s| s.parse::<usize>().ok())
+                .and_then(|s| fonts.get(s).cloned())
+                .unwrap_or_default();
+
+            set_cell(
+                &mut rows,
+                row_index - 1,
+                column - 1,
+                cell_data(format, text),
+            );
+        }
+    }
+
+    Ok(rows)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::{
+        tests::{archive, drops_styles, italic, plain},
+        LoadError,
+    };
+    use crate::{
+        spreadsheet::rich::{FontStyles, HexColor},
+        Spreadsheet,
+    };
+
+    #[test]
+    fn loads_xlsx() {
+        let xlsx = archive(&[
+            (
+                "xl/workbook.xml",
+                r#"<workbook xmlns:r="rels"><sheets>
+                    <sheet name="Intro" sheetId="1" r:id="rId1"/>
+                    <sheet name="Cards_and_Hypotheses" sheetId="2" r:id="rId2"/>
+                </sheets></workbook>"#,
+            ),
+            (
+                "xl/_rels/workbook.xml.rels",
+                r#"<Relationships>
+                    <Relationship Id="rId1" Target="worksheets/sheet1.xml"/>
+                    <Relationship Id="rId2" Target="/xl/worksheets/sheet2.xml"/>
+                </Relationships>"#,
+            ),
+            (
+                "xl/styles.xml",
+                r#"<styleSheet>
+                    <fonts><font><name val="Arial"/></font><font><strike/><color rgb="FFFF0000"/></font></fonts>
+                    <cellXfs><xf fontId="0"/><xf fontId="1"/></cellXfs>
+                </styleSheet>"#,
+            ),
+            (
+                "xl/sharedStrings.xml",
+                r#"<sst>
+                    <si><t>The Doctor</t></si>
+                    <si><r><t xml:space="preserve">Vault; </t></r><r><rPr><i/></rPr><t>Ossuary</t></r></si>
+                    <si><r><rPr><color rgb="FF00FF00"/></rPr><t xml:space="preserve">Dunes; </t></r><r><rPr><strike/><i val="0"/></rPr><t>Act 2</t></r></si>
+                </sst>"#,
+            ),
+            (
+                "xl/worksheets/sheet2.xml",
+                r#"<worksheet><sheetData>
+                    <row r="1"><c r="A1" t="inlineStr"><is><t>header</t></is></c></row>
+                    <row r="3"><c r="B3" t="s"><v>0</v></c><c r="G3" t="s"><v>1</v></c></row>
+                    <row r="4"><c r="B4" t="str"><v>Humility</v></c><c r="G4" s="1" t="str"><v>Act 1</v></c></row>
+                    <row r="5"><c r="B5" t="inlineStr"><is><t>Rain of Chaos</t></is></c><c r="G5" t="s"><v>2</v></c></row>
+                </sheetData></worksheet>"#,
+            ),
+        ]);
+
+        let spreadsheet = Spreadsheet::from_xlsx(&xlsx).unwrap();
+        assert_eq!(spreadsheet.sheet.values.len(), 3);
+        assert_eq!(spreadsheet.sheet.values[0][1], "The Doctor");
+        assert_eq!(spreadsheet.sheet.values[0][6], "Vault; Ossuary");
+        assert_eq!(spreadsheet.sheet.values[1].len(), 7);
+
+        let styles = drops_styles(&spreadsheet);
+        assert_eq!(
+            styles[0],
+            [
+                ("Vault; ".to_owned(), plain()),
+                ("Ossuary".to_owned(), italic())
+            ]
+        );
+        let strikethrough = &styles[1][0].1;
+        assert!(strikethrough.strikethrough);
+        assert_eq!(strikethrough.color, HexColor::Other("#FF0000".to_owned()));
+        assert_eq!(
+            styles[2],
+            [
+                (
+                    "Dunes; ".to_owned(),
+                    FontStyles {
+                        color: HexColor::Other("#00FF00".to_owned()),
+                        ..plain()
+                    }
+                ),
+                (
+                    "Act 2".to_owned(),
+                    FontStyles {
+                        strikethrough: true,
+                        ..plain()
+                    }
+                ),
+            ]
+        );
+
+        assert!(matches!(
+            Spreadsheet::from_xlsx(&archive(&[])),
+            Err(LoadError::MissingPart(_))
+        ));
+    }
+}
@@ -0,0 +1,185 @@
+//! XML tree of the parts of XLSX and ODS archives, read with `quick-xml`.
+//! Names keep their namespace prefixes, DTDs and processing instructions are skipped.
+
+use quick_xml::{
+    events::{BytesStart, Event},
+    Reader,
+};
+use std::fmt::Display;
+
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Element {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<Node>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Node {
+    Element(Element),
+    Text(String),
+}
+
+impl Element {
+    /// Name without the namespace prefix: `t` for `x:t`.
+    pub fn local_name(&self) -> &str {
+        self.name
+            .rsplit_once(':')
+            .map_or(self.name.as_str(), |(_, local)| local)
+    }
+
+    /// Attribute by its full name, e.g. `table:name`.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(attr, _)| attr == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        self.children.iter().filter_map(|node| match node {
+            Node::Element(element) => Some(element),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// Child elements by local name.
+    pub fn children_named<'a>(&'a self, local_name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.elements()
+            .filter(move |element| element.local_name() == local_name)
+    }
+
+    pub fn child(&self, local_name: &str) -> Option<&Element> {
+        self.elements()
+            .find(|element| element.local_name() == local_name)
+    }
+
+    /// Text of all descendants.
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for node in &self.children {
+            match node {
+                Node::Text(s) => text.push_str(s),
+                Node::Element(element) => text.push_str(&element.text()),
+            }
+        }
+        text
+    }
+}
+
+#[derive(Debug)]
+pub struct XmlError {
+    /// Byte offset in the document.
+    pub position: usize,
+    pub message: String,
+}
+
+impl Display for XmlError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} at byte {}", self.message, self.position)
+    }
+}
+
+/// Parses the document into its root element.
+pub fn parse(xml: &str) -> Result<Element, XmlError> {
+    let mut reader = Reader::from_str(xml);
+    let error = |reader: &Reader<&[u8]>, message: String| XmlError {
+        position: reader.buffer_position(),
+        message,
+    };
+    let mut stack: Vec<Element> = vec![];
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|err| error(&reader, err.to_string()))?;
+        let (element, closed) = match event {
+            Event::Start(start) => (element(&start).map_err(|err| error(&reader, err))?, false),
+            Event::Empty(start) => (element(&start).map_err(|err| error(&reader, err))?, true),
+            Event::End(_) => {
+                let element = stack
+                    .pop()
+                    .ok_or_else(|| error(&reader, "Closing tag without opening tag".to_owned()))?;
+                (element, true)
+            }
+            Event::Text(text) => {
+                let text = text
+                    .unescape()
+                    .map_err(|err| error(&reader, err.to_string()))?;
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(Node::Text(text.into_owned()));
+                }
+                continue;
+            }
+            Event::CData(cdata) => {
+                let text = String::from_utf8_lossy(&cdata.into_inner()).into_owned();
+                if let Some(parent) = stack.last_mut() {
+                    parent.children.push(Node::Text(text));
+                }
+                continue;
+            }
+            Event::Eof => return Err(error(&reader, "Missing root element".to_owned())),
+            Event::Comment(_) | Event::Decl(_) | Event::PI(_) | Event::DocType(_) => continue,
+        };
+
+        if !closed {
+            stack.push(element);
+            continue;
+        }
+        match stack.last_mut() {
+            Some(parent) => parent.children.push(Node::Element(element)),
+            None => return Ok(element),
+        }
+    }
+}
+
+/// Element of the opening tag, without children.
+fn element(start: &BytesStart) -> Result<Element, String> {
+    let name = String::from_utf8_lossy(start.name().as_ref()).into_owned();
+    let attributes = start
+        .attributes()
+        .map(|attr| {
+            let attr = attr.map_err(|err| err.to_string())?;
+            let value = attr.unescape_value().map_err(|err| err.to_string())?;
+            Ok((
+                String::from_utf8_lossy(attr.key.as_ref()).into_owned(),
+                value.into_owned(),
+            ))
+        })
+        .collect::<Result<_, String>>()?;
+    Ok(Element {
+        name,
+        attributes,
+        children: vec![],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_tree() {
+        let root = parse(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <!-- comment -->
+            <sst xmlns="main" count='2'>
+                <si><t>Tom &amp; Jerry &#x2019;s</t></si>
+                <si><r><rPr><i/></rPr><t xml:space="preserve"> italic</t></r><![CDATA[<raw>]]></si>
+            </sst>"#,
+        )
+        .unwrap();
+        assert_eq!(root.name, "sst");
+        assert_eq!(root.attr("count"), Some("2"));
+
+        let items: Vec<&Element> = root.children_named("si").collect();
+        assert_eq!(items[0].text(), "Tom & Jerry \u{2019}s");
+        assert_eq!(items[1].text(), " italic<raw>");
+        let run = items[1].child("r").unwrap();
+        assert!(run.child("rPr").unwrap().child("i").is_some());
+        assert_eq!(run.child("t").unwrap().attr("xml:space"), Some("preserve"));
+
+        assert!(parse("<a><b></a>").is_err());
+        assert!(parse("").is_err());
+    }
+}
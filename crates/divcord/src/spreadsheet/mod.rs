@@ -1,6 +1,7 @@
 //! Defines and loads [Divcord Spreadsheet](https://docs.google.com/spreadsheets/d/1Pf2KNuGguZLyf6eu_R0E503U0QNyfMZqaRETsN5g6kU/edit?pli=1#gid=0)
 
 pub mod fs_cache_fetcher;
+pub mod local;
 pub mod record;
 pub mod rich;
 
@@ -16,6 +17,10 @@ use serde::{Deserialize, Serialize};
 pub const DROPS_COLUMN_LETTER: char = 'G';
 pub const DROPS_DATAMINED_COLUMN_LETTER: char = 'H';
 pub const DROPS_VERIFY_COLUMN_LETTER: char = 'I';
+/// Title of the sheet with records.
+pub const SHEET_TITLE: &str = "Cards_and_Hypotheses";
+/// Records start at the third row, after the headers.
+pub const FIRST_RECORD_ROW: u32 = 3;
 
 /// [Divcord Spreadsheet](https://docs.google.com/spreadsheets/d/1Pf2KNuGguZLyf6eu_R0E503U0QNyfMZqaRETsN5g6kU/edit?pli=1#gid=0)
 /// This simple struct consist of api results:
@@ -57,7 +62,11 @@ impl Spreadsheet {
     }
 
     /// Fetch fresh spreadsheet data.
-    pub async fn fetch(google_api_key: &str) -> Result<Spreadsheet, reqwest::Error> {
+    ///
+    /// Returns `googlesheets::error::Error` instead of `reqwest::Error` since the Sheets API
+    /// errors are reported too. Network failures are `Error::ReqwestError`, also available
+    /// through `std::error::Error::source`.
+    pub async fn fetch(google_api_key: &str) -> Result<Spreadsheet, googlesheets::error::Error> {
         fetch_spreadsheet(google_api_key).await
     }
 
@@ -92,14 +101,18 @@ impl Spreadsheet {
 mod fetch {
     use crate::spreadsheet::{
         StyledDropsColumns, DROPS_COLUMN_LETTER, DROPS_DATAMINED_COLUMN_LETTER,
-        DROPS_VERIFY_COLUMN_LETTER,
+        DROPS_VERIFY_COLUMN_LETTER, FIRST_RECORD_ROW, SHEET_TITLE,
     };
 
     use super::{rich::RichColumn, Spreadsheet};
-    use googlesheets::sheet::ValueRange;
+    use googlesheets::{
+        error::Error, range::column_index, sheet::Credential, sheet::ValueRange, A1Range, CellRef,
+    };
+
+    const SPREADSHEET_ID: &str = "1Pf2KNuGguZLyf6eu_R0E503U0QNyfMZqaRETsN5g6kU";
 
     /// Fetch fresh spreadsheet data.
-    pub async fn fetch_spreadsheet(google_api_key: &str) -> Result<Spreadsheet, reqwest::Error> {
+    pub async fn fetch_spreadsheet(google_api_key: &str) -> Result<Spreadsheet, Error> {
         let sheet = fetch_table_sheet(google_api_key).await?;
         let number_of_rows = sheet.values.len();
         let styled_columns = fetch_styled_columns(google_api_key, number_of_rows).await?;
@@ -113,7 +126,7 @@ mod fetch {
     async fn fetch_styled_columns(
         google_api_key: &str,
         number_of_rows: usize,
-    ) -> Result<StyledDropsColumns, reqwest::Error> {
+    ) -> Result<StyledDropsColumns, Error> {
         let drops_rich = fetch_rich_column(google_api_key, DROPS_COLUMN_LETTER).await?;
         let drops_datamined_rich =
             fetch_rich_column(google_api_key, DROPS_DATAMINED_COLUMN_LETTER).await?;
@@ -131,26 +144,29 @@ mod fetch {
         })
     }
 
-    async fn fetch_table_sheet(google_api_key: &str) -> Result<ValueRange, reqwest::Error> {
-        let spreadsheet_id = "1Pf2KNuGguZLyf6eu_R0E503U0QNyfMZqaRETsN5g6kU";
-        let sheet = "Cards_and_Hypotheses";
-        let range = format!("{sheet}!A3:Z");
-
-        let url = format!(
-        "https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}/values/{range}?key={google_api_key}"
-    );
-        let value_range: ValueRange = reqwest::get(url).await?.error_for_status()?.json().await?;
-        Ok(value_range)
+    async fn fetch_table_sheet(google_api_key: &str) -> Result<ValueRange, Error> {
+        let range = A1Range::sheet(SHEET_TITLE)
+            .start(CellRef::new(0, FIRST_RECORD_ROW))
+            .end(CellRef::column(column_index("Z").unwrap_or_default()));
+        googlesheets::read(
+            SPREADSHEET_ID,
+            &range,
+            Credential::ApiKey(google_api_key.to_owned()),
+        )
+        .await
     }
 
-    async fn fetch_rich_column(
-        google_api_key: &str,
-        letter: char,
-    ) -> Result<RichColumn, reqwest::Error> {
-        let spreadsheet_id = "1Pf2KNuGguZLyf6eu_R0E503U0QNyfMZqaRETsN5g6kU";
-        let sheet = "Cards_and_Hypotheses";
-        let column = letter;
-        let url = format!("https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}?&ranges={sheet}!{column}3:{column}&includeGridData=true&key={google_api_key}");
-        reqwest::get(url).await?.error_for_status()?.json().await
+    async fn fetch_rich_column(google_api_key: &str, letter: char) -> Result<RichColumn, Error> {
+        let column = column_index(&letter.to_string()).unwrap_or_default();
+        let range = A1Range::sheet(SHEET_TITLE)
+            .start(CellRef::new(column, FIRST_RECORD_ROW))
+            .end(CellRef::column(column));
+        let spreadsheet = googlesheets::read_grid(
+            SPREADSHEET_ID,
+            &[range],
+            Credential::ApiKey(google_api_key.to_owned()),
+        )
+        .await?;
+        Ok(RichColumn::from(spreadsheet))
     }
 }
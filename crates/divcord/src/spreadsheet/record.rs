@@ -3,7 +3,7 @@
 use std::fmt::Display;
 
 use super::rich::{DropsFrom, ParseCellError};
-use crate::{dropsource::Source, spreadsheet::StyledDropsCells};
+use crate::{annotation::Annotations, dropsource::Source, spreadsheet::StyledDropsCells};
 use divi::cards::CheckCardName;
 use serde::{Deserialize, Serialize};
 use serde_json::{Error as SerdeJsonError, Value};
@@ -25,6 +25,12 @@ pub struct Record {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub notes: Option<String>,
     pub verify_sources: Vec<Source>,
+    /// Tags, sources and links found in [`Record::tag_hypothesis`].
+    #[serde(default, skip_serializing_if = "Annotations::is_empty")]
+    pub tag_hypothesis_annotations: Annotations,
+    /// Tags, sources and links found in [`Record::notes`].
+    #[serde(default, skip_serializing_if = "Annotations::is_empty")]
+    pub notes_annotations: Annotations,
 }
 
 /// Represents spreadsheet's row after the initial preparation (but before the main parsing).
@@ -423,3 +429,21 @@ pub enum RemainingWork {
     #[serde(rename = "story")]
     Story,
 }
+
+/// Done record of the card with the sources and nothing else, for tests.
+#[cfg(test)]
+pub(crate) fn record(id: usize, card: &str, sources: Vec<Source>) -> Record {
+    Record {
+        id,
+        greynote: GreyNote::Empty,
+        card: card.to_owned(),
+        tag_hypothesis: None,
+        confidence: Confidence::Done,
+        remaining_work: RemainingWork::NotApplicable,
+        sources,
+        notes: None,
+        verify_sources: vec![],
+        tag_hypothesis_annotations: Annotations::default(),
+        notes_annotations: Annotations::default(),
+    }
+}
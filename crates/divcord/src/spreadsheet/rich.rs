@@ -1,5 +1,6 @@
 //! Break a spreadsheet cell into styled text fragments
 
+use googlesheets::{format::Color, spreadsheet};
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 
@@ -38,6 +39,81 @@ impl RichColumn {
     }
 }
 
+/// Keeps the cached shape of the column while the fetch goes through [`googlesheets::read_grid`].
+impl From<spreadsheet::Spreadsheet> for RichColumn {
+    fn from(spreadsheet: spreadsheet::Spreadsheet) -> Self {
+        RichColumn {
+            sheets: spreadsheet.sheets.into_iter().map(Sheet::from).collect(),
+        }
+    }
+}
+
+impl From<spreadsheet::Sheet> for Sheet {
+    fn from(sheet: spreadsheet::Sheet) -> Self {
+        let properties = sheet.properties;
+        Sheet {
+            data: sheet.data.into_iter().map(Data::from).collect(),
+            properties: Properties {
+                sheet_id: u32::from(properties.sheet_id) as usize,
+                title: properties.title,
+                index: properties.index as usize,
+                sheet_type: properties.sheet_type.unwrap_or_default(),
+            },
+        }
+    }
+}
+
+impl From<spreadsheet::GridData> for Data {
+    fn from(data: spreadsheet::GridData) -> Self {
+        Data {
+            start_column: Some(data.start_column as usize),
+            row_data: data
+                .row_data
+                .into_iter()
+                .map(|row| RowData {
+                    values: row.values.into_iter().map(Cell::from).collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+impl From<spreadsheet::CellData> for Cell {
+    fn from(cell: spreadsheet::CellData) -> Self {
+        let format = cell.effective_format.unwrap_or_default();
+        let text_format = format.text_format.unwrap_or_default();
+        Cell {
+            effective_format: EffectiveFormat {
+                background_color: format.background_color.into(),
+                text_format: TextFormat {
+                    color: text_format.foreground_color.into(),
+                    italic: text_format.italic.unwrap_or_default(),
+                    strikethrough: text_format.strikethrough.unwrap_or_default(),
+                },
+            },
+            text_content: cell.formatted_value,
+            text_format_runs: (!cell.text_format_runs.is_empty()).then(|| {
+                cell.text_format_runs
+                    .into_iter()
+                    .map(|run| {
+                        let format = run.format.unwrap_or_default();
+                        TextFormatRun {
+                            start_index: Some(run.start_index),
+                            font_family: format.font_family,
+                            format: Some(Format {
+                                italic: format.italic,
+                                strikethrough: format.strikethrough,
+                                // Runs of the online sheet keep the cell color.
+                                foreground_color: None,
+                            }),
+                        }
+                    })
+                    .collect()
+            }),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "camelCase")]
 pub struct Sheet {
@@ -231,6 +307,16 @@ pub struct ProtobufColor {
     red: Option<f32>,
 }
 
+impl From<Option<Color>> for ProtobufColor {
+    fn from(color: Option<Color>) -> Self {
+        ProtobufColor {
+            blue: color.map(|c| c.blue),
+            green: color.map(|c| c.green),
+            red: color.map(|c| c.red),
+        }
+    }
+}
+
 impl ProtobufColor {
     pub fn into_hexcolor(self) -> HexColor {
         let red_frac = self.red.unwrap_or(0.0);
@@ -405,7 +491,11 @@ impl TextFormatRun {
 
     pub fn styles(&self, parent_styles: &FontStyles) -> FontStyles {
         FontStyles {
-            color: parent_styles.color.to_owned(),
+            color: self
+                .format
+                .as_ref()
+                .and_then(|format| format.foreground_color.clone())
+                .map_or_else(|| parent_styles.color.to_owned(), HexColor::from),
             italic: self.is_italic(parent_styles),
             strikethrough: self.is_strikethrough(parent_styles),
         }
@@ -417,10 +507,41 @@ impl TextFormatRun {
 pub struct Format {
     pub italic: Option<bool>,
     pub strikethrough: Option<bool>,
+    /// Color of the run, the cell color if not set. Only set by the local file loaders, the
+    /// online sheet and cached data always use the cell color.
+    #[serde(skip)]
+    pub foreground_color: Option<ProtobufColor>,
 }
 
 mod tests {
 
+    #[test]
+    fn online_runs_keep_cell_color() {
+        use super::*;
+        use googlesheets::spreadsheet::{CellData, TextFormatRun as GridRun};
+
+        let green = spreadsheet::TextFormat {
+            foreground_color: Some(Color::rgb(0.0, 1.0, 0.0)),
+            ..Default::default()
+        };
+        let cell = Cell::from(CellData {
+            formatted_value: Some("Dunes; Act 2".to_owned()),
+            text_format_runs: vec![GridRun {
+                start_index: 0,
+                format: Some(green),
+            }],
+            ..Default::default()
+        });
+
+        let run = &cell.text_format_runs.as_ref().unwrap()[0];
+        let parent = FontStyles {
+            color: HexColor::White,
+            italic: false,
+            strikethrough: false,
+        };
+        assert_eq!(run.styles(&parent).color, HexColor::White);
+    }
+
     #[test]
     fn test_text_fragments() {
         use super::*;
@@ -451,6 +572,7 @@ mod tests {
                     format: Some(Format {
                         italic: Some(false),
                         strikethrough: None,
+                        foreground_color: None,
                     }),
                     font_family: None,
                 },
@@ -459,6 +581,7 @@ mod tests {
                     format: Some(Format {
                         italic: None,
                         strikethrough: None,
+                        foreground_color: None,
                     }),
                     font_family: None,
                 },
@@ -467,6 +590,7 @@ mod tests {
                     format: Some(Format {
                         italic: Some(false),
                         strikethrough: None,
+                        foreground_color: None,
                     }),
                     font_family: None,
                 },
@@ -0,0 +1,168 @@
+//! "Did you mean" suggestions for drop sources that failed to parse.
+
+use crate::{
+    dropsource::predefined::definitions::definitions,
+    parse::{record_url, DivcordColumn, ParseSourceError, ParseSourceErrorKind},
+};
+use poe_data::PoeData;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Suggestions per unknown source.
+pub const MAX_SUGGESTIONS: usize = 3;
+
+/// Known source name close to the unknown one.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Suggestion {
+    /// Text to write in the spreadsheet instead.
+    pub text: String,
+    pub source_type: String,
+    /// Edit distance to the unknown name, ignoring case.
+    pub distance: usize,
+}
+
+/// Replacements for one unknown source of a record.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fix {
+    pub record_id: usize,
+    pub card: String,
+    pub url: String,
+    pub text: String,
+    pub replacements: Vec<Suggestion>,
+}
+
+/// Levenshtein distance over chars.
+fn distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Names that the parser accepts with their source types.
+fn candidates(poe_data: &PoeData) -> Vec<(String, String)> {
+    let mut candidates: Vec<(String, String)> = vec![];
+    for definition in definitions().iter() {
+        for name in std::iter::once(&definition.id).chain(&definition.aliases) {
+            candidates.push((name.clone(), definition.source_type.clone()));
+        }
+    }
+    for map in &poe_data.maps {
+        candidates.push((map.name.clone(), "Map".to_owned()));
+    }
+    for boss in &poe_data.mapbosses {
+        candidates.push((boss.name.clone(), "Map Boss".to_owned()));
+    }
+    for act in poe_data.acts.iter().filter(|act| !act.is_town) {
+        candidates.push((act.name.clone(), "Act".to_owned()));
+        for boss in &act.bossfights {
+            candidates.push((boss.name.clone(), "Act Boss".to_owned()));
+        }
+    }
+    candidates
+}
+
+/// Closest known source names, best first. Names further than a third of their length are left
+/// out, so a typo gets suggestions and unrelated text gets none.
+pub fn suggestions(name: &str, poe_data: &PoeData) -> Vec<Suggestion> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return vec![];
+    }
+    let max_distance = (name.chars().count() / 3).max(2);
+
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut suggestions: Vec<Suggestion> = candidates(poe_data)
+        .into_iter()
+        .filter_map(|(text, source_type)| {
+            let distance = distance(&name, &text.to_lowercase());
+            (distance <= max_distance).then_some(Suggestion {
+                text,
+                source_type,
+                distance,
+            })
+        })
+        .collect();
+    suggestions.sort_by(|a, b| a.distance.cmp(&b.distance).then(a.text.cmp(&b.text)));
+    suggestions.retain(|suggestion| seen.insert(suggestion.text.clone()));
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+impl ParseSourceError {
+    /// Replacements for an unknown source, if there are any.
+    pub fn fix(&self) -> Option<Fix> {
+        let ParseSourceErrorKind::UnknownDropSource(drops_from, suggestions) = &self.kind else {
+            return None;
+        };
+        if suggestions.is_empty() {
+            return None;
+        }
+        Some(Fix {
+            record_id: self.record_id,
+            card: self.card.clone(),
+            url: record_url(self.record_id, DivcordColumn::Sources),
+            text: drops_from.name.clone(),
+            replacements: suggestions.clone(),
+        })
+    }
+}
+
+/// Machine-readable list of replacements for all unknown sources.
+pub fn fixes(errors: &[ParseSourceError]) -> Vec<Fix> {
+    errors.iter().filter_map(ParseSourceError::fix).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use poe_data::{cards::CardsData, mapbosses::MapBoss, maps::Map};
+    use std::collections::HashMap;
+
+    #[test]
+    fn suggests_close_names() {
+        let poe_data = PoeData {
+            acts: vec![],
+            cards: CardsData(HashMap::new()),
+            maps: vec![Map {
+                name: "Spider Forest Map".to_owned(),
+                tier: 4,
+                available: true,
+                unique: false,
+                icon: String::new(),
+                slug: "spider-forest-map".to_owned(),
+            }],
+            mapbosses: vec![MapBoss {
+                name: "Rama, The Kinslayer".to_owned(),
+                maps: vec!["Acid Caverns Map".to_owned()],
+            }],
+        };
+
+        assert_eq!(distance("kitten", "sitting"), 3);
+
+        let suggestions = suggestions("Spidr Forest map", &poe_data);
+        assert_eq!(suggestions[0].text, "Spider Forest Map");
+        assert_eq!(suggestions[0].source_type, "Map");
+        assert_eq!(suggestions[0].distance, 1);
+
+        assert_eq!(
+            super::suggestions("Rama the Kinslayer", &poe_data)[0].text,
+            "Rama, The Kinslayer"
+        );
+        assert_eq!(
+            super::suggestions("Kirac Mission", &poe_data)[0].text,
+            "Kirac Missions"
+        );
+        assert!(super::suggestions("Something unrelated", &poe_data).is_empty());
+    }
+}
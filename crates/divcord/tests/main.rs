@@ -105,3 +105,69 @@ async fn main() {
     );
     assert!(result.is_err());
 }
+
+#[test]
+fn source_levels_are_checked_against_card_levels() {
+    use divcord::{
+        parse::{check_source_levels, ParseSourceErrorKind, SourcesKind},
+        spreadsheet::rich::{FontStyles, HexColor},
+        Source,
+    };
+    use poe_data::act::ActAreaId;
+
+    let mut poe_data: PoeData =
+        serde_json::from_str(&std::fs::read_to_string("benches/poeData.json").unwrap()).unwrap();
+    poe_data.cards.0.get_mut("Rain of Chaos").unwrap().max_level = Some(60);
+    let drops_from = DropsFrom {
+        name: String::new(),
+        styles: FontStyles {
+            color: HexColor::White,
+            italic: false,
+            strikethrough: false,
+        },
+    };
+
+    let doctor = create_dumb("The Doctor", drops_from.clone());
+    let sources = [
+        Source::Map("Spider Forest Map".to_owned()),
+        Source::Act(ActAreaId::new("1_1_1".to_owned())),
+    ];
+    let errors = check_source_levels(&doctor, &sources, SourcesKind::Verify, &poe_data);
+    // Voidstones raise the tier 4 map up to level 83, only the act is too low.
+    assert_eq!(errors.len(), 1);
+    assert!(matches!(
+        &errors[0].kind,
+        ParseSourceErrorKind::SourceLevelIsLowerThanCardMinLevel {
+            area_level: 1,
+            card_min_level: 75,
+            column: SourcesKind::Verify,
+            ..
+        }
+    ));
+    assert!(errors[0].to_string().contains("range=H1"));
+
+    // Tier 11 map is high enough.
+    let errors = check_source_levels(
+        &doctor,
+        &[Source::Map("Burial Chambers Map".to_owned())],
+        SourcesKind::Source,
+        &poe_data,
+    );
+    assert!(errors.is_empty());
+
+    let rain = create_dumb("Rain of Chaos", drops_from);
+    let errors = check_source_levels(
+        &rain,
+        &[Source::Map("Atoll Map".to_owned())],
+        SourcesKind::Source,
+        &poe_data,
+    );
+    assert!(matches!(
+        &errors[0].kind,
+        ParseSourceErrorKind::SourceLevelIsHigherThanCardMaxLevel {
+            area_level: 72,
+            card_max_level: 60,
+            ..
+        }
+    ));
+}
@@ -1,18 +1,23 @@
 mod utils;
 
-use divcord::{ParseRecordError, Record};
+use divcord::{
+    dropsource::predefined::definitions::{self, Definitions},
+    lint::{Diagnostic, LintReport, ReportFormat},
+    spreadsheet::local::LoadError,
+    ParseRecordError, Record, Spreadsheet,
+};
 use poe_data::PoeData;
 use utils::set_panic_hook;
 use wasm_bindgen::{prelude::wasm_bindgen, JsValue};
 
-/// Fetch spreadsheet and parse.
+/// Fetch spreadsheet with the caller's Google API key and parse.
 #[wasm_bindgen]
 pub async fn fetch_divcord_records(
+    api_key: &str,
     poe_data: JsValue,
     on_error: &js_sys::Function,
 ) -> Result<JsValue, JsValue> {
     set_panic_hook();
-    let api_key = env!("GOOGLE_API_KEY");
 
     let poe_data: PoeData =
         serde_wasm_bindgen::from_value(poe_data).map_err(|err| JsValue::from(err.to_string()))?;
@@ -20,30 +25,84 @@ pub async fn fetch_divcord_records(
         .await
         .map_err(|e| JsValue::from_str(&e.to_string()))?;
 
-    let on_err = |s: &str| {
-        on_error
-            .call1(&JsValue::null(), &JsValue::from_str(s))
-            .unwrap()
+    Ok(collect_records(&spreadsheet, &poe_data, on_error))
+}
+
+/// Parse a downloaded copy of the spreadsheet, `.xlsx` or `.ods`, without network access.
+#[wasm_bindgen]
+pub fn parse_divcord_records_from_file(
+    bytes: &[u8],
+    file_name: &str,
+    poe_data: JsValue,
+    on_error: &js_sys::Function,
+) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+
+    let poe_data: PoeData =
+        serde_wasm_bindgen::from_value(poe_data).map_err(|err| JsValue::from(err.to_string()))?;
+    let spreadsheet = spreadsheet_from_file(bytes, file_name)?;
+
+    Ok(collect_records(&spreadsheet, &poe_data, on_error))
+}
+
+/// Lint a downloaded copy of the spreadsheet. `format` is `json`, `sarif` or `markdown`.
+#[wasm_bindgen]
+pub fn lint_divcord_records_from_file(
+    bytes: &[u8],
+    file_name: &str,
+    poe_data: JsValue,
+    format: &str,
+) -> Result<String, JsValue> {
+    set_panic_hook();
+
+    let format: ReportFormat = format
+        .parse()
+        .map_err(|e: divcord::lint::UnknownReportFormat| JsValue::from_str(&e.to_string()))?;
+    let poe_data: PoeData =
+        serde_wasm_bindgen::from_value(poe_data).map_err(|err| JsValue::from(err.to_string()))?;
+    let spreadsheet = spreadsheet_from_file(bytes, file_name)?;
+
+    Ok(LintReport::new(&spreadsheet, &poe_data).render(format))
+}
+
+fn spreadsheet_from_file(bytes: &[u8], file_name: &str) -> Result<Spreadsheet, JsValue> {
+    match file_name.rsplit_once('.').map(|(_, extension)| extension) {
+        Some("xlsx") => Spreadsheet::from_xlsx(bytes),
+        Some("ods") => Spreadsheet::from_ods(bytes),
+        _ => Err(LoadError::UnsupportedFormat(file_name.to_owned())),
+    }
+    .map_err(|e| JsValue::from_str(&e.to_string()))
+}
+
+/// Parse records, reporting each error through the callback as an array of
+/// [`Diagnostic`](divcord::lint::Diagnostic) objects.
+fn collect_records(
+    spreadsheet: &divcord::Spreadsheet,
+    poe_data: &PoeData,
+    on_error: &js_sys::Function,
+) -> JsValue {
+    let on_err = |diagnostics: Vec<Diagnostic>| {
+        let diagnostics = serde_wasm_bindgen::to_value(&diagnostics).unwrap();
+        on_error.call1(&JsValue::null(), &diagnostics).unwrap()
     };
 
     let mut records: Vec<Record> = vec![];
-    for record in divcord::records_iter(&spreadsheet, &poe_data) {
+    for record in divcord::records_iter(spreadsheet, poe_data) {
         match record {
             Ok(record_result) => {
                 records.push(record_result.record);
                 if !record_result.errors.is_empty() {
-                    let errors_string =
-                        ParseRecordError::ParseDropSources(record_result.errors).to_string();
-                    on_err(&errors_string);
+                    let err = ParseRecordError::ParseDropSources(record_result.errors);
+                    on_err(Vec::<Diagnostic>::from(&err));
                 }
             }
             Err(err) => {
-                on_err(&err.to_string());
+                on_err(vec![Diagnostic::from(&err)]);
             }
         }
     }
 
-    Ok(serde_wasm_bindgen::to_value(&records).unwrap())
+    serde_wasm_bindgen::to_value(&records).unwrap()
 }
 
 #[wasm_bindgen]
@@ -60,7 +119,8 @@ pub fn find_cards_by_source_types(types: JsValue, records: JsValue, poe_data: Js
     let types: Vec<String> = serde_wasm_bindgen::from_value(types).unwrap();
     let records: Vec<Record> = serde_wasm_bindgen::from_value(records).unwrap();
 
-    let cards = divcord::cards::cards_by_source_types(&types, &records, &poe_data);
+    let graph = divcord::graph::SourceGraph::new(&poe_data);
+    let cards = divcord::cards::cards_by_source_types(&types, &records, &graph);
 
     serde_wasm_bindgen::to_value(&cards).unwrap()
 }
@@ -76,12 +136,90 @@ pub fn find_cards_by_source_types_strings(
     let poe_data: PoeData = serde_json::from_str(&poe_data).unwrap();
     let records: Vec<Record> = serde_json::from_str(&records).unwrap();
 
-    let cards = divcord::cards::cards_by_source_types(&types, &records, &poe_data);
+    let graph = divcord::graph::SourceGraph::new(&poe_data);
+    let cards = divcord::cards::cards_by_source_types(&types, &records, &graph);
 
     // serde_wasm_bindgen::to_value(&cards).unwrap()
     serde_json::to_string(&cards).unwrap()
 }
 
+/// Index of cards by source and sources by card, to build once and look up from JS.
+#[wasm_bindgen]
+pub fn build_divcord_index(records: JsValue, poe_data: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let poe_data: PoeData =
+        serde_wasm_bindgen::from_value(poe_data).map_err(|err| JsValue::from(err.to_string()))?;
+    let records: Vec<Record> =
+        serde_wasm_bindgen::from_value(records).map_err(|err| JsValue::from(err.to_string()))?;
+
+    let index = divcord::DivcordIndex::new(&records, &poe_data);
+    serde_wasm_bindgen::to_value(&index).map_err(|err| JsValue::from(err.to_string()))
+}
+
+/// Filter, sort and page records with a text query, see [`divcord::query`] for the syntax.
+#[wasm_bindgen]
+pub fn query_records(query: &str, records: JsValue, poe_data: JsValue) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let poe_data: PoeData =
+        serde_wasm_bindgen::from_value(poe_data).map_err(|err| JsValue::from(err.to_string()))?;
+    let records: Vec<Record> =
+        serde_wasm_bindgen::from_value(records).map_err(|err| JsValue::from(err.to_string()))?;
+
+    let result = divcord::query_records(query, &records, &poe_data)
+        .map_err(|err| JsValue::from_str(&err.to_string()))?;
+
+    serde_wasm_bindgen::to_value(&result).map_err(|err| JsValue::from(err.to_string()))
+}
+
+/// Drop sources ranked by the value of target cards, see [`divcord::farm`]. `prices` can be
+/// `undefined` to use prices of `poe_data` cards.
+#[wasm_bindgen]
+pub fn where_to_farm(
+    query: JsValue,
+    records: JsValue,
+    poe_data: JsValue,
+    prices: JsValue,
+) -> Result<JsValue, JsValue> {
+    set_panic_hook();
+    let query: divcord::farm::FarmQuery =
+        serde_wasm_bindgen::from_value(query).map_err(|err| JsValue::from(err.to_string()))?;
+    let records: Vec<Record> =
+        serde_wasm_bindgen::from_value(records).map_err(|err| JsValue::from(err.to_string()))?;
+    let poe_data: PoeData =
+        serde_wasm_bindgen::from_value(poe_data).map_err(|err| JsValue::from(err.to_string()))?;
+    let prices: Option<divcord::farm::Prices> =
+        serde_wasm_bindgen::from_value(prices).map_err(|err| JsValue::from(err.to_string()))?;
+
+    let sources = divcord::farm::where_to_farm(&query, &records, &poe_data, prices.as_ref());
+    serde_wasm_bindgen::to_value(&sources).map_err(|err| JsValue::from(err.to_string()))
+}
+
+/// Drop source graph as Graphviz DOT (`format` is `dot`) or JSON (`json`).
+#[wasm_bindgen]
+pub fn source_graph(poe_data: JsValue, format: &str) -> Result<String, JsValue> {
+    set_panic_hook();
+    let poe_data: PoeData =
+        serde_wasm_bindgen::from_value(poe_data).map_err(|err| JsValue::from(err.to_string()))?;
+
+    let graph = divcord::graph::SourceGraph::new(&poe_data);
+    match format {
+        "dot" => Ok(graph.to_dot()),
+        "json" => serde_json::to_string(&graph.to_json()).map_err(|e| JsValue::from(e.to_string())),
+        _ => Err(JsValue::from_str(&format!(
+            "Unknown graph format `{format}`. Expected dot or json."
+        ))),
+    }
+}
+
+/// Add or replace predefined source definitions (JSON array) on top of the built-in ones.
+#[wasm_bindgen]
+pub fn set_predefined_sources(json: &str) -> Result<(), JsValue> {
+    set_panic_hook();
+    let overrides = Definitions::from_json(json).map_err(|e| JsValue::from_str(&e.to_string()))?;
+    definitions::set_definitions(overrides);
+    Ok(())
+}
+
 #[wasm_bindgen]
 pub fn slug(s: String) -> String {
     slug::slugify(s)
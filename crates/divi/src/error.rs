@@ -15,6 +15,8 @@ pub enum Error {
     ParseIntError(ParseIntError),
     CsvError(CsvError),
     NinjaError(NinjaError),
+    /// History column header equal to the header of a synced sample column.
+    HistoryColumnClash(String),
 }
 
 impl Display for Error {
@@ -29,6 +31,10 @@ impl Display for Error {
             Error::ParseIntError(err) => err.fmt(f),
             Error::CsvError(err) => err.fmt(f),
             Error::NinjaError(err) => err.fmt(f),
+            Error::HistoryColumnClash(header) => write!(
+                f,
+                "History column \"{header}\" has the same header as a column of the sample."
+            ),
         }
     }
 }
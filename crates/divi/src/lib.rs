@@ -61,7 +61,10 @@ pub mod cards;
 pub mod consts;
 pub mod error;
 pub mod prices;
+pub mod report;
 pub mod sample;
+pub mod sheet_sync;
+pub mod sheet_template;
 
 pub use crate::{
     card_record::CardRecord,
@@ -0,0 +1,226 @@
+//! Summary tab for a sample exported to Google Sheets: totals, top cards by value
+//! and charts of value share and weight against price.
+
+use crate::{card_record::CardRecord, sample::Sample};
+use googlesheets::{
+    format::{FormatRequest, GridRange, NumberFormat},
+    sheet::{Dimension, SheetId, ValueRange},
+    A1Range, Chart,
+};
+use serde_json::{json, Value};
+use std::fmt::Display;
+
+/// Zero-based row of the top cards header.
+const TOP_HEADER_ROW: u32 = 7;
+/// Zero-based column of the weight/price table that feeds the scatter chart.
+const SCATTER_COLUMN: u32 = 5;
+/// Zero-based column where charts are placed, right of both tables.
+const CHARTS_COLUMN: u32 = 8;
+/// Rows taken by one chart with the default size.
+const CHART_HEIGHT: u32 = 20;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    pub league: String,
+    pub total_value: f32,
+    pub total_amount: u32,
+    pub distinct_cards: usize,
+    /// Cards with the highest `sum`, most valuable first.
+    pub top_cards: Vec<CardRecord>,
+    /// Amount and value of all cards outside the top.
+    pub other: (u32, f32),
+    /// Weight and price of every card that has both.
+    pub weight_price: Vec<(f32, f32)>,
+}
+
+impl Report {
+    #[must_use]
+    pub fn new(sample: &Sample, league: impl Display, top: usize) -> Self {
+        let mut cards: Vec<&CardRecord> = sample.cards.iter().filter(|c| c.amount > 0).collect();
+        cards.sort_by(|a, b| {
+            b.sum
+                .unwrap_or_default()
+                .total_cmp(&a.sum.unwrap_or_default())
+        });
+
+        let total_value = cards.iter().map(|c| c.sum.unwrap_or_default()).sum();
+        let total_amount = cards.iter().map(|c| c.amount).sum();
+        let top_cards: Vec<CardRecord> = cards.iter().take(top).map(|&c| c.clone()).collect();
+        let other = cards.iter().skip(top).fold((0, 0.), |(amount, value), c| {
+            (amount + c.amount, value + c.sum.unwrap_or_default())
+        });
+        let weight_price = cards
+            .iter()
+            .filter_map(|c| Some((c.weight?, c.price?)))
+            .collect();
+
+        Report {
+            league: league.to_string(),
+            total_value,
+            total_amount,
+            distinct_cards: cards.len(),
+            top_cards,
+            other,
+            weight_price,
+        }
+    }
+
+    /// Rows of the top table, including "Other" if there are cards outside the top.
+    fn top_rows(&self) -> Vec<Vec<Value>> {
+        let mut rows: Vec<Vec<Value>> = self
+            .top_cards
+            .iter()
+            .map(|c| vec![json!(c.name), json!(c.amount), json!(c.price), json!(c.sum)])
+            .collect();
+        let (amount, value) = self.other;
+        if amount > 0 {
+            rows.push(vec![
+                json!("Other"),
+                json!(amount),
+                Value::Null,
+                json!(value),
+            ]);
+        }
+        rows
+    }
+
+    /// Values of the summary tab: totals and top cards at `A1`, weight/price table at `F1`.
+    #[must_use]
+    pub fn value_ranges(&self, title: &str) -> Vec<ValueRange> {
+        let mut summary = vec![
+            vec![json!("Summary")],
+            vec![json!("League"), json!(self.league)],
+            vec![json!("Total value"), json!(self.total_value)],
+            vec![json!("Cards"), json!(self.total_amount)],
+            vec![json!("Distinct cards"), json!(self.distinct_cards)],
+            vec![],
+            vec![json!("Top cards by value")],
+            vec![json!("name"), json!("amount"), json!("price"), json!("sum")],
+        ];
+        summary.extend(self.top_rows());
+
+        let mut weight_price = vec![vec![json!("weight"), json!("price")]];
+        weight_price.extend(
+            self.weight_price
+                .iter()
+                .map(|(weight, price)| vec![json!(weight), json!(price)]),
+        );
+
+        vec![
+            ValueRange::new(Dimension::Rows, A1Range::cell(title, 0, 1), summary),
+            ValueRange::new(
+                Dimension::Rows,
+                A1Range::cell(title, SCATTER_COLUMN, 1),
+                weight_price,
+            ),
+        ]
+    }
+
+    /// Formatting and charts for the summary tab, once values are written.
+    #[must_use]
+    pub fn requests(&self, sheet_id: SheetId) -> Vec<FormatRequest> {
+        let top_start = TOP_HEADER_ROW + 1;
+        let top_end = top_start + u32::try_from(self.top_cards.len()).unwrap_or(u32::MAX);
+        let pie_end = top_start + u32::try_from(self.top_rows().len()).unwrap_or(u32::MAX);
+        let scatter_end = 1 + u32::try_from(self.weight_price.len()).unwrap_or(u32::MAX);
+        let value = NumberFormat::number("#,##0.0");
+
+        let mut requests = vec![
+            FormatRequest::Bold(GridRange::row(sheet_id, 0)),
+            FormatRequest::Bold(GridRange::row(sheet_id, TOP_HEADER_ROW - 1)),
+            FormatRequest::Bold(GridRange::row(sheet_id, TOP_HEADER_ROW)),
+            FormatRequest::NumberFormat {
+                range: GridRange::new(sheet_id, 2..3, 1..2),
+                format: value.clone(),
+            },
+            FormatRequest::NumberFormat {
+                range: GridRange::new(sheet_id, top_start..pie_end, 2..4),
+                format: value,
+            },
+        ];
+
+        if top_end > top_start {
+            let names = GridRange::new(sheet_id, top_start..pie_end, 0..1);
+            let sums = GridRange::new(sheet_id, top_start..pie_end, 3..4);
+            requests.push(FormatRequest::AddChart(
+                Chart::pie("Value share", names, sums).anchor(sheet_id, 0, CHARTS_COLUMN),
+            ));
+
+            let names = GridRange::new(sheet_id, top_start..top_end, 0..1);
+            let sums = GridRange::new(sheet_id, top_start..top_end, 3..4);
+            requests.push(FormatRequest::AddChart(
+                Chart::bar("Top cards by value", names, sums)
+                    .axis_titles("card", "chaos")
+                    .anchor(sheet_id, CHART_HEIGHT, CHARTS_COLUMN),
+            ));
+        }
+
+        if scatter_end > 1 {
+            let weights =
+                GridRange::new(sheet_id, 1..scatter_end, SCATTER_COLUMN..SCATTER_COLUMN + 1);
+            let prices = GridRange::new(
+                sheet_id,
+                1..scatter_end,
+                SCATTER_COLUMN + 1..SCATTER_COLUMN + 2,
+            );
+            requests.push(FormatRequest::AddChart(
+                Chart::scatter("Weight vs price", weights, prices)
+                    .axis_titles("weight", "price")
+                    .anchor(sheet_id, CHART_HEIGHT * 2, CHARTS_COLUMN),
+            ));
+        }
+
+        requests
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        prices::{DivinationCardPrice, Prices},
+        sample::Input,
+    };
+
+    #[test]
+    fn report_from_sample() {
+        let prices = Prices(vec![
+            DivinationCardPrice {
+                name: "The Doctor".to_owned(),
+                price: Some(800.),
+            },
+            DivinationCardPrice {
+                name: "Rain of Chaos".to_owned(),
+                price: Some(0.5),
+            },
+            DivinationCardPrice {
+                name: "Humility".to_owned(),
+                price: Some(2.),
+            },
+        ]);
+        let sample = Sample::create(
+            Input::Csv("name,amount\nThe Doctor,1\nRain of Chaos,100\nHumility,3".to_owned()),
+            Some(prices),
+        )
+        .unwrap();
+
+        let report = Report::new(&sample, "Settlers", 2);
+        assert_eq!(report.distinct_cards, 3);
+        assert_eq!(report.total_amount, 104);
+        assert!((report.total_value - 856.).abs() < 0.01);
+        let top: Vec<&str> = report.top_cards.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(top, ["The Doctor", "Rain of Chaos"]);
+        assert_eq!(report.other.0, 3);
+
+        let ranges = report.value_ranges("Settlers summary");
+        assert_eq!(ranges[0].values[TOP_HEADER_ROW as usize + 3][0], "Other");
+        assert_eq!(ranges[1].range.to_string(), "'Settlers summary'!F1");
+
+        let charts = report
+            .requests(SheetId::from(1))
+            .into_iter()
+            .filter(|r| matches!(r, FormatRequest::AddChart(_)))
+            .count();
+        assert_eq!(charts, 3);
+    }
+}
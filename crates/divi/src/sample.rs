@@ -155,7 +155,7 @@ impl Sample {
         self.cards
             .order_by(preferences.ordered_by, preferences.order);
 
-        let columns = preserve_column_order(&preferences.columns);
+        let columns = preferences.ordered_columns();
         let mut values: Vec<Vec<Value>> = vec![];
         let headers: Vec<Value> = columns.iter().map(|c| json!(&c)).collect();
         values.push(headers);
@@ -288,6 +288,14 @@ impl Default for TablePreferences {
     }
 }
 
+impl TablePreferences {
+    /// Columns in the order they are written: name > amount > weight > price > sum
+    #[must_use]
+    pub fn ordered_columns(&self) -> Vec<Column> {
+        preserve_column_order(&self.columns)
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone, Default, Copy)]
 #[serde(rename_all = "camelCase")]
 pub enum Order {
@@ -0,0 +1,318 @@
+//! Incremental sync of a sample into an existing sheet tab.
+//!
+//! Rows are matched by card name. Only the sample's own columns (and the optional history column)
+//! are written, so user-added columns and rows of cards missing from the sample stay untouched.
+
+use crate::{
+    card_record::CardRecord,
+    error::Error,
+    sample::{Column, Sample, TablePreferences},
+    sheet_template::SheetTemplate,
+};
+use googlesheets::{
+    sheet::{Dimension, ValueRange},
+    A1Range, CellRef,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncOptions {
+    pub preferences: TablePreferences,
+    /// Header of a column that receives this sync's amounts, e.g. the date of the sync.
+    /// An existing column with the same header is overwritten. Must differ from the headers of
+    /// the sample's columns.
+    pub history_column: Option<String>,
+    /// Only the table position is used: the header row and the first column of the table.
+    #[serde(default)]
+    pub template: SheetTemplate,
+}
+
+/// One column to write, starting from the header row.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyncedColumn {
+    /// Zero-based column index in the tab.
+    pub index: u32,
+    /// Header followed by one value per row. `Null` values are skipped by the Sheets API,
+    /// so rows of cards that are not in the sample keep their content.
+    pub values: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SheetSync {
+    /// One-based row of the header.
+    pub header_row: u32,
+    pub columns: Vec<SyncedColumn>,
+    /// Number of existing rows that were updated.
+    pub updated: usize,
+    /// Number of cards appended below existing rows.
+    pub appended: usize,
+}
+
+impl SheetSync {
+    /// Column-wise value ranges for `googlesheets::batch_update`.
+    #[must_use]
+    pub fn value_ranges(&self, title: &str) -> Vec<ValueRange> {
+        self.columns
+            .iter()
+            .map(|column| {
+                ValueRange::new(
+                    Dimension::Columns,
+                    A1Range::sheet(title).start(CellRef::new(column.index, self.header_row)),
+                    vec![column.values.clone()],
+                )
+            })
+            .collect()
+    }
+}
+
+fn header_text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_lowercase()),
+        _ => None,
+    }
+}
+
+fn cell_value(card: &CardRecord, column: Column) -> Value {
+    match column {
+        Column::Name => Value::from(card.name.clone()),
+        Column::Amount => Value::from(card.amount),
+        Column::Weight => Value::from(card.weight),
+        Column::Price => Value::from(card.price),
+        Column::Sum => Value::from(card.sum),
+    }
+}
+
+impl Sample {
+    /// Computes the writes that merge the sample into `existing`, the current values of the tab
+    /// starting from `A1`. The table starts at the template's table cell.
+    pub fn sync_into(
+        mut self,
+        existing: &[Vec<Value>],
+        options: &SyncOptions,
+    ) -> Result<SheetSync, Error> {
+        let preferences = &options.preferences;
+        if preferences.cards_must_have_amount {
+            self.cards.0.retain(|c| c.amount > 0);
+        }
+        self.cards
+            .order_by(preferences.ordered_by, preferences.order);
+
+        let (first_column, header_row) = options.template.table_origin();
+        let existing = existing.get(header_row as usize..).unwrap_or_default();
+        let headers: Vec<Option<String>> = existing
+            .first()
+            .map(|row| {
+                row.iter()
+                    .skip(first_column as usize)
+                    .map(header_text)
+                    .collect()
+            })
+            .unwrap_or_default();
+        let mut next_free_column =
+            first_column.saturating_add(u32::try_from(headers.len()).unwrap_or(u32::MAX));
+        let mut column_index = |header: &str| -> u32 {
+            let header = header.trim().to_lowercase();
+            if let Some(index) = headers.iter().position(|h| h.as_deref() == Some(&header)) {
+                first_column.saturating_add(u32::try_from(index).unwrap_or(u32::MAX))
+            } else {
+                next_free_column += 1;
+                next_free_column - 1
+            }
+        };
+
+        // Name is always written, it is the key of the sync.
+        let mut columns = preferences.ordered_columns();
+        if !columns.contains(&Column::Name) {
+            columns.insert(0, Column::Name);
+        }
+        if let Some(history_column) = &options.history_column {
+            let header = history_column.trim().to_lowercase();
+            if columns.iter().any(|column| column.to_string() == header) {
+                return Err(Error::HistoryColumnClash(history_column.clone()));
+            }
+        }
+        let targets: Vec<(Column, u32)> = columns
+            .into_iter()
+            .map(|column| (column, column_index(&column.to_string())))
+            .collect();
+        let history_index = options.history_column.as_deref().map(column_index);
+
+        let name_index = targets
+            .iter()
+            .find(|(column, _)| *column == Column::Name)
+            .map_or(0, |(_, index)| *index as usize);
+        let mut rows_by_name: HashMap<String, usize> = HashMap::new();
+        for (row, values) in existing.iter().enumerate().skip(1) {
+            if let Some(Value::String(name)) = values.get(name_index) {
+                rows_by_name.entry(name.trim().to_owned()).or_insert(row);
+            }
+        }
+
+        let mut total_rows = existing.len().max(1);
+        let mut card_rows: Vec<(&CardRecord, usize)> = vec![];
+        let (mut updated, mut appended) = (0, 0);
+        for card in &self.cards {
+            if card.price.unwrap_or_default() < preferences.min_price {
+                continue;
+            }
+            if let Some(row) = rows_by_name.get(&card.name) {
+                card_rows.push((card, *row));
+                updated += 1;
+            } else {
+                card_rows.push((card, total_rows));
+                total_rows += 1;
+                appended += 1;
+            }
+        }
+
+        let mut synced: Vec<SyncedColumn> = targets
+            .iter()
+            .map(|(column, index)| {
+                let mut values = vec![Value::Null; total_rows];
+                values[0] = Value::from(column.to_string());
+                for (card, row) in &card_rows {
+                    values[*row] = cell_value(card, *column);
+                }
+                SyncedColumn {
+                    index: *index,
+                    values,
+                }
+            })
+            .collect();
+
+        if let (Some(index), Some(header)) = (history_index, &options.history_column) {
+            let mut values = vec![Value::Null; total_rows];
+            values[0] = Value::from(header.clone());
+            for (card, row) in &card_rows {
+                values[*row] = Value::from(card.amount);
+            }
+            synced.push(SyncedColumn { index, values });
+        }
+
+        Ok(SheetSync {
+            header_row: header_row + 1,
+            columns: synced,
+            updated,
+            appended,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample::{Input, Order};
+    use serde_json::json;
+
+    fn sample(csv: &str) -> Sample {
+        Sample::create(Input::Csv(csv.to_owned()), None).unwrap()
+    }
+
+    #[test]
+    fn updates_matching_rows_and_appends_new_cards() {
+        let existing = vec![
+            vec![json!("name"), json!("notes"), json!("amount")],
+            vec![json!("The Doctor"), json!("keep me"), json!(1)],
+            vec![json!("Rain of Chaos"), json!(""), json!(10)],
+        ];
+        let options = SyncOptions {
+            preferences: TablePreferences {
+                columns: vec![Column::Name, Column::Amount],
+                ordered_by: Column::Name,
+                order: Order::Asc,
+                cards_must_have_amount: true,
+                min_price: 0.,
+            },
+            history_column: Some("1 Oct, 25".to_owned()),
+            template: SheetTemplate::default(),
+        };
+
+        let sync = sample("name,amount\nRain of Chaos,12\nHumility,3")
+            .sync_into(&existing, &options)
+            .unwrap();
+        assert_eq!(sync.updated, 1);
+        assert_eq!(sync.appended, 1);
+
+        // The user's "notes" column (index 1) is never written.
+        let indices: Vec<u32> = sync.columns.iter().map(|c| c.index).collect();
+        assert_eq!(indices, [0, 2, 3]);
+
+        let amount = &sync.columns[1];
+        assert_eq!(
+            amount.values,
+            [json!("amount"), Value::Null, json!(12), json!(3)]
+        );
+        let name = &sync.columns[0];
+        assert_eq!(name.values[3], json!("Humility"));
+        let history = &sync.columns[2];
+        assert_eq!(history.values[0], json!("1 Oct, 25"));
+        assert_eq!(history.values[2], json!(12));
+
+        let ranges = sync.value_ranges("My Tab");
+        assert_eq!(ranges[2].range.to_string(), "'My Tab'!D1");
+    }
+
+    #[test]
+    fn empty_tab_gets_headers() {
+        let options = SyncOptions {
+            preferences: TablePreferences {
+                cards_must_have_amount: true,
+                ..Default::default()
+            },
+            history_column: None,
+            template: SheetTemplate::default(),
+        };
+        let sync = sample("name,amount\nThe Doctor,2")
+            .sync_into(&[], &options)
+            .unwrap();
+        assert_eq!(sync.appended, 1);
+        assert_eq!(sync.columns[0].values, [json!("name"), json!("The Doctor")]);
+        assert_eq!(sync.columns[1].values, [json!("amount"), json!(2)]);
+    }
+
+    #[test]
+    fn table_starts_at_template_cell() {
+        let existing = vec![
+            vec![json!("My drops")],
+            vec![],
+            vec![Value::Null, json!("name"), json!("amount")],
+            vec![Value::Null, json!("The Doctor"), json!(1)],
+        ];
+        let options = SyncOptions {
+            preferences: TablePreferences {
+                columns: vec![Column::Name, Column::Amount],
+                cards_must_have_amount: true,
+                ..Default::default()
+            },
+            history_column: None,
+            template: SheetTemplate {
+                table: CellRef::new(1, 3),
+                ..Default::default()
+            },
+        };
+
+        let sync = sample("name,amount\nThe Doctor,3")
+            .sync_into(&existing, &options)
+            .unwrap();
+        assert_eq!(sync.updated, 1);
+        let indices: Vec<u32> = sync.columns.iter().map(|c| c.index).collect();
+        assert_eq!(indices, [1, 2]);
+        assert_eq!(sync.columns[1].values, [json!("amount"), json!(3)]);
+        assert_eq!(sync.value_ranges("Tab")[1].range.to_string(), "'Tab'!C3");
+    }
+
+    #[test]
+    fn history_column_must_not_clash_with_sample_columns() {
+        let options = SyncOptions {
+            history_column: Some(" Amount".to_owned()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            sample("name,amount\nThe Doctor,2").sync_into(&[], &options),
+            Err(Error::HistoryColumnClash(_))
+        ));
+    }
+}
@@ -0,0 +1,132 @@
+//! Layout of an exported sample tab: where the table, the league/date label and the totals go.
+
+use crate::sample::{Sample, TablePreferences};
+use googlesheets::{
+    sheet::{Dimension, ValueRange},
+    A1Range, CellRef,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SheetTemplate {
+    /// Top-left cell of the sample table, header included.
+    pub table: CellRef,
+    /// Cell of the "{date} {league} League" label.
+    pub label: Option<CellRef>,
+    /// Top-left cell of the totals block: total cards and total price.
+    pub totals: Option<CellRef>,
+}
+
+impl Default for SheetTemplate {
+    /// Table at `A1`, label at `H5`, no totals.
+    fn default() -> Self {
+        SheetTemplate {
+            table: CellRef::new(0, 1),
+            label: Some(CellRef::new(7, 5)),
+            totals: None,
+        }
+    }
+}
+
+/// Zero-based column and one-based row. A whole-column or whole-row reference starts at its
+/// first cell. Row 0 does not exist in A1 notation and is read as row 1.
+fn position(cell: CellRef) -> (u32, u32) {
+    (cell.column.unwrap_or(0), cell.row.unwrap_or(1).max(1))
+}
+
+impl SheetTemplate {
+    /// Zero-based column and row of the table's top-left cell, for `SheetStyle::origin`.
+    #[must_use]
+    pub fn table_origin(&self) -> (u32, u32) {
+        let (column, row) = position(self.table);
+        (column, row.saturating_sub(1))
+    }
+
+    /// Values of the tab. Totals count all cards of the sample, regardless of preferences.
+    #[must_use]
+    pub fn value_ranges(
+        &self,
+        title: &str,
+        sample: Sample,
+        preferences: TablePreferences,
+        label: &str,
+    ) -> Vec<ValueRange> {
+        let cell = |cell: CellRef| {
+            let (column, row) = position(cell);
+            A1Range::cell(title, column, row)
+        };
+
+        let totals = self.totals.map(|totals| {
+            let amount: u32 = sample.cards.iter().map(|c| c.amount).sum();
+            let price: f32 = sample.cards.iter().filter_map(|c| c.sum).sum();
+            ValueRange::new(
+                Dimension::Rows,
+                cell(totals),
+                vec![
+                    vec![json!("total cards"), json!(amount)],
+                    vec![json!("total price"), json!(price)],
+                ],
+            )
+        });
+
+        let mut ranges = vec![ValueRange::new(
+            Dimension::Rows,
+            cell(self.table),
+            sample.into_serde_values(Some(preferences)),
+        )];
+        if let Some(label_cell) = self.label {
+            ranges.push(ValueRange::new(
+                Dimension::Rows,
+                cell(label_cell),
+                vec![vec![json!(label)]],
+            ));
+        }
+        ranges.extend(totals);
+        ranges
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sample::Input;
+
+    #[test]
+    fn template_layout() {
+        let sample = || {
+            Sample::create(
+                Input::Csv("name,amount\nThe Doctor,1\nRain of Chaos,30".to_owned()),
+                None,
+            )
+            .unwrap()
+        };
+
+        let ranges = SheetTemplate::default().value_ranges(
+            "Sample",
+            sample(),
+            TablePreferences::default(),
+            "1 Oct, 25 Settlers League",
+        );
+        let cells: Vec<String> = ranges.iter().map(|r| r.range.to_string()).collect();
+        assert_eq!(cells, ["Sample!A1", "Sample!H5"]);
+
+        let template: SheetTemplate =
+            serde_json::from_str(r#"{ "table": "B3", "label": null, "totals": "F3" }"#).unwrap();
+        assert_eq!(template.table_origin(), (1, 2));
+        let ranges =
+            template.value_ranges("Sample", sample(), TablePreferences::default(), "ignored");
+        assert_eq!(ranges.len(), 2);
+        assert_eq!(ranges[0].range.to_string(), "Sample!B3");
+        assert_eq!(ranges[1].range.to_string(), "Sample!F3");
+        assert_eq!(ranges[1].values[0], [json!("total cards"), json!(31)]);
+
+        let template = SheetTemplate {
+            table: CellRef::new(2, 0),
+            label: None,
+            totals: None,
+        };
+        assert_eq!(template.table_origin(), (2, 0));
+    }
+}
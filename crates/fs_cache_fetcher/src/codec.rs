@@ -0,0 +1,113 @@
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use serde::{de::DeserializeOwned, Serialize};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// How cache entries are stored on disk.
+///
+/// Reading does not depend on the configured codec: the format is detected from the content,
+/// so entries written with another codec (e.g. plain JSON from older versions) stay readable.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    #[default]
+    Json,
+    /// Gzip-compressed JSON, `poeData.json.gz`.
+    JsonGzip,
+    /// Compact binary CBOR, `poeData.cbor`. Fastest to load.
+    Cbor,
+}
+
+impl Codec {
+    /// File path for an entry with the given logical filename.
+    pub fn path(&self, path: &Path) -> PathBuf {
+        match self {
+            Codec::Json => path.to_owned(),
+            Codec::JsonGzip => {
+                let mut s = path.as_os_str().to_owned();
+                s.push(".gz");
+                PathBuf::from(s)
+            }
+            Codec::Cbor => path.with_extension("cbor"),
+        }
+    }
+
+    /// Detects the codec of stored bytes.
+    /// Cached items are maps or sequences, whose CBOR headers never look like the start of JSON.
+    pub fn detect(bytes: &[u8]) -> Codec {
+        if bytes.starts_with(&GZIP_MAGIC) {
+            return Codec::JsonGzip;
+        }
+
+        match bytes.iter().find(|b| !b.is_ascii_whitespace()) {
+            Some(b'{' | b'[' | b'"' | b'-' | b'0'..=b'9' | b't' | b'f' | b'n') | None => {
+                Codec::Json
+            }
+            Some(_) => Codec::Cbor,
+        }
+    }
+
+    pub fn encode<T: Serialize>(&self, data: &T) -> std::io::Result<Vec<u8>> {
+        match self {
+            Codec::Json => Ok(serde_json::to_vec(data)?),
+            Codec::JsonGzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                serde_json::to_writer(&mut encoder, data)?;
+                encoder.finish()
+            }
+            Codec::Cbor => {
+                let mut bytes = Vec::new();
+                ciborium::into_writer(data, &mut bytes).map_err(invalid_data)?;
+                Ok(bytes)
+            }
+        }
+    }
+
+    /// Decodes bytes of any codec, see [`Codec::detect`].
+    pub fn decode<T: DeserializeOwned>(bytes: &[u8]) -> std::io::Result<T> {
+        match Codec::detect(bytes) {
+            Codec::Json => Ok(serde_json::from_slice(bytes)?),
+            Codec::JsonGzip => {
+                let mut json = Vec::new();
+                GzDecoder::new(bytes).read_to_end(&mut json)?;
+                Ok(serde_json::from_slice(&json)?)
+            }
+            Codec::Cbor => ciborium::from_reader(bytes).map_err(invalid_data),
+        }
+    }
+}
+
+fn invalid_data(err: impl std::fmt::Display) -> std::io::Error {
+    std::io::Error::new(std::io::ErrorKind::InvalidData, err.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn every_codec_roundtrips() {
+        let data: HashMap<String, Vec<Option<f32>>> =
+            HashMap::from([("The Doctor".to_owned(), vec![Some(1.5), None])]);
+
+        for codec in [Codec::Json, Codec::JsonGzip, Codec::Cbor] {
+            let bytes = codec.encode(&data).unwrap();
+            assert_eq!(Codec::detect(&bytes), codec);
+            let decoded: HashMap<String, Vec<Option<f32>>> = Codec::decode(&bytes).unwrap();
+            assert_eq!(decoded, data);
+        }
+    }
+
+    #[test]
+    fn codec_paths() {
+        let path = Path::new("data/poeData.json");
+        assert_eq!(Codec::Json.path(path), PathBuf::from("data/poeData.json"));
+        assert_eq!(
+            Codec::JsonGzip.path(path),
+            PathBuf::from("data/poeData.json.gz")
+        );
+        assert_eq!(Codec::Cbor.path(path), PathBuf::from("data/poeData.cbor"));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::{Codec, LoadPolicy};
+use std::path::PathBuf;
+use std::time::Duration;
+
+#[derive(Debug, Default, Clone)]
+pub struct Config {
+    pub save: bool,
+    /// File name relative to the cache root. May contain subdirectories (`prices/Standard.json`)
+    /// and, for keyed fetchers, a `{key}` placeholder (`prices/{key}.json`).
+    pub filename: String,
+    pub stale: Stale,
+    /// Overrides the cache root. See [`crate::cache_root`] for the fallback order.
+    pub cache_dir: Option<PathBuf>,
+    pub policy: LoadPolicy,
+    pub codec: Codec,
+}
+
+impl Config {
+    pub fn builder() -> ConfigBuilder {
+        ConfigBuilder::default()
+    }
+
+    pub fn stale(&mut self, stale: Stale) -> &mut Self {
+        self.stale = stale;
+        self
+    }
+
+    pub fn save(&mut self, save: bool) -> &mut Self {
+        self.save = save;
+        self
+    }
+
+    pub fn filename(&mut self, filename: impl Into<String>) -> &mut Self {
+        self.filename = filename.into();
+        self
+    }
+
+    pub fn cache_dir(&mut self, cache_dir: impl Into<PathBuf>) -> &mut Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    pub fn policy(&mut self, policy: LoadPolicy) -> &mut Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn codec(&mut self, codec: Codec) -> &mut Self {
+        self.codec = codec;
+        self
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub enum Stale {
+    #[default]
+    Never,
+    After(Duration),
+    ReloadEveryTime,
+}
+
+#[derive(Default)]
+pub struct ConfigBuilder {
+    pub save: bool,
+    pub filename: String,
+    pub stale: Stale,
+    pub cache_dir: Option<PathBuf>,
+    pub policy: LoadPolicy,
+    pub codec: Codec,
+}
+
+impl ConfigBuilder {
+    pub fn stale(mut self, stale: Stale) -> Self {
+        self.stale = stale;
+        self
+    }
+
+    pub fn save(mut self, save: bool) -> Self {
+        self.save = save;
+        self
+    }
+
+    pub fn filename(mut self, filename: impl Into<String>) -> Self {
+        self.filename = filename.into();
+        self
+    }
+
+    pub fn cache_dir(mut self, cache_dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(cache_dir.into());
+        self
+    }
+
+    pub fn policy(mut self, policy: LoadPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn codec(mut self, codec: Codec) -> Self {
+        self.codec = codec;
+        self
+    }
+
+    pub fn build(self) -> Config {
+        Config {
+            save: self.save,
+            filename: self.filename,
+            stale: self.stale,
+            cache_dir: self.cache_dir,
+            policy: self.policy,
+            codec: self.codec,
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! One cache file on disk together with its metadata sidecar.
+
+use crate::{Metadata, Stale};
+use std::ffi::OsString;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::SystemTime;
+
+const META_SUFFIX: &str = ".meta.json";
+
+#[derive(Debug)]
+pub struct FileNotExists(pub PathBuf);
+
+/// Contents of an entry whose hash matches its metadata.
+pub(crate) struct Stored {
+    pub bytes: Vec<u8>,
+    /// Schema version from metadata. Entries written before metadata existed are version 0.
+    pub schema_version: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    path: PathBuf,
+    /// Where the entry was stored before, with another codec. Read if `path` does not exist yet.
+    legacy: Option<PathBuf>,
+}
+
+impl Entry {
+    pub fn new(path: PathBuf) -> Self {
+        Entry { path, legacy: None }
+    }
+
+    pub fn with_legacy(mut self, legacy: PathBuf) -> Self {
+        if legacy != self.path {
+            self.legacy = Some(legacy);
+        }
+        self
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn meta_path(&self) -> PathBuf {
+        with_suffix(&self.path, META_SUFFIX)
+    }
+
+    /// Path of the file to read: the entry itself or its legacy file.
+    pub fn existing_path(&self) -> Option<&Path> {
+        std::iter::once(&self.path)
+            .chain(&self.legacy)
+            .find(|path| path.is_file())
+            .map(PathBuf::as_path)
+    }
+
+    pub fn exists(&self) -> bool {
+        self.existing_path().is_some()
+    }
+
+    /// Reads the sidecar. `None` for entries without one or with an unreadable one.
+    pub fn metadata(&self) -> Option<Metadata> {
+        let path = self.existing_path()?;
+        let json = fs::read(with_suffix(path, META_SUFFIX)).ok()?;
+        serde_json::from_slice(&json).ok()
+    }
+
+    /// When the entry was fetched, according to metadata, or file mtime as a fallback.
+    pub fn fetched_at(&self) -> Result<SystemTime, FileNotExists> {
+        if let Some(metadata) = self.metadata() {
+            return Ok(metadata.fetched_at);
+        }
+
+        let file_not_exists = |_| FileNotExists(self.path.to_owned());
+        fs::metadata(self.existing_path().unwrap_or(&self.path))
+            .map_err(file_not_exists)?
+            .modified()
+            .map_err(file_not_exists)
+    }
+
+    pub fn up_to_date(&self, stale: &Stale) -> Result<bool, FileNotExists> {
+        if !self.exists() {
+            return Ok(false);
+        }
+
+        match stale {
+            Stale::Never => Ok(true),
+            Stale::After(stale_after) => {
+                let until = self.fetched_at()? + *stale_after;
+                Ok(until > SystemTime::now())
+            }
+            Stale::ReloadEveryTime => Ok(false),
+        }
+    }
+
+    /// Reads the entry. `None` if the file is missing or its content does not match the recorded hash.
+    pub(crate) fn read(&self) -> std::io::Result<Option<Stored>> {
+        let Some(path) = self.existing_path() else {
+            return Ok(None);
+        };
+
+        let bytes = fs::read(path)?;
+        match self.metadata() {
+            Some(metadata) if !metadata.matches(&bytes) => Ok(None),
+            Some(metadata) => Ok(Some(Stored {
+                bytes,
+                schema_version: metadata.schema_version,
+            })),
+            None => Ok(Some(Stored {
+                bytes,
+                schema_version: 0,
+            })),
+        }
+    }
+
+    /// Atomically replaces the entry and its sidecar.
+    pub(crate) fn write(&self, bytes: &[u8], metadata: &Metadata) -> std::io::Result<()> {
+        if let Some(dir) = self.path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        write_atomic(&self.path, bytes)?;
+        write_atomic(&self.meta_path(), &serde_json::to_vec_pretty(metadata)?)
+    }
+
+    pub fn remove(&self) -> std::io::Result<()> {
+        let legacy = self
+            .legacy
+            .iter()
+            .flat_map(|path| [path.to_owned(), with_suffix(path, META_SUFFIX)]);
+        for path in [self.path.to_owned(), self.meta_path()]
+            .into_iter()
+            .chain(legacy)
+        {
+            match fs::remove_file(path) {
+                Err(err) if err.kind() != std::io::ErrorKind::NotFound => return Err(err),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Numbers temporary files, so that concurrent writes of one process do not share one.
+static TMP_COUNTER: AtomicU64 = AtomicU64::new(0);
+
+/// Writes into a temporary file in the same dir and renames it over the target,
+/// so readers never observe a half-written file.
+fn write_atomic(path: &Path, bytes: &[u8]) -> std::io::Result<()> {
+    let n = TMP_COUNTER.fetch_add(1, Ordering::Relaxed);
+    let tmp = with_suffix(path, &format!(".{}.{n}.tmp", std::process::id()));
+    let result = File::create(&tmp).and_then(|mut file| {
+        file.write_all(bytes)?;
+        file.sync_all()
+    });
+
+    match result.and_then(|_| fs::rename(&tmp, path)) {
+        Ok(()) => Ok(()),
+        Err(err) => {
+            let _ = fs::remove_file(&tmp);
+            Err(err)
+        }
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut s: OsString = path.as_os_str().to_owned();
+    s.push(suffix);
+    PathBuf::from(s)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::FetchInfo;
+
+    #[test]
+    fn tampered_entry_is_not_read() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = Entry::new(dir.path().join("data.json"));
+        let bytes = br#"{"a":1}"#;
+        entry
+            .write(bytes, &Metadata::new(bytes, 2, FetchInfo::default()))
+            .unwrap();
+
+        let stored = entry.read().unwrap().unwrap();
+        assert_eq!(stored.bytes, bytes);
+        assert_eq!(stored.schema_version, 2);
+
+        fs::write(entry.path(), br#"{"a":2}"#).unwrap();
+        assert!(entry.read().unwrap().is_none());
+    }
+
+    #[test]
+    fn entry_without_sidecar_is_version_zero() {
+        let dir = tempfile::tempdir().unwrap();
+        let entry = Entry::new(dir.path().join("legacy.json"));
+        fs::write(entry.path(), "[]").unwrap();
+
+        assert_eq!(entry.read().unwrap().unwrap().schema_version, 0);
+        assert!(entry.up_to_date(&Stale::Never).unwrap());
+    }
+
+    #[test]
+    fn concurrent_writes_do_not_share_temp_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("prices.json");
+        let writers: Vec<_> = (0..8u8)
+            .map(|i| {
+                let path = path.clone();
+                std::thread::spawn(move || write_atomic(&path, &[i; 4096]))
+            })
+            .collect();
+        for writer in writers {
+            writer.join().unwrap().unwrap();
+        }
+
+        let bytes = fs::read(&path).unwrap();
+        assert_eq!(bytes.len(), 4096);
+        assert!(bytes.iter().all(|b| *b == bytes[0]));
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+}
@@ -1,9 +1,25 @@
-use std::time::{Duration, SystemTime};
-use std::{
-    fs::{self, File},
-    io::BufReader,
-    path::PathBuf,
-};
+mod codec;
+mod config;
+mod entry;
+mod location;
+mod memory;
+mod metadata;
+mod policy;
+pub mod registry;
+mod send;
+
+pub use codec::Codec;
+pub use config::{Config, ConfigBuilder, Stale};
+pub use entry::{Entry, FileNotExists};
+pub use location::{cache_root, default_cache_dir, keyed_filename, CACHE_DIR_ENV, KEY_PLACEHOLDER};
+pub use memory::MemoryCache;
+pub use metadata::{content_hash, FetchInfo, Metadata};
+pub use policy::{LoadPolicy, Loaded};
+pub use send::SendDataFetcher;
+
+use entry::Stored;
+use std::fmt::Display;
+use std::path::PathBuf;
 
 #[allow(async_fn_in_trait)]
 pub trait DataFetcher
@@ -12,10 +28,25 @@ where
 {
     type Item: serde::Serialize + serde::de::DeserializeOwned;
     type Error: From<serde_json::Error> + From<std::io::Error>;
+    /// Version of the `Item` layout. Bump it on breaking changes of `Item`:
+    /// cached entries of other versions go through [`DataFetcher::migrate`].
+    const SCHEMA_VERSION: u32 = 0;
     async fn fetch(&self) -> Result<Self::Item, Self::Error>;
     fn config(&self) -> &Config;
     fn config_mut(&mut self) -> &mut Config;
 
+    /// Same as fetch, but also reports where the data came from. Stored in entry metadata.
+    async fn fetch_with_info(&self) -> Result<(Self::Item, FetchInfo), Self::Error> {
+        Ok((self.fetch().await?, FetchInfo::default()))
+    }
+
+    /// Converts a cached entry of another schema version.
+    /// Returning `None` (the default) discards the entry and refetches.
+    fn migrate(&self, from_version: u32, value: serde_json::Value) -> Option<Self::Item> {
+        let _ = (from_version, value);
+        None
+    }
+
     async fn load_with_mut_default_config(
         f: impl Fn(&mut Config),
     ) -> Result<Self::Item, Self::Error> {
@@ -31,148 +62,433 @@ where
     }
 
     async fn load(&self) -> Result<Self::Item, Self::Error> {
-        let config = self.config();
-        match up_to_date(&self.file_path(), &config.stale).unwrap_or(false) {
-            true => {
-                let file = File::open(self.file_path())?;
-                let reader = BufReader::new(file);
+        Ok(self.load_detailed().await?.into_inner())
+    }
 
-                Ok(serde_json::from_reader(reader)?)
-            }
-            false => {
-                let fetched = self.fetch().await?;
-                if config.save {
-                    self.save(&fetched)?;
-                }
+    /// Loads according to [`Config::policy`] and tells whether the data is fresh.
+    async fn load_detailed(&self) -> Result<Loaded<Self::Item>, Self::Error> {
+        let config = self.config();
+        policy::load_with_policy(
+            &self.entry(),
+            &config.stale,
+            config.policy,
+            || self.read_cached(),
+            || async {
+                let (fetched, info) = self.fetch_with_info().await?;
+                self.save_with_info(&fetched, info)?;
                 Ok(fetched)
-            }
-        }
+            },
+        )
+        .await
+    }
+
+    /// Refetches and saves the entry on a separate thread, so that the next `load` gets fresh data.
+    fn revalidate_in_background(&self) -> std::thread::JoinHandle<Result<(), Self::Error>>
+    where
+        Self: Clone + Send + 'static,
+        Self::Error: Send + 'static,
+    {
+        let fetcher = self.clone();
+        policy::spawn_blocking_thread(move || async move { fetcher.update().await })
     }
 
-    fn filename(&self) -> &'static str {
-        self.config().filename
+    /// Reads the cache entry regardless of its age.
+    /// `None` if there is no entry, it is corrupted or of a schema version that could not be migrated.
+    fn read_cached(&self) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(stored) = self.entry().read()? else {
+            return Ok(None);
+        };
+        Ok(decode(stored, Self::SCHEMA_VERSION, |version, value| {
+            self.migrate(version, value)
+        }))
     }
 
+    fn filename(&self) -> &str {
+        &self.config().filename
+    }
+
+    /// Path of the entry, with the extension of [`Config::codec`].
     fn file_path(&self) -> PathBuf {
-        let dir = project_root::get_project_root()
-            .unwrap_or_else(|_| std::env::current_dir().unwrap())
-            .join("data");
-        if !dir.exists() {
-            std::fs::create_dir_all(&dir).unwrap();
-        }
+        let config = self.config();
+        config.codec.path(&cache_root(config).join(self.filename()))
+    }
 
-        dir.join(self.filename())
+    fn entry(&self) -> Entry {
+        Entry::new(self.file_path()).with_legacy(cache_root(self.config()).join(self.filename()))
     }
 
     fn up_to_date(&self) -> bool {
-        up_to_date(&self.file_path(), &self.config().stale).unwrap_or(false)
+        self.entry()
+            .up_to_date(&self.config().stale)
+            .unwrap_or(false)
     }
 
     async fn update(&self) -> Result<(), Self::Error> {
-        let t = self.fetch().await?;
-        self.save(&t)?;
+        let (t, info) = self.fetch_with_info().await?;
+        self.save_with_info(&t, info)?;
         Ok(())
     }
 
     fn save(&self, data: &Self::Item) -> Result<(), Self::Error> {
+        self.save_with_info(data, FetchInfo::default())
+    }
+
+    fn save_with_info(&self, data: &Self::Item, info: FetchInfo) -> Result<(), Self::Error> {
         if !self.config().save {
             return Ok(());
         }
 
-        let json = serde_json::to_string(data)?;
-        fs::write(self.file_path(), json)?;
-
+        let config = self.config();
+        encode_and_write(
+            &self.entry(),
+            config.codec,
+            data,
+            Self::SCHEMA_VERSION,
+            info,
+        )?;
         Ok(())
     }
 }
 
-#[derive(Debug)]
-pub struct FileNotExists(pub PathBuf);
-fn up_to_date(path: &PathBuf, stale: &Stale) -> Result<bool, FileNotExists> {
-    if !path.exists() {
-        return Ok(false);
+/// Fetcher that manages many cache entries of the same kind, one per key:
+/// prices per league, sheets per spreadsheet id.
+///
+/// [`Config::filename`] is a template, `{key}` is replaced with the key.
+/// See [`keyed_filename`].
+#[allow(async_fn_in_trait)]
+pub trait KeyedDataFetcher
+where
+    Self: Default,
+{
+    type Key: Display;
+    type Item: serde::Serialize + serde::de::DeserializeOwned;
+    type Error: From<serde_json::Error> + From<std::io::Error>;
+    /// See [`DataFetcher::SCHEMA_VERSION`].
+    const SCHEMA_VERSION: u32 = 0;
+    async fn fetch(&self, key: &Self::Key) -> Result<Self::Item, Self::Error>;
+    fn config(&self) -> &Config;
+    fn config_mut(&mut self) -> &mut Config;
+
+    async fn fetch_with_info(
+        &self,
+        key: &Self::Key,
+    ) -> Result<(Self::Item, FetchInfo), Self::Error> {
+        Ok((self.fetch(key).await?, FetchInfo::default()))
     }
 
-    match stale {
-        Stale::Never => Ok(true),
-        Stale::After(stale_after) => {
-            let file_not_exists = |_| FileNotExists(path.to_owned());
+    /// See [`DataFetcher::migrate`].
+    fn migrate(&self, from_version: u32, value: serde_json::Value) -> Option<Self::Item> {
+        let _ = (from_version, value);
+        None
+    }
 
-            let last_modified = fs::metadata(path)
-                .map_err(file_not_exists)?
-                .modified()
-                .map_err(file_not_exists)?;
-            let until = last_modified + *stale_after;
-            Ok(until > SystemTime::now())
-        }
-        Stale::ReloadEveryTime => Ok(false),
+    fn default_with_mut_config(f: impl Fn(&mut Config)) -> Self {
+        let mut default = Self::default();
+        f(default.config_mut());
+        default
     }
-}
 
-#[derive(Default, Clone)]
-pub struct Config {
-    pub save: bool,
-    pub filename: &'static str,
-    pub stale: Stale,
-}
+    async fn load(&self, key: &Self::Key) -> Result<Self::Item, Self::Error> {
+        Ok(self.load_detailed(key).await?.into_inner())
+    }
 
-impl Config {
-    pub fn builder() -> ConfigBuilder {
-        ConfigBuilder::default()
+    /// See [`DataFetcher::load_detailed`].
+    async fn load_detailed(&self, key: &Self::Key) -> Result<Loaded<Self::Item>, Self::Error> {
+        let config = self.config();
+        policy::load_with_policy(
+            &self.entry(key),
+            &config.stale,
+            config.policy,
+            || self.read_cached(key),
+            || async {
+                let (fetched, info) = self.fetch_with_info(key).await?;
+                self.save_with_info(key, &fetched, info)?;
+                Ok(fetched)
+            },
+        )
+        .await
+    }
+
+    /// See [`DataFetcher::revalidate_in_background`].
+    fn revalidate_in_background(
+        &self,
+        key: Self::Key,
+    ) -> std::thread::JoinHandle<Result<(), Self::Error>>
+    where
+        Self: Clone + Send + 'static,
+        Self::Key: Send + 'static,
+        Self::Error: Send + 'static,
+    {
+        let fetcher = self.clone();
+        policy::spawn_blocking_thread(move || async move { fetcher.update(&key).await })
     }
 
-    pub fn stale(&mut self, stale: Stale) -> &mut Self {
-        self.stale = stale;
-        self
+    fn read_cached(&self, key: &Self::Key) -> Result<Option<Self::Item>, Self::Error> {
+        let Some(stored) = self.entry(key).read()? else {
+            return Ok(None);
+        };
+        Ok(decode(stored, Self::SCHEMA_VERSION, |version, value| {
+            self.migrate(version, value)
+        }))
     }
 
-    pub fn save(&mut self, save: bool) -> &mut Self {
-        self.save = save;
-        self
+    fn filename(&self, key: &Self::Key) -> String {
+        keyed_filename(&self.config().filename, key)
+    }
+
+    fn file_path(&self, key: &Self::Key) -> PathBuf {
+        let config = self.config();
+        config
+            .codec
+            .path(&cache_root(config).join(self.filename(key)))
     }
 
-    pub fn filename(&mut self, filename: &'static str) -> &mut Self {
-        self.filename = filename;
-        self
+    fn entry(&self, key: &Self::Key) -> Entry {
+        Entry::new(self.file_path(key))
+            .with_legacy(cache_root(self.config()).join(self.filename(key)))
+    }
+
+    fn up_to_date(&self, key: &Self::Key) -> bool {
+        self.entry(key)
+            .up_to_date(&self.config().stale)
+            .unwrap_or(false)
+    }
+
+    async fn update(&self, key: &Self::Key) -> Result<(), Self::Error> {
+        let (t, info) = self.fetch_with_info(key).await?;
+        self.save_with_info(key, &t, info)?;
+        Ok(())
+    }
+
+    fn save(&self, key: &Self::Key, data: &Self::Item) -> Result<(), Self::Error> {
+        self.save_with_info(key, data, FetchInfo::default())
+    }
+
+    fn save_with_info(
+        &self,
+        key: &Self::Key,
+        data: &Self::Item,
+        info: FetchInfo,
+    ) -> Result<(), Self::Error> {
+        if !self.config().save {
+            return Ok(());
+        }
+
+        let config = self.config();
+        encode_and_write(
+            &self.entry(key),
+            config.codec,
+            data,
+            Self::SCHEMA_VERSION,
+            info,
+        )?;
+        Ok(())
     }
 }
 
-#[derive(Default, Clone)]
-pub enum Stale {
-    #[default]
-    Never,
-    After(Duration),
-    ReloadEveryTime,
+fn decode<T: serde::de::DeserializeOwned>(
+    stored: Stored,
+    schema_version: u32,
+    migrate: impl FnOnce(u32, serde_json::Value) -> Option<T>,
+) -> Option<T> {
+    if stored.schema_version == schema_version {
+        return Codec::decode(&stored.bytes).ok();
+    }
+
+    let value = Codec::decode(&stored.bytes).ok()?;
+    migrate(stored.schema_version, value)
 }
 
-#[derive(Default)]
-pub struct ConfigBuilder {
-    pub save: bool,
-    pub filename: &'static str,
-    pub stale: Stale,
+fn encode_and_write<T: serde::Serialize>(
+    entry: &Entry,
+    codec: Codec,
+    data: &T,
+    schema_version: u32,
+    info: FetchInfo,
+) -> Result<(), std::io::Error> {
+    let bytes = codec.encode(data)?;
+    entry.write(&bytes, &Metadata::new(&bytes, schema_version, info))
 }
 
-impl ConfigBuilder {
-    pub fn stale(mut self, stale: Stale) -> Self {
-        self.stale = stale;
-        self
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct LeagueFetcher(Config);
+
+    impl KeyedDataFetcher for LeagueFetcher {
+        type Key = String;
+        type Item = String;
+        type Error = std::io::Error;
+
+        async fn fetch(&self, key: &String) -> Result<String, std::io::Error> {
+            Ok(format!("prices for {key}"))
+        }
+
+        fn config(&self) -> &Config {
+            &self.0
+        }
+
+        fn config_mut(&mut self) -> &mut Config {
+            &mut self.0
+        }
     }
 
-    pub fn save(mut self, save: bool) -> Self {
-        self.save = save;
-        self
+    #[tokio::test]
+    async fn keyed_entries_are_saved_separately() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetcher = LeagueFetcher::default_with_mut_config(|config| {
+            config
+                .save(true)
+                .filename("prices/{key}.json")
+                .cache_dir(dir.path());
+        });
+
+        let standard = String::from("Standard");
+        let settlers = String::from("Settlers");
+        fetcher.load(&standard).await.unwrap();
+        fetcher.load(&settlers).await.unwrap();
+
+        assert!(dir.path().join("prices/Standard.json").exists());
+        assert!(dir.path().join("prices/Settlers.json").exists());
+        assert!(fetcher.up_to_date(&standard));
+        assert_eq!(
+            fetcher.read_cached(&settlers).unwrap().unwrap(),
+            "prices for Settlers"
+        );
     }
 
-    pub fn filename(mut self, filename: &'static str) -> Self {
-        self.filename = filename;
-        self
+    #[derive(Default)]
+    struct NamesFetcher(Config);
+
+    impl DataFetcher for NamesFetcher {
+        type Item = Vec<String>;
+        type Error = std::io::Error;
+        const SCHEMA_VERSION: u32 = 1;
+
+        async fn fetch(&self) -> Result<Vec<String>, std::io::Error> {
+            Ok(vec![String::from("fetched")])
+        }
+
+        fn migrate(&self, from_version: u32, value: serde_json::Value) -> Option<Vec<String>> {
+            match from_version {
+                0 => Some(vec![value.as_str()?.to_owned()]),
+                _ => None,
+            }
+        }
+
+        fn config(&self) -> &Config {
+            &self.0
+        }
+
+        fn config_mut(&mut self) -> &mut Config {
+            &mut self.0
+        }
     }
 
-    pub fn build(self) -> Config {
-        Config {
-            save: self.save,
-            filename: self.filename,
-            stale: self.stale,
+    #[tokio::test]
+    async fn older_schema_is_migrated() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetcher = NamesFetcher::default_with_mut_config(|config| {
+            config
+                .save(true)
+                .filename("names.json")
+                .cache_dir(dir.path());
+        });
+        std::fs::write(fetcher.file_path(), r#""migrated""#).unwrap();
+
+        assert_eq!(fetcher.load().await.unwrap(), vec!["migrated"]);
+
+        std::fs::write(fetcher.file_path(), r#"{"unknown": true}"#).unwrap();
+        assert_eq!(fetcher.load().await.unwrap(), vec!["fetched"]);
+        assert_eq!(fetcher.entry().metadata().unwrap().schema_version, 1);
+    }
+
+    #[tokio::test]
+    async fn corrupt_entry_is_refetched() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetcher = NamesFetcher::default_with_mut_config(|config| {
+            config
+                .save(true)
+                .filename("names.json")
+                .cache_dir(dir.path());
+        });
+        // Truncated, but with a sidecar of the same bytes and the current schema version.
+        let bytes = br#"["trunc"#;
+        fetcher
+            .entry()
+            .write(bytes, &Metadata::new(bytes, 1, FetchInfo::default()))
+            .unwrap();
+
+        assert!(fetcher.read_cached().unwrap().is_none());
+        assert_eq!(fetcher.load().await.unwrap(), vec!["fetched"]);
+    }
+
+    #[derive(Default)]
+    struct OfflineFetcher(Config);
+
+    impl DataFetcher for OfflineFetcher {
+        type Item = String;
+        type Error = std::io::Error;
+
+        async fn fetch(&self) -> Result<String, std::io::Error> {
+            Err(std::io::Error::other("no network"))
         }
+
+        fn config(&self) -> &Config {
+            &self.0
+        }
+
+        fn config_mut(&mut self) -> &mut Config {
+            &mut self.0
+        }
+    }
+
+    #[tokio::test]
+    async fn stale_entry_is_served_when_fetch_fails() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut fetcher = OfflineFetcher::default_with_mut_config(|config| {
+            config
+                .save(true)
+                .filename("poeData.json")
+                .stale(Stale::ReloadEveryTime)
+                .cache_dir(dir.path());
+        });
+        assert!(fetcher.load().await.is_err());
+
+        fetcher.save(&String::from("old")).unwrap();
+        assert!(fetcher.load().await.is_err());
+
+        fetcher
+            .config_mut()
+            .policy(LoadPolicy::StaleOnError { max_age: None });
+        let loaded = fetcher.load_detailed().await.unwrap();
+        assert!(loaded.is_stale());
+        assert_eq!(loaded.into_inner(), "old");
+
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        fetcher.config_mut().policy(LoadPolicy::StaleOnError {
+            max_age: Some(std::time::Duration::from_millis(1)),
+        });
+        assert!(fetcher.load().await.is_err());
+
+        fetcher.config_mut().policy(LoadPolicy::Offline);
+        assert_eq!(fetcher.load().await.unwrap(), "old");
+    }
+
+    #[tokio::test]
+    async fn plain_json_is_read_after_switching_codec() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut fetcher = OfflineFetcher::default_with_mut_config(|config| {
+            config
+                .save(true)
+                .filename("cardElementData.json")
+                .cache_dir(dir.path());
+        });
+        fetcher.save(&String::from("from json")).unwrap();
+
+        fetcher.config_mut().codec(Codec::Cbor);
+        assert_eq!(fetcher.load().await.unwrap(), "from json");
     }
 }
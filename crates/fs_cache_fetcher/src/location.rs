@@ -0,0 +1,94 @@
+//! Where cache entries live on disk.
+
+use crate::Config;
+use std::fmt::Display;
+use std::path::PathBuf;
+
+/// Environment variable that sets the cache root for every fetcher without an explicit
+/// [`Config::cache_dir`].
+pub const CACHE_DIR_ENV: &str = "DIVICARDS_CACHE_DIR";
+
+/// Placeholder in [`Config::filename`] replaced with the entry key by keyed fetchers.
+pub const KEY_PLACEHOLDER: &str = "{key}";
+
+const APP_DIR_NAME: &str = "divicards";
+
+/// Resolves the cache root in order of priority:
+/// 1. [`Config::cache_dir`], set with the builder or `config_mut`
+/// 2. [`CACHE_DIR_ENV`] environment variable
+/// 3. `data` dir of the cargo project, when running from a source checkout
+/// 4. platform cache dir (`$XDG_CACHE_HOME/divicards`, `~/.cache/divicards`, ...)
+pub fn cache_root(config: &Config) -> PathBuf {
+    if let Some(dir) = &config.cache_dir {
+        return dir.to_owned();
+    }
+
+    if let Some(dir) = std::env::var_os(CACHE_DIR_ENV).filter(|dir| !dir.is_empty()) {
+        return PathBuf::from(dir);
+    }
+
+    if let Ok(project_root) = project_root::get_project_root() {
+        return project_root.join("data");
+    }
+
+    default_cache_dir()
+}
+
+/// Platform cache dir for installed binaries.
+pub fn default_cache_dir() -> PathBuf {
+    dirs::cache_dir()
+        .unwrap_or_else(std::env::temp_dir)
+        .join(APP_DIR_NAME)
+}
+
+/// Fills [`KEY_PLACEHOLDER`] of a filename template with the key.
+/// Templates without a placeholder get the key as a prefix: `prices.json` -> `Standard-prices.json`.
+pub fn keyed_filename(template: &str, key: &impl Display) -> String {
+    let key = sanitize_key(&key.to_string());
+    match template.contains(KEY_PLACEHOLDER) {
+        true => template.replace(KEY_PLACEHOLDER, &key),
+        false => format!("{key}-{template}"),
+    }
+}
+
+/// Keys come from user data (league names, spreadsheet ids), so they must not escape the cache root.
+fn sanitize_key(key: &str) -> String {
+    key.chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c => c,
+        })
+        .collect::<String>()
+        .replace("..", "_")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keyed_filename_template() {
+        assert_eq!(
+            keyed_filename("prices/{key}.json", &"Settlers"),
+            "prices/Settlers.json"
+        );
+        assert_eq!(
+            keyed_filename("prices.json", &"Standard"),
+            "Standard-prices.json"
+        );
+    }
+
+    #[test]
+    fn keys_cannot_escape_cache_root() {
+        assert_eq!(
+            keyed_filename("sheets/{key}.json", &"../../etc/passwd"),
+            "sheets/____etc_passwd.json"
+        );
+    }
+
+    #[test]
+    fn config_cache_dir_has_priority() {
+        let config = Config::builder().cache_dir("/tmp/somewhere").build();
+        assert_eq!(cache_root(&config), PathBuf::from("/tmp/somewhere"));
+    }
+}
@@ -0,0 +1,157 @@
+use crate::SendDataFetcher;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::sync::OnceCell;
+
+/// One load of the value. Replaced with an empty cell when the value expires or is invalidated,
+/// loads started before that keep their own cell.
+type Slot<T> = Arc<OnceCell<(Arc<T>, Instant)>>;
+
+/// Shared in-memory tier in front of a [`SendDataFetcher`].
+///
+/// Loads go to disk or network only when the memory value is missing or older than `ttl`.
+/// Concurrent loads wait for the one in flight instead of starting their own. No lock is held
+/// while fetching, so [`MemoryCache::invalidate`] never waits for a slow fetch.
+pub struct MemoryCache<F: SendDataFetcher> {
+    fetcher: F,
+    ttl: Option<Duration>,
+    slot: Mutex<Slot<F::Item>>,
+}
+
+impl<F: SendDataFetcher> MemoryCache<F> {
+    /// `ttl` of `None` keeps the value until [`MemoryCache::invalidate`].
+    pub fn new(fetcher: F, ttl: Option<Duration>) -> Self {
+        MemoryCache {
+            fetcher,
+            ttl,
+            slot: Mutex::default(),
+        }
+    }
+
+    pub fn fetcher(&self) -> &F {
+        &self.fetcher
+    }
+
+    pub async fn load(&self) -> Result<Arc<F::Item>, F::Error> {
+        let slot = {
+            let mut slot = self.slot.lock().unwrap();
+            let expired = slot.get().is_some_and(|(_, loaded_at)| {
+                self.ttl.is_some_and(|ttl| loaded_at.elapsed() >= ttl)
+            });
+            if expired {
+                *slot = Slot::default();
+            }
+            Arc::clone(&slot)
+        };
+
+        let (item, _) = slot
+            .get_or_try_init(|| async {
+                let item = self.fetcher.load_send().await?;
+                Ok::<_, F::Error>((Arc::new(item), Instant::now()))
+            })
+            .await?;
+        Ok(Arc::clone(item))
+    }
+
+    pub async fn invalidate(&self) {
+        *self.slot.lock().unwrap() = Slot::default();
+    }
+}
+
+impl<F: SendDataFetcher> Default for MemoryCache<F> {
+    fn default() -> Self {
+        MemoryCache::new(F::default(), None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Config, DataFetcher};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static FETCHES: AtomicUsize = AtomicUsize::new(0);
+
+    #[derive(Default)]
+    struct SlowFetcher(Config);
+
+    impl SendDataFetcher for SlowFetcher {
+        type Item = u32;
+        type Error = std::io::Error;
+
+        async fn fetch(&self) -> Result<u32, std::io::Error> {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            Ok(FETCHES.fetch_add(1, Ordering::SeqCst) as u32)
+        }
+
+        fn config(&self) -> &Config {
+            &self.0
+        }
+
+        fn config_mut(&mut self) -> &mut Config {
+            &mut self.0
+        }
+    }
+
+    #[tokio::test]
+    async fn concurrent_loads_fetch_once() {
+        let cache = Arc::new(MemoryCache::<SlowFetcher>::default());
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let cache = Arc::clone(&cache);
+                tokio::spawn(async move { *cache.load().await.unwrap() })
+            })
+            .collect();
+
+        for handle in handles {
+            assert_eq!(handle.await.unwrap(), 0);
+        }
+        assert_eq!(FETCHES.load(Ordering::SeqCst), 1);
+
+        cache.invalidate().await;
+        assert_eq!(*cache.load().await.unwrap(), 1);
+    }
+
+    #[derive(Default)]
+    struct UrlFetcher(Config);
+
+    impl SendDataFetcher for UrlFetcher {
+        type Item = u32;
+        type Error = std::io::Error;
+
+        async fn fetch(&self) -> Result<u32, std::io::Error> {
+            Ok(7)
+        }
+
+        async fn fetch_with_info(&self) -> Result<(u32, crate::FetchInfo), std::io::Error> {
+            let info = crate::FetchInfo::new(Some("https://example.com/data.json".to_owned()));
+            Ok((7, info))
+        }
+
+        fn config(&self) -> &Config {
+            &self.0
+        }
+
+        fn config_mut(&mut self) -> &mut Config {
+            &mut self.0
+        }
+    }
+
+    #[tokio::test]
+    async fn load_send_keeps_fetch_info() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetcher = UrlFetcher::default_with_mut_config(|config| {
+            config
+                .save(true)
+                .filename("data.json")
+                .cache_dir(dir.path());
+        });
+        assert_eq!(fetcher.load_send().await.unwrap(), 7);
+
+        let metadata = fetcher.entry().metadata().unwrap();
+        assert_eq!(
+            metadata.source_url.as_deref(),
+            Some("https://example.com/data.json")
+        );
+    }
+}
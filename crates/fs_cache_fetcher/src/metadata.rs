@@ -0,0 +1,60 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::time::SystemTime;
+
+/// Sidecar record stored next to every cache entry as `<file>.meta.json`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Metadata {
+    pub fetched_at: SystemTime,
+    pub source_url: Option<String>,
+    pub etag: Option<String>,
+    /// Version of `fs_cache_fetcher` that wrote the entry.
+    pub crate_version: String,
+    /// [`crate::DataFetcher::SCHEMA_VERSION`] of the fetcher that wrote the entry.
+    pub schema_version: u32,
+    /// Hex-encoded SHA-256 of the entry file.
+    pub content_hash: String,
+}
+
+impl Metadata {
+    pub fn new(bytes: &[u8], schema_version: u32, info: FetchInfo) -> Self {
+        Metadata {
+            fetched_at: SystemTime::now(),
+            source_url: info.source_url,
+            etag: info.etag,
+            crate_version: env!("CARGO_PKG_VERSION").to_owned(),
+            schema_version,
+            content_hash: content_hash(bytes),
+        }
+    }
+
+    pub fn matches(&self, bytes: &[u8]) -> bool {
+        self.content_hash == content_hash(bytes)
+    }
+}
+
+/// Details about the origin of fetched data, returned by `fetch_with_info`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FetchInfo {
+    pub source_url: Option<String>,
+    pub etag: Option<String>,
+}
+
+impl FetchInfo {
+    pub fn new(source_url: Option<String>) -> Self {
+        FetchInfo {
+            source_url,
+            etag: None,
+        }
+    }
+
+    pub fn etag(mut self, etag: impl Into<String>) -> Self {
+        self.etag = Some(etag.into());
+        self
+    }
+}
+
+pub fn content_hash(bytes: &[u8]) -> String {
+    format!("{:x}", Sha256::digest(bytes))
+}
@@ -0,0 +1,123 @@
+use crate::{Entry, Stale};
+use std::future::Future;
+use std::time::Duration;
+
+/// What `load` does when the cache entry is not up to date.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum LoadPolicy {
+    /// Fetch, fail if fetching fails.
+    #[default]
+    Online,
+    /// Fetch, serve the outdated entry with a warning if fetching fails.
+    /// An entry older than `max_age` is not served, the fetch error is returned instead.
+    StaleOnError { max_age: Option<Duration> },
+    /// Never touch the network, serve any cached entry regardless of its age.
+    Offline,
+    /// Serve the outdated entry right away without fetching. Fetch only if there is no entry.
+    /// Use `revalidate_in_background` to refresh the entry for next time.
+    StaleWhileRevalidate,
+}
+
+/// Loaded data and where it came from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Loaded<T> {
+    /// Entry is up to date according to [`Stale`].
+    Cached(T),
+    Fetched(T),
+    /// Entry is outdated, but served according to [`LoadPolicy`].
+    Stale {
+        item: T,
+        age: Duration,
+    },
+}
+
+impl<T> Loaded<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            Loaded::Cached(item) => item,
+            Loaded::Fetched(item) => item,
+            Loaded::Stale { item, .. } => item,
+        }
+    }
+
+    pub fn is_stale(&self) -> bool {
+        matches!(self, Loaded::Stale { .. })
+    }
+}
+
+pub(crate) async fn load_with_policy<T, E, Fut>(
+    entry: &Entry,
+    stale: &Stale,
+    policy: LoadPolicy,
+    read_cached: impl Fn() -> Result<Option<T>, E>,
+    fetch_and_save: impl FnOnce() -> Fut,
+) -> Result<Loaded<T>, E>
+where
+    E: From<std::io::Error>,
+    Fut: Future<Output = Result<T, E>>,
+{
+    if entry.up_to_date(stale).unwrap_or(false) {
+        if let Some(cached) = read_cached()? {
+            return Ok(Loaded::Cached(cached));
+        }
+    }
+
+    let age = || {
+        entry
+            .fetched_at()
+            .ok()
+            .and_then(|fetched_at| fetched_at.elapsed().ok())
+            .unwrap_or_default()
+    };
+    let serve_stale = |item: T| Loaded::Stale { item, age: age() };
+
+    match policy {
+        LoadPolicy::Online => Ok(Loaded::Fetched(fetch_and_save().await?)),
+        LoadPolicy::StaleOnError { max_age } => match fetch_and_save().await {
+            Ok(fetched) => Ok(Loaded::Fetched(fetched)),
+            Err(err) if max_age.is_some_and(|max_age| age() > max_age) => Err(err),
+            Err(err) => match read_cached() {
+                Ok(Some(cached)) => {
+                    tracing::warn!(
+                        "Could not fetch {}. Serving outdated cache entry.",
+                        entry.path().display()
+                    );
+                    Ok(serve_stale(cached))
+                }
+                _ => Err(err),
+            },
+        },
+        LoadPolicy::Offline => match read_cached()? {
+            Some(cached) => Ok(serve_stale(cached)),
+            None => Err(std::io::Error::new(
+                std::io::ErrorKind::NotFound,
+                format!(
+                    "Offline mode: no usable cache entry at {}",
+                    entry.path().display()
+                ),
+            )
+            .into()),
+        },
+        LoadPolicy::StaleWhileRevalidate => match read_cached()? {
+            Some(cached) => Ok(serve_stale(cached)),
+            None => Ok(Loaded::Fetched(fetch_and_save().await?)),
+        },
+    }
+}
+
+/// Runs the future to completion on a separate thread with its own runtime.
+/// Fetcher futures are not `Send`, so they cannot be spawned onto the caller's runtime.
+pub(crate) fn spawn_blocking_thread<E, Fut>(
+    f: impl FnOnce() -> Fut + Send + 'static,
+) -> std::thread::JoinHandle<Result<(), E>>
+where
+    E: From<std::io::Error> + Send + 'static,
+    Fut: Future<Output = Result<(), E>>,
+{
+    std::thread::spawn(move || {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()?;
+        runtime.block_on(f())
+    })
+}
@@ -0,0 +1,352 @@
+//! Type-erased collection of fetchers, for tools that inspect and maintain the cache.
+
+use crate::{
+    cache_root, keyed_filename, Codec, DataFetcher, Entry, KeyedDataFetcher, LoadPolicy, Metadata,
+    Stale, KEY_PLACEHOLDER,
+};
+use std::collections::BTreeSet;
+use std::fmt::Debug;
+use std::future::Future;
+use std::path::PathBuf;
+use std::pin::Pin;
+use std::str::FromStr;
+use std::time::{Duration, SystemTime};
+
+pub type RefreshFuture<'a> = Pin<Box<dyn Future<Output = Result<(), String>> + 'a>>;
+
+/// Object-safe view of a fetcher.
+pub trait CacheInspect {
+    fn entry(&self) -> Entry;
+    fn stale(&self) -> Stale;
+    fn policy(&self) -> LoadPolicy;
+    fn codec(&self) -> Codec;
+    fn up_to_date(&self) -> bool;
+    fn refresh(&self) -> RefreshFuture<'_>;
+}
+
+impl<F> CacheInspect for F
+where
+    F: DataFetcher,
+    F::Error: Debug,
+{
+    fn entry(&self) -> Entry {
+        DataFetcher::entry(self)
+    }
+
+    fn stale(&self) -> Stale {
+        self.config().stale.clone()
+    }
+
+    fn policy(&self) -> LoadPolicy {
+        self.config().policy
+    }
+
+    fn codec(&self) -> Codec {
+        self.config().codec
+    }
+
+    fn up_to_date(&self) -> bool {
+        DataFetcher::up_to_date(self)
+    }
+
+    fn refresh(&self) -> RefreshFuture<'_> {
+        Box::pin(async move { self.update().await.map_err(|err| format!("{err:?}")) })
+    }
+}
+
+/// One entry of a keyed fetcher, registered as a separate cache entry.
+pub struct KeyedEntry<F: KeyedDataFetcher> {
+    pub fetcher: F,
+    pub key: F::Key,
+}
+
+impl<F: KeyedDataFetcher> KeyedEntry<F> {
+    pub fn new(fetcher: F, key: F::Key) -> Self {
+        KeyedEntry { fetcher, key }
+    }
+}
+
+impl<F> CacheInspect for KeyedEntry<F>
+where
+    F: KeyedDataFetcher,
+    F::Error: Debug,
+{
+    fn entry(&self) -> Entry {
+        self.fetcher.entry(&self.key)
+    }
+
+    fn stale(&self) -> Stale {
+        self.fetcher.config().stale.clone()
+    }
+
+    fn policy(&self) -> LoadPolicy {
+        self.fetcher.config().policy
+    }
+
+    fn codec(&self) -> Codec {
+        self.fetcher.config().codec
+    }
+
+    fn up_to_date(&self) -> bool {
+        self.fetcher.up_to_date(&self.key)
+    }
+
+    fn refresh(&self) -> RefreshFuture<'_> {
+        Box::pin(async move {
+            self.fetcher
+                .update(&self.key)
+                .await
+                .map_err(|err| format!("{err:?}"))
+        })
+    }
+}
+
+/// Keys of the entries of a keyed fetcher that exist on disk, read back from the file names.
+/// Files of the current codec and legacy plain files are both found. Keys that do not parse,
+/// e.g. ones changed by key sanitizing, are skipped.
+pub fn keys_on_disk<F>(fetcher: &F) -> Vec<F::Key>
+where
+    F: KeyedDataFetcher,
+    F::Key: FromStr,
+{
+    let config = fetcher.config();
+    let legacy = cache_root(config).join(keyed_filename(&config.filename, &KEY_PLACEHOLDER));
+    let mut keys = BTreeSet::new();
+    for pattern in [config.codec.path(&legacy), legacy] {
+        let (Some(dir), Some(file_name)) = (pattern.parent(), pattern.file_name()) else {
+            continue;
+        };
+        let Some((prefix, suffix)) = file_name
+            .to_str()
+            .and_then(|name| name.split_once(KEY_PLACEHOLDER))
+        else {
+            continue;
+        };
+        let Ok(read_dir) = std::fs::read_dir(dir) else {
+            continue;
+        };
+        for name in read_dir
+            .flatten()
+            .filter_map(|e| e.file_name().into_string().ok())
+        {
+            if let Some(key) = name
+                .strip_prefix(prefix)
+                .and_then(|rest| rest.strip_suffix(suffix))
+                .filter(|key| !key.is_empty())
+            {
+                keys.insert(key.to_owned());
+            }
+        }
+    }
+
+    keys.into_iter()
+        .filter_map(|key| key.parse().ok())
+        .collect()
+}
+
+/// Snapshot of one registered cache entry.
+#[derive(Debug, Clone)]
+pub struct EntryInfo {
+    pub name: String,
+    pub path: PathBuf,
+    pub exists: bool,
+    pub size: Option<u64>,
+    pub age: Option<Duration>,
+    pub up_to_date: bool,
+    pub stale: Stale,
+    pub policy: LoadPolicy,
+    pub codec: Codec,
+    pub metadata: Option<Metadata>,
+}
+
+#[derive(Default)]
+pub struct Registry {
+    fetchers: Vec<(String, Box<dyn CacheInspect>)>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry::default()
+    }
+
+    pub fn register(
+        mut self,
+        name: impl Into<String>,
+        fetcher: impl CacheInspect + 'static,
+    ) -> Self {
+        self.fetchers.push((name.into(), Box::new(fetcher)));
+        self
+    }
+
+    /// Registers every entry of the keyed fetcher found on disk as `{name}/{key}`.
+    pub fn register_keyed<F>(mut self, name: impl Into<String>, fetcher: F) -> Self
+    where
+        F: KeyedDataFetcher + Clone + 'static,
+        F::Key: FromStr + 'static,
+        F::Error: Debug,
+    {
+        let name = name.into();
+        for key in keys_on_disk(&fetcher) {
+            let entry = KeyedEntry::new(fetcher.clone(), key);
+            self.fetchers
+                .push((format!("{name}/{}", entry.key), Box::new(entry)));
+        }
+        self
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.fetchers.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn CacheInspect> {
+        self.fetchers
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, fetcher)| fetcher.as_ref())
+    }
+
+    pub fn list(&self) -> Vec<EntryInfo> {
+        self.fetchers
+            .iter()
+            .map(|(name, fetcher)| {
+                let entry = fetcher.entry();
+                let path = entry.existing_path().unwrap_or(entry.path()).to_owned();
+                EntryInfo {
+                    name: name.to_owned(),
+                    exists: entry.exists(),
+                    size: std::fs::metadata(&path).ok().map(|m| m.len()),
+                    age: entry
+                        .fetched_at()
+                        .ok()
+                        .and_then(|fetched_at| SystemTime::now().duration_since(fetched_at).ok())
+                        .filter(|_| entry.exists()),
+                    up_to_date: fetcher.up_to_date(),
+                    stale: fetcher.stale(),
+                    policy: fetcher.policy(),
+                    codec: fetcher.codec(),
+                    metadata: entry.metadata(),
+                    path,
+                }
+            })
+            .collect()
+    }
+
+    /// Refetches one entry by its registered name.
+    pub async fn refresh(&self, name: &str) -> Result<(), String> {
+        match self.get(name) {
+            Some(fetcher) => fetcher.refresh().await,
+            None => Err(format!("Unknown cache entry: {name}")),
+        }
+    }
+
+    /// Removes entries older than `max_age`. Returns names of removed entries.
+    pub fn prune(&self, max_age: Duration) -> std::io::Result<Vec<String>> {
+        let mut removed = vec![];
+        for info in self.list() {
+            if info.age.is_some_and(|age| age > max_age) {
+                if let Some(fetcher) = self.get(&info.name) {
+                    fetcher.entry().remove()?;
+                    removed.push(info.name);
+                }
+            }
+        }
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Config;
+
+    #[derive(Default)]
+    struct Numbers(Config);
+
+    impl DataFetcher for Numbers {
+        type Item = Vec<u32>;
+        type Error = std::io::Error;
+
+        async fn fetch(&self) -> Result<Vec<u32>, std::io::Error> {
+            Ok(vec![1, 2, 3])
+        }
+
+        fn config(&self) -> &Config {
+            &self.0
+        }
+
+        fn config_mut(&mut self) -> &mut Config {
+            &mut self.0
+        }
+    }
+
+    #[tokio::test]
+    async fn list_refresh_prune() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetcher = Numbers::default_with_mut_config(|config| {
+            config
+                .save(true)
+                .filename("numbers.json")
+                .cache_dir(dir.path());
+        });
+        let registry = Registry::new().register("numbers", fetcher);
+
+        let info = &registry.list()[0];
+        assert!(!info.exists);
+        assert!(info.age.is_none());
+
+        registry.refresh("numbers").await.unwrap();
+        assert!(registry.refresh("unknown").await.is_err());
+        let info = &registry.list()[0];
+        assert!(info.exists);
+        assert!(info.up_to_date);
+        assert_eq!(info.size, Some(7));
+
+        assert!(registry
+            .prune(Duration::from_secs(3600))
+            .unwrap()
+            .is_empty());
+        assert_eq!(registry.prune(Duration::ZERO).unwrap(), vec!["numbers"]);
+        assert!(!registry.list()[0].exists);
+    }
+
+    #[derive(Default, Clone)]
+    struct Doubled(Config);
+
+    impl KeyedDataFetcher for Doubled {
+        type Key = u32;
+        type Item = u32;
+        type Error = std::io::Error;
+
+        async fn fetch(&self, key: &u32) -> Result<u32, std::io::Error> {
+            Ok(key * 2)
+        }
+
+        fn config(&self) -> &Config {
+            &self.0
+        }
+
+        fn config_mut(&mut self) -> &mut Config {
+            &mut self.0
+        }
+    }
+
+    #[tokio::test]
+    async fn keyed_entries_are_found_on_disk() {
+        let dir = tempfile::tempdir().unwrap();
+        let fetcher = Doubled::default_with_mut_config(|config| {
+            config
+                .save(true)
+                .filename("{key}-doubled.json")
+                .cache_dir(dir.path());
+        });
+        fetcher.update(&2).await.unwrap();
+        fetcher.update(&10).await.unwrap();
+        std::fs::write(dir.path().join("other.json"), "[]").unwrap();
+
+        let registry = Registry::new().register_keyed("doubled", fetcher);
+        let names: Vec<&str> = registry.names().collect();
+        assert_eq!(names, ["doubled/10", "doubled/2"]);
+        assert!(registry.list().iter().all(|info| info.exists));
+        registry.refresh("doubled/2").await.unwrap();
+    }
+}
@@ -0,0 +1,77 @@
+use crate::{policy, Config, DataFetcher, FetchInfo};
+use std::future::Future;
+
+/// [`DataFetcher`] whose futures are `Send`, so it can be used from `tokio::spawn` and axum handlers.
+///
+/// Every `SendDataFetcher` is also a [`DataFetcher`], so entries, metadata and policies work the same.
+pub trait SendDataFetcher
+where
+    Self: Default + Send + Sync,
+{
+    type Item: serde::Serialize + serde::de::DeserializeOwned + Send + Sync;
+    type Error: From<serde_json::Error> + From<std::io::Error> + Send;
+    /// See [`DataFetcher::SCHEMA_VERSION`].
+    const SCHEMA_VERSION: u32 = 0;
+    fn fetch(&self) -> impl Future<Output = Result<Self::Item, Self::Error>> + Send;
+    fn config(&self) -> &Config;
+    fn config_mut(&mut self) -> &mut Config;
+
+    /// See [`DataFetcher::fetch_with_info`].
+    fn fetch_with_info(
+        &self,
+    ) -> impl Future<Output = Result<(Self::Item, FetchInfo), Self::Error>> + Send {
+        async move { Ok((SendDataFetcher::fetch(self).await?, FetchInfo::default())) }
+    }
+
+    /// See [`DataFetcher::migrate`].
+    fn migrate(&self, from_version: u32, value: serde_json::Value) -> Option<Self::Item> {
+        let _ = (from_version, value);
+        None
+    }
+
+    /// Same as [`DataFetcher::load`], but the future is `Send`.
+    fn load_send(&self) -> impl Future<Output = Result<Self::Item, Self::Error>> + Send {
+        async move {
+            let config = SendDataFetcher::config(self);
+            let loaded = policy::load_with_policy(
+                &DataFetcher::entry(self),
+                &config.stale,
+                config.policy,
+                || DataFetcher::read_cached(self),
+                || async {
+                    let (fetched, info) = SendDataFetcher::fetch_with_info(self).await?;
+                    DataFetcher::save_with_info(self, &fetched, info)?;
+                    Ok(fetched)
+                },
+            )
+            .await?;
+            Ok(loaded.into_inner())
+        }
+    }
+}
+
+impl<F: SendDataFetcher> DataFetcher for F {
+    type Item = F::Item;
+    type Error = F::Error;
+    const SCHEMA_VERSION: u32 = <F as SendDataFetcher>::SCHEMA_VERSION;
+
+    async fn fetch(&self) -> Result<Self::Item, Self::Error> {
+        SendDataFetcher::fetch(self).await
+    }
+
+    async fn fetch_with_info(&self) -> Result<(Self::Item, FetchInfo), Self::Error> {
+        SendDataFetcher::fetch_with_info(self).await
+    }
+
+    fn config(&self) -> &Config {
+        SendDataFetcher::config(self)
+    }
+
+    fn config_mut(&mut self) -> &mut Config {
+        SendDataFetcher::config_mut(self)
+    }
+
+    fn migrate(&self, from_version: u32, value: serde_json::Value) -> Option<Self::Item> {
+        SendDataFetcher::migrate(self, from_version, value)
+    }
+}
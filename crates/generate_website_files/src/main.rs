@@ -60,11 +60,11 @@ async fn main() {
         .collect();
     write(&sources_hashmap, &json_dir, "sources2.json");
     write(&records, &json_dir, "records.json");
-    write(&poe_data, &json_dir, PoeData::filename());
+    write(&poe_data, &json_dir, &PoeData::filename());
     write(
         &card_element,
         &json_dir,
-        DivinationCardElementData::filename(),
+        &DivinationCardElementData::filename(),
     );
 
     match avatars::prepare_avatars_ts().await {
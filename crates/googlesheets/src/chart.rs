@@ -0,0 +1,164 @@
+//! Embedded charts for `spreadsheets.batchUpdate` (`addChart` request).
+//! https://developers.google.com/sheets/api/samples/charts
+
+use crate::format::GridRange;
+use crate::sheet::SheetId;
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChartKind {
+    Pie,
+    Bar,
+    Column,
+    Scatter,
+}
+
+/// Chart over a domain (labels or x values) and one series, both read from the sheet.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Chart {
+    kind: ChartKind,
+    title: String,
+    domain: GridRange,
+    series: GridRange,
+    axis_titles: Option<(String, String)>,
+    /// Sheet, zero-based row and column of the top-left corner.
+    anchor: (SheetId, u32, u32),
+}
+
+impl Chart {
+    /// The chart is placed at the top-left corner of the domain's sheet until [`Chart::anchor`].
+    pub fn new(
+        kind: ChartKind,
+        title: impl Into<String>,
+        domain: GridRange,
+        series: GridRange,
+    ) -> Self {
+        Chart {
+            kind,
+            title: title.into(),
+            domain,
+            series,
+            axis_titles: None,
+            anchor: (domain.sheet_id, 0, 0),
+        }
+    }
+
+    pub fn pie(title: impl Into<String>, domain: GridRange, series: GridRange) -> Self {
+        Chart::new(ChartKind::Pie, title, domain, series)
+    }
+
+    pub fn bar(title: impl Into<String>, domain: GridRange, series: GridRange) -> Self {
+        Chart::new(ChartKind::Bar, title, domain, series)
+    }
+
+    pub fn scatter(title: impl Into<String>, domain: GridRange, series: GridRange) -> Self {
+        Chart::new(ChartKind::Scatter, title, domain, series)
+    }
+
+    /// Titles of the domain and the value axis. Pie charts have no axes.
+    pub fn axis_titles(mut self, domain: impl Into<String>, values: impl Into<String>) -> Self {
+        self.axis_titles = Some((domain.into(), values.into()));
+        self
+    }
+
+    pub fn anchor(mut self, sheet_id: SheetId, row: u32, column: u32) -> Self {
+        self.anchor = (sheet_id, row, column);
+        self
+    }
+
+    fn spec(&self) -> Value {
+        let source = |range: &GridRange| json!({ "sourceRange": { "sources": [range] } });
+        match self.kind {
+            ChartKind::Pie => json!({
+                "title": self.title,
+                "pieChart": {
+                    "legendPosition": "RIGHT_LEGEND",
+                    "domain": source(&self.domain),
+                    "series": source(&self.series)
+                }
+            }),
+            ChartKind::Bar | ChartKind::Column | ChartKind::Scatter => {
+                // Bar charts are horizontal, their domain is on the left axis.
+                let (chart_type, domain_axis, value_axis) = match self.kind {
+                    ChartKind::Bar => ("BAR", "LEFT_AXIS", "BOTTOM_AXIS"),
+                    ChartKind::Column => ("COLUMN", "BOTTOM_AXIS", "LEFT_AXIS"),
+                    _ => ("SCATTER", "BOTTOM_AXIS", "LEFT_AXIS"),
+                };
+                let axis = match &self.axis_titles {
+                    Some((domain_title, value_title)) => json!([
+                        { "position": domain_axis, "title": domain_title },
+                        { "position": value_axis, "title": value_title }
+                    ]),
+                    None => json!([]),
+                };
+                json!({
+                    "title": self.title,
+                    "basicChart": {
+                        "chartType": chart_type,
+                        "legendPosition": "NO_LEGEND",
+                        "axis": axis,
+                        "domains": [{ "domain": source(&self.domain) }],
+                        "series": [{ "series": source(&self.series), "targetAxis": value_axis }]
+                    }
+                })
+            }
+        }
+    }
+
+    /// The `addChart` request object.
+    pub fn to_json(&self) -> Value {
+        let (sheet_id, row, column) = self.anchor;
+        json!({
+            "addChart": {
+                "chart": {
+                    "spec": self.spec(),
+                    "position": {
+                        "overlayPosition": {
+                            "anchorCell": { "sheetId": sheet_id, "rowIndex": row, "columnIndex": column }
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chart_requests() {
+        let sheet_id = SheetId::from(3);
+        let names = GridRange::new(sheet_id, 1..11, 0..1);
+        let sums = GridRange::new(sheet_id, 1..11, 3..4);
+
+        let pie = Chart::pie("Value share", names, sums)
+            .anchor(sheet_id, 0, 6)
+            .to_json();
+        let spec = &pie["addChart"]["chart"]["spec"];
+        assert_eq!(spec["title"], "Value share");
+        assert_eq!(
+            spec["pieChart"]["series"]["sourceRange"]["sources"][0],
+            json!({ "sheetId": 3, "startRowIndex": 1, "endRowIndex": 11, "startColumnIndex": 3, "endColumnIndex": 4 })
+        );
+        assert_eq!(
+            pie["addChart"]["chart"]["position"]["overlayPosition"]["anchorCell"]["columnIndex"],
+            6
+        );
+
+        let scatter = Chart::scatter("Weight vs price", names, sums)
+            .axis_titles("weight", "price")
+            .to_json();
+        let basic = &scatter["addChart"]["chart"]["spec"]["basicChart"];
+        assert_eq!(basic["chartType"], "SCATTER");
+        assert_eq!(basic["axis"][0]["title"], "weight");
+        assert_eq!(basic["series"][0]["targetAxis"], "LEFT_AXIS");
+
+        let bar = Chart::bar("Top", names, sums).to_json();
+        assert_eq!(
+            bar["addChart"]["chart"]["spec"]["basicChart"]["series"][0]["targetAxis"],
+            "BOTTOM_AXIS"
+        );
+    }
+}
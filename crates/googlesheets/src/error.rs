@@ -24,6 +24,51 @@ pub enum Error {
     ReqwestError(reqwest::Error),
     GoogleError(GoogleError),
     SerdeError(serde_json::Error),
+    ServiceAccount(ServiceAccountError),
+    Range(RangeError),
+    SheetNotFound(String),
+}
+
+#[derive(Debug)]
+pub enum RangeError {
+    Empty,
+    EmptySheetName,
+    UnterminatedQuote,
+    InvalidCell(String),
+}
+
+impl Display for RangeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RangeError::Empty => write!(f, "Range is empty"),
+            RangeError::EmptySheetName => write!(f, "Sheet name in range is empty"),
+            RangeError::UnterminatedQuote => write!(f, "Sheet name in range has no closing quote"),
+            RangeError::InvalidCell(cell) => write!(f, "Invalid cell reference in range: {cell}"),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ServiceAccountError {
+    Io(std::io::Error),
+    InvalidPrivateKey(String),
+    Signing,
+    TokenRequest(String),
+}
+
+impl Display for ServiceAccountError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ServiceAccountError::Io(err) => write!(f, "Could not read service account key. {err}"),
+            ServiceAccountError::InvalidPrivateKey(err) => {
+                write!(f, "Invalid service account private key. {err}")
+            }
+            ServiceAccountError::Signing => write!(f, "Could not sign service account JWT"),
+            ServiceAccountError::TokenRequest(err) => {
+                write!(f, "Service account token request failed. {err}")
+            }
+        }
+    }
 }
 
 impl Display for Error {
@@ -32,6 +77,20 @@ impl Display for Error {
             Error::ReqwestError(err) => err.fmt(f),
             Error::GoogleError(err) => err.fmt(f),
             Error::SerdeError(err) => err.fmt(f),
+            Error::ServiceAccount(err) => err.fmt(f),
+            Error::Range(err) => err.fmt(f),
+            Error::SheetNotFound(title) => write!(f, "Sheet {title} not found"),
+        }
+    }
+}
+
+/// Transport and parsing failures are exposed as the source, e.g. to get the `reqwest::Error`.
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::ReqwestError(err) => Some(err),
+            Error::SerdeError(err) => Some(err),
+            _ => None,
         }
     }
 }
@@ -53,3 +112,15 @@ impl From<serde_json::Error> for Error {
         Error::SerdeError(value)
     }
 }
+
+impl From<ServiceAccountError> for Error {
+    fn from(value: ServiceAccountError) -> Self {
+        Error::ServiceAccount(value)
+    }
+}
+
+impl From<RangeError> for Error {
+    fn from(value: RangeError) -> Self {
+        Error::Range(value)
+    }
+}
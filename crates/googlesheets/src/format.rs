@@ -0,0 +1,398 @@
+//! Formatting requests for `spreadsheets.batchUpdate`: header styling, number formats,
+//! column sizes and conditional color scales.
+//! https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets/request
+
+use crate::chart::Chart;
+use crate::error::Error;
+use crate::sheet::{parse_response, Credential, SheetId};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::ops::Range;
+
+/// Zero-based, end-exclusive grid range. Missing ends are unbounded.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct GridRange {
+    pub sheet_id: SheetId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_row_index: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_row_index: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_column_index: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_column_index: Option<u32>,
+}
+
+impl GridRange {
+    pub const fn sheet(sheet_id: SheetId) -> Self {
+        GridRange {
+            sheet_id,
+            start_row_index: None,
+            end_row_index: None,
+            start_column_index: None,
+            end_column_index: None,
+        }
+    }
+
+    /// Zero-based, end-exclusive rows and columns.
+    pub const fn new(sheet_id: SheetId, rows: Range<u32>, columns: Range<u32>) -> Self {
+        GridRange {
+            sheet_id,
+            start_row_index: Some(rows.start),
+            end_row_index: Some(rows.end),
+            start_column_index: Some(columns.start),
+            end_column_index: Some(columns.end),
+        }
+    }
+
+    /// Single column, rows from `start_row` to the end of the sheet.
+    pub const fn column(sheet_id: SheetId, column: u32, start_row: u32) -> Self {
+        GridRange {
+            sheet_id,
+            start_row_index: Some(start_row),
+            end_row_index: None,
+            start_column_index: Some(column),
+            end_column_index: Some(column + 1),
+        }
+    }
+
+    pub const fn row(sheet_id: SheetId, row: u32) -> Self {
+        GridRange {
+            sheet_id,
+            start_row_index: Some(row),
+            end_row_index: Some(row + 1),
+            start_column_index: None,
+            end_column_index: None,
+        }
+    }
+}
+
+/// Components are in `0.0..=1.0`. Responses omit zero components.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Default)]
+#[serde(default)]
+pub struct Color {
+    pub red: f32,
+    pub green: f32,
+    pub blue: f32,
+}
+
+impl Color {
+    pub const WHITE: Color = Color::rgb(1.0, 1.0, 1.0);
+    /// Green of the default Sheets color scale, #57BB8A.
+    pub const GREEN: Color = Color::rgb(0.34, 0.73, 0.54);
+
+    pub const fn rgb(red: f32, green: f32, blue: f32) -> Self {
+        Color { red, green, blue }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum NumberFormatType {
+    #[serde(rename = "NUMBER_FORMAT_TYPE_UNSPECIFIED")]
+    Unspecified,
+    Text,
+    Number,
+    Percent,
+    Currency,
+    Date,
+    Time,
+    DateTime,
+    Scientific,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct NumberFormat {
+    #[serde(rename = "type")]
+    pub kind: NumberFormatType,
+    #[serde(default)]
+    pub pattern: String,
+}
+
+impl NumberFormat {
+    /// Number with a pattern like `#,##0.00`.
+    pub fn number(pattern: impl Into<String>) -> Self {
+        NumberFormat {
+            kind: NumberFormatType::Number,
+            pattern: pattern.into(),
+        }
+    }
+
+    pub fn percent(pattern: impl Into<String>) -> Self {
+        NumberFormat {
+            kind: NumberFormatType::Percent,
+            pattern: pattern.into(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum FormatRequest {
+    Bold(GridRange),
+    FreezeRows {
+        sheet_id: SheetId,
+        rows: u32,
+    },
+    NumberFormat {
+        range: GridRange,
+        format: NumberFormat,
+    },
+    /// Columns from `start` to `end` (exclusive) are resized to fit their content.
+    AutoResizeColumns {
+        sheet_id: SheetId,
+        start: u32,
+        end: u32,
+    },
+    /// Background gradient from the lowest value in `min` color to the highest in `max` color.
+    ColorScale {
+        range: GridRange,
+        min: Color,
+        max: Color,
+    },
+    AddChart(Chart),
+    /// Removes the chart with the id, see [`Sheet::charts`](crate::spreadsheet::Sheet::charts).
+    DeleteChart(i64),
+}
+
+impl FormatRequest {
+    /// The request object in the `requests` array of `spreadsheets.batchUpdate`.
+    pub fn to_json(&self) -> Value {
+        match self {
+            FormatRequest::Bold(range) => json!({
+                "repeatCell": {
+                    "range": range,
+                    "cell": { "userEnteredFormat": { "textFormat": { "bold": true } } },
+                    "fields": "userEnteredFormat.textFormat.bold"
+                }
+            }),
+            FormatRequest::FreezeRows { sheet_id, rows } => json!({
+                "updateSheetProperties": {
+                    "properties": {
+                        "sheetId": sheet_id,
+                        "gridProperties": { "frozenRowCount": rows }
+                    },
+                    "fields": "gridProperties.frozenRowCount"
+                }
+            }),
+            FormatRequest::NumberFormat { range, format } => json!({
+                "repeatCell": {
+                    "range": range,
+                    "cell": { "userEnteredFormat": { "numberFormat": format } },
+                    "fields": "userEnteredFormat.numberFormat"
+                }
+            }),
+            FormatRequest::AutoResizeColumns {
+                sheet_id,
+                start,
+                end,
+            } => json!({
+                "autoResizeDimensions": {
+                    "dimensions": {
+                        "sheetId": sheet_id,
+                        "dimension": "COLUMNS",
+                        "startIndex": start,
+                        "endIndex": end
+                    }
+                }
+            }),
+            FormatRequest::ColorScale { range, min, max } => json!({
+                "addConditionalFormatRule": {
+                    "rule": {
+                        "ranges": [range],
+                        "gradientRule": {
+                            "minpoint": { "color": min, "type": "MIN" },
+                            "maxpoint": { "color": max, "type": "MAX" }
+                        }
+                    },
+                    "index": 0
+                }
+            }),
+            FormatRequest::AddChart(chart) => chart.to_json(),
+            FormatRequest::DeleteChart(chart_id) => json!({
+                "deleteEmbeddedObject": { "objectId": chart_id }
+            }),
+        }
+    }
+}
+
+/// Formatting of a sheet with a header row followed by data rows.
+#[derive(Debug, Clone, Default)]
+pub struct SheetStyle {
+    /// Zero-based column and row of the table's top-left cell.
+    origin: (u32, u32),
+    header: bool,
+    number_formats: Vec<(u32, NumberFormat)>,
+    color_scales: Vec<(u32, Color, Color)>,
+    auto_resize: bool,
+}
+
+impl SheetStyle {
+    pub fn new() -> Self {
+        SheetStyle::default()
+    }
+
+    /// Moves the table from `A1` to a zero-based column and row. Column indexes of the other
+    /// setters stay relative to the table.
+    pub fn origin(mut self, column: u32, row: u32) -> Self {
+        self.origin = (column, row);
+        self
+    }
+
+    /// Bold header cells of the written columns, frozen together with the rows above them.
+    pub fn header(mut self) -> Self {
+        self.header = true;
+        self
+    }
+
+    /// Number format for data rows of a zero-based column.
+    pub fn number_format(mut self, column: u32, format: NumberFormat) -> Self {
+        self.number_formats.push((column, format));
+        self
+    }
+
+    /// Color scale over data rows of a zero-based column.
+    pub fn color_scale(mut self, column: u32, min: Color, max: Color) -> Self {
+        self.color_scales.push((column, min, max));
+        self
+    }
+
+    /// Resize all written columns to fit their content.
+    pub fn auto_resize(mut self) -> Self {
+        self.auto_resize = true;
+        self
+    }
+
+    /// Requests for a sheet with `columns` written columns.
+    pub fn requests(&self, sheet_id: SheetId, columns: u32) -> Vec<FormatRequest> {
+        let (first_column, header_row) = self.origin;
+        let data_start_row = header_row + u32::from(self.header);
+        let mut requests = vec![];
+
+        if self.header {
+            requests.push(FormatRequest::Bold(GridRange::new(
+                sheet_id,
+                header_row..header_row + 1,
+                first_column..first_column + columns,
+            )));
+            requests.push(FormatRequest::FreezeRows {
+                sheet_id,
+                rows: header_row + 1,
+            });
+        }
+
+        for (column, format) in &self.number_formats {
+            requests.push(FormatRequest::NumberFormat {
+                range: GridRange::column(sheet_id, first_column + column, data_start_row),
+                format: format.clone(),
+            });
+        }
+
+        for (column, min, max) in &self.color_scales {
+            requests.push(FormatRequest::ColorScale {
+                range: GridRange::column(sheet_id, first_column + column, data_start_row),
+                min: *min,
+                max: *max,
+            });
+        }
+
+        // Resize last, so that widths account for number formats.
+        if self.auto_resize && columns > 0 {
+            requests.push(FormatRequest::AutoResizeColumns {
+                sheet_id,
+                start: first_column,
+                end: first_column + columns,
+            });
+        }
+
+        requests
+    }
+}
+
+/// Applies formatting and chart requests with `spreadsheets.batchUpdate`.
+#[tracing::instrument(skip(requests, credential))]
+pub async fn format_sheet(
+    spreadsheet_id: &str,
+    requests: &[FormatRequest],
+    credential: Credential,
+) -> Result<Value, Error> {
+    let request = Client::new()
+        .post(format!(
+            "https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}:batchUpdate"
+        ))
+        .body(
+            json!({
+                "requests": requests.iter().map(FormatRequest::to_json).collect::<Vec<_>>()
+            })
+            .to_string(),
+        );
+    let response = credential.authorize(request).await?.send().await?;
+
+    parse_response(response).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn style_requests() {
+        let sheet_id = SheetId::from(7);
+        let requests = SheetStyle::new()
+            .header()
+            .number_format(2, NumberFormat::number("#,##0.0"))
+            .color_scale(2, Color::WHITE, Color::GREEN)
+            .auto_resize()
+            .requests(sheet_id, 3);
+
+        let json: Vec<Value> = requests.iter().map(FormatRequest::to_json).collect();
+        assert_eq!(json.len(), 5);
+        assert_eq!(
+            json[0]["repeatCell"]["range"],
+            json!({
+                "sheetId": 7,
+                "startRowIndex": 0,
+                "endRowIndex": 1,
+                "startColumnIndex": 0,
+                "endColumnIndex": 3
+            })
+        );
+        assert_eq!(
+            json[1]["updateSheetProperties"]["properties"]["gridProperties"]["frozenRowCount"],
+            1
+        );
+        assert_eq!(
+            json[2]["repeatCell"]["cell"]["userEnteredFormat"]["numberFormat"],
+            json!({ "type": "NUMBER", "pattern": "#,##0.0" })
+        );
+        assert_eq!(
+            json[2]["repeatCell"]["range"],
+            json!({ "sheetId": 7, "startRowIndex": 1, "startColumnIndex": 2, "endColumnIndex": 3 })
+        );
+        assert_eq!(
+            json[3]["addConditionalFormatRule"]["rule"]["gradientRule"]["maxpoint"]["type"],
+            "MAX"
+        );
+        assert_eq!(json[4]["autoResizeDimensions"]["dimensions"]["endIndex"], 3);
+        assert_eq!(
+            FormatRequest::DeleteChart(12).to_json(),
+            json!({ "deleteEmbeddedObject": { "objectId": 12 } })
+        );
+
+        let moved = SheetStyle::new()
+            .origin(1, 2)
+            .header()
+            .number_format(2, NumberFormat::number("#,##0.0"))
+            .requests(sheet_id, 3);
+        assert_eq!(
+            moved[0],
+            FormatRequest::Bold(GridRange::new(sheet_id, 2..3, 1..4))
+        );
+        assert_eq!(moved[1], FormatRequest::FreezeRows { sheet_id, rows: 3 });
+        assert_eq!(
+            moved[2].to_json()["repeatCell"]["range"],
+            json!({ "sheetId": 7, "startRowIndex": 3, "startColumnIndex": 3, "endColumnIndex": 4 })
+        );
+    }
+}
@@ -1,6 +1,19 @@
+pub mod chart;
 pub mod error;
+pub mod format;
+pub mod range;
+#[cfg(feature = "service_account")]
+pub mod service_account;
 pub mod sheet;
+pub mod spreadsheet;
 
+pub use chart::Chart;
+pub use format::{format_sheet, SheetStyle};
+pub use range::{A1Range, CellRef};
+#[cfg(feature = "service_account")]
+pub use service_account::ServiceAccount;
 pub use sheet::{
-    add_sheet, add_sheet_with_values, batch_update, read, read_batch, write_values_into_sheet,
+    add_sheet, add_sheet_with_values, batch_update, clear, read, read_batch,
+    write_values_into_sheet, Credential,
 };
+pub use spreadsheet::{create_spreadsheet, read_grid, sheet_by_title, spreadsheet, NewSpreadsheet};
@@ -0,0 +1,420 @@
+//! A1 notation ranges: `Sheet1!A1:B2`, `'My Sheet'!H5`, `3.26!H3:H`, `A:A`, `Sheet1`.
+//! https://developers.google.com/sheets/api/guides/concepts#cell
+
+use crate::error::RangeError;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Everything except unreserved characters, so the range is safe both in a path segment and a query.
+const RANGE_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Zero-based column index from column letters: `A` is 0, `Z` is 25, `AA` is 26.
+pub fn column_index(letters: &str) -> Option<u32> {
+    if letters.is_empty() {
+        return None;
+    }
+    letters
+        .chars()
+        .try_fold(0u32, |acc, ch| {
+            if !ch.is_ascii_alphabetic() {
+                return None;
+            }
+            let digit = ch.to_ascii_uppercase() as u32 - 'A' as u32 + 1;
+            acc.checked_mul(26)?.checked_add(digit)
+        })?
+        .checked_sub(1)
+}
+
+/// Column letters from a zero-based column index: 0 is `A`, 26 is `AA`.
+pub fn column_letters(index: u32) -> String {
+    let mut letters = Vec::new();
+    let mut n = index as u64 + 1;
+    while n > 0 {
+        let rem = ((n - 1) % 26) as u8;
+        letters.push((b'A' + rem) as char);
+        n = (n - 1) / 26;
+    }
+    letters.iter().rev().collect()
+}
+
+/// Sheets grids end at column `ZZZ`, longer letter runs can't be cells.
+const MAX_COLUMN_LETTERS: usize = 3;
+
+/// One side of a range. Either part may be missing: `A` is a whole column, `5` is a whole row.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CellRef {
+    /// Zero-based column index.
+    pub column: Option<u32>,
+    /// One-based row number, as displayed in the sheet.
+    pub row: Option<u32>,
+}
+
+impl CellRef {
+    pub const fn new(column: u32, row: u32) -> Self {
+        CellRef {
+            column: Some(column),
+            row: Some(row),
+        }
+    }
+
+    pub const fn column(column: u32) -> Self {
+        CellRef {
+            column: Some(column),
+            row: None,
+        }
+    }
+
+    pub const fn row(row: u32) -> Self {
+        CellRef {
+            column: None,
+            row: Some(row),
+        }
+    }
+
+    /// Moves the cell right by `columns` and down by `rows`. Missing parts stay missing.
+    pub fn offset(self, columns: u32, rows: u32) -> Self {
+        CellRef {
+            column: self.column.map(|column| column + columns),
+            row: self.row.map(|row| row + rows),
+        }
+    }
+}
+
+impl Display for CellRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(column) = self.column {
+            f.write_str(&column_letters(column))?;
+        }
+        if let Some(row) = self.row {
+            write!(f, "{row}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for CellRef {
+    type Err = RangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || RangeError::InvalidCell(s.to_owned());
+        let digits_at = s.find(|ch: char| ch.is_ascii_digit()).unwrap_or(s.len());
+        let (letters, digits) = s.split_at(digits_at);
+        if letters.is_empty() && digits.is_empty() {
+            return Err(invalid());
+        }
+
+        if letters.len() > MAX_COLUMN_LETTERS {
+            return Err(invalid());
+        }
+
+        let column = match letters {
+            "" => None,
+            letters => Some(column_index(letters).ok_or_else(invalid)?),
+        };
+        let row = match digits {
+            "" => None,
+            digits => match digits.parse::<u32>() {
+                Ok(row) if row > 0 => Some(row),
+                _ => return Err(invalid()),
+            },
+        };
+
+        Ok(CellRef { column, row })
+    }
+}
+
+/// Range in A1 notation. Without a sheet name the API uses the first visible sheet,
+/// without cells the range is the whole sheet.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct A1Range {
+    sheet: Option<String>,
+    start: Option<CellRef>,
+    end: Option<CellRef>,
+}
+
+impl A1Range {
+    /// The whole sheet.
+    pub fn sheet(title: impl Into<String>) -> Self {
+        A1Range {
+            sheet: Some(title.into()),
+            start: None,
+            end: None,
+        }
+    }
+
+    /// Range on the first visible sheet.
+    pub const fn cells(start: CellRef, end: Option<CellRef>) -> Self {
+        A1Range {
+            sheet: None,
+            start: Some(start),
+            end,
+        }
+    }
+
+    /// Single cell, e.g. `A1Range::cell("Sheet1", 7, 5)` is `Sheet1!H5`.
+    pub fn cell(title: impl Into<String>, column: u32, row: u32) -> Self {
+        A1Range::sheet(title).start(CellRef::new(column, row))
+    }
+
+    pub fn start(mut self, start: CellRef) -> Self {
+        self.start = Some(start);
+        self
+    }
+
+    pub fn end(mut self, end: CellRef) -> Self {
+        self.end = Some(end);
+        self
+    }
+
+    pub fn sheet_name(&self) -> Option<&str> {
+        self.sheet.as_deref()
+    }
+
+    pub fn start_cell(&self) -> Option<CellRef> {
+        self.start
+    }
+
+    pub fn end_cell(&self) -> Option<CellRef> {
+        self.end
+    }
+
+    /// Number of columns, if both ends have a column.
+    pub fn width(&self) -> Option<u32> {
+        let start = self.start?.column?;
+        let end = self.end.map_or(Some(start), |end| end.column)?;
+        Some(start.abs_diff(end) + 1)
+    }
+
+    /// Number of rows, if both ends have a row.
+    pub fn height(&self) -> Option<u32> {
+        let start = self.start?.row?;
+        let end = self.end.map_or(Some(start), |end| end.row)?;
+        Some(start.abs_diff(end) + 1)
+    }
+
+    /// Moves both ends right by `columns` and down by `rows`.
+    pub fn offset(mut self, columns: u32, rows: u32) -> Self {
+        self.start = self.start.map(|cell| cell.offset(columns, rows));
+        self.end = self.end.map(|cell| cell.offset(columns, rows));
+        self
+    }
+
+    /// Percent-encoded form for URL paths and query strings.
+    pub fn url_encoded(&self) -> String {
+        utf8_percent_encode(&self.to_string(), RANGE_ENCODE_SET).to_string()
+    }
+}
+
+/// Sheet names are quoted unless they are plain words that can't be mistaken for cells.
+fn needs_quoting(title: &str) -> bool {
+    title.is_empty()
+        || !title
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_')
+        || title.starts_with(|ch: char| ch.is_ascii_digit())
+        || title.parse::<CellRef>().is_ok()
+}
+
+fn parse_cells(s: &str) -> Result<(CellRef, Option<CellRef>), RangeError> {
+    match s.split_once(':') {
+        Some((start, end)) => Ok((start.parse()?, Some(end.parse()?))),
+        None => Ok((s.parse()?, None)),
+    }
+}
+
+impl Display for A1Range {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(sheet) = &self.sheet {
+            if needs_quoting(sheet) {
+                write!(f, "'{}'", sheet.replace('\'', "''"))?;
+            } else {
+                f.write_str(sheet)?;
+            }
+            if self.start.is_some() {
+                f.write_str("!")?;
+            }
+        }
+        if let Some(start) = self.start {
+            write!(f, "{start}")?;
+        }
+        if let Some(end) = self.end {
+            write!(f, ":{end}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for A1Range {
+    type Err = RangeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(RangeError::Empty);
+        }
+
+        let (sheet, cells) = if let Some(quoted) = s.strip_prefix('\'') {
+            let mut title = String::new();
+            let mut chars = quoted.char_indices().peekable();
+            let rest = loop {
+                match chars.next() {
+                    Some((_, '\'')) if chars.peek().is_some_and(|(_, ch)| *ch == '\'') => {
+                        chars.next();
+                        title.push('\'');
+                    }
+                    Some((i, '\'')) => break &quoted[i + 1..],
+                    Some((_, ch)) => title.push(ch),
+                    None => return Err(RangeError::UnterminatedQuote),
+                }
+            };
+            match rest {
+                "" => (Some(title), None),
+                rest => match rest.strip_prefix('!') {
+                    Some(cells) => (Some(title), Some(cells)),
+                    None => return Err(RangeError::InvalidCell(rest.to_owned())),
+                },
+            }
+        } else {
+            match s.split_once('!') {
+                Some((sheet, cells)) => (Some(sheet.to_owned()), Some(cells)),
+                // Bare `A1:B2` refers to cells, anything else is a sheet name.
+                None => match parse_cells(s) {
+                    Ok((start, end)) => return Ok(A1Range::cells(start, end)),
+                    Err(_) => (Some(s.to_owned()), None),
+                },
+            }
+        };
+
+        if sheet.as_deref() == Some("") {
+            return Err(RangeError::EmptySheetName);
+        }
+
+        let (start, end) = match cells {
+            Some(cells) => {
+                let (start, end) = parse_cells(cells)?;
+                (Some(start), end)
+            }
+            None => (None, None),
+        };
+
+        Ok(A1Range { sheet, start, end })
+    }
+}
+
+impl Serialize for CellRef {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CellRef {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.trim().parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl Serialize for A1Range {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for A1Range {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_letters_round_trip() {
+        for (letters, index) in [
+            ("A", 0),
+            ("Z", 25),
+            ("AA", 26),
+            ("AZ", 51),
+            ("ZZ", 701),
+            ("AAA", 702),
+        ] {
+            assert_eq!(column_index(letters), Some(index));
+            assert_eq!(column_letters(index), letters);
+        }
+        assert_eq!(column_index("h"), Some(7));
+        assert_eq!(column_index(""), None);
+        assert_eq!(column_index("A1"), None);
+    }
+
+    #[test]
+    fn parses_and_formats() {
+        let range: A1Range = "3.26!H3:H".parse().unwrap();
+        assert_eq!(range.sheet_name(), Some("3.26"));
+        assert_eq!(range.start_cell(), Some(CellRef::new(7, 3)));
+        assert_eq!(range.end_cell(), Some(CellRef::column(7)));
+        assert_eq!(range.width(), Some(1));
+        assert_eq!(range.height(), None);
+        assert_eq!(range.to_string(), "'3.26'!H3:H");
+
+        let range: A1Range = "'It''s a sheet'!A1:C10".parse().unwrap();
+        assert_eq!(range.sheet_name(), Some("It's a sheet"));
+        assert_eq!(range.width(), Some(3));
+        assert_eq!(range.height(), Some(10));
+        assert_eq!(range.to_string(), "'It''s a sheet'!A1:C10");
+
+        assert_eq!(
+            "Sheet1".parse::<A1Range>().unwrap(),
+            A1Range::sheet("Sheet1")
+        );
+        assert_eq!(
+            "B2:D".parse::<A1Range>().unwrap(),
+            A1Range::cells(CellRef::new(1, 2), Some(CellRef::column(3)))
+        );
+        assert_eq!(A1Range::cell("Sheet1", 7, 5).to_string(), "Sheet1!H5");
+        assert_eq!(A1Range::sheet("A1").to_string(), "'A1'");
+        assert_eq!(
+            A1Range::cell("Sheet1", 0, 1).offset(27, 4).to_string(),
+            "Sheet1!AB5"
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_ranges() {
+        assert!(matches!("".parse::<A1Range>(), Err(RangeError::Empty)));
+        assert!(matches!(
+            "'Unclosed!A1".parse::<A1Range>(),
+            Err(RangeError::UnterminatedQuote)
+        ));
+        assert!(matches!(
+            "!A1".parse::<A1Range>(),
+            Err(RangeError::EmptySheetName)
+        ));
+        assert!(matches!(
+            "Sheet1!A0".parse::<A1Range>(),
+            Err(RangeError::InvalidCell(_))
+        ));
+        assert!(matches!(
+            "Sheet1!1A".parse::<A1Range>(),
+            Err(RangeError::InvalidCell(_))
+        ));
+    }
+
+    #[test]
+    fn url_encodes_sheet_names() {
+        let range = A1Range::sheet("My Sheet!").start(CellRef::new(0, 1));
+        assert_eq!(range.url_encoded(), "%27My%20Sheet%21%27%21A1");
+        assert_eq!(
+            serde_json::to_string(&range).unwrap(),
+            r#""'My Sheet!'!A1""#
+        );
+    }
+}
@@ -1,15 +1,47 @@
 use std::fmt::{Debug, Display};
 
-use reqwest::Client;
-use serde::{Deserialize, Serialize};
+use reqwest::{Client, RequestBuilder, Response};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::{json, Value};
 
 use crate::error::{Error, GoogleErrorResponse};
+use crate::format::{format_sheet, SheetStyle};
+use crate::range::A1Range;
+#[cfg(feature = "service_account")]
+use crate::service_account::ServiceAccount;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Credential {
     AccessToken(String),
     ApiKey(String),
+    /// Loaded from the key file with [`ServiceAccount::from_file`], never (de)serialized.
+    #[cfg(feature = "service_account")]
+    #[serde(skip)]
+    ServiceAccount(ServiceAccount),
+}
+
+impl Credential {
+    /// Adds the credential to the request: bearer token or `key` query parameter.
+    pub async fn authorize(&self, request: RequestBuilder) -> Result<RequestBuilder, Error> {
+        match self {
+            Credential::AccessToken(token) => Ok(request.bearer_auth(token)),
+            Credential::ApiKey(api_key) => Ok(request.query(&[("key", api_key)])),
+            #[cfg(feature = "service_account")]
+            Credential::ServiceAccount(account) => {
+                Ok(request.bearer_auth(account.access_token().await?))
+            }
+        }
+    }
+}
+
+/// Parses a successful response as `T`, an unsuccessful one as [`GoogleErrorResponse`].
+pub(crate) async fn parse_response<T: DeserializeOwned>(response: Response) -> Result<T, Error> {
+    if response.status().as_u16() >= 400 {
+        let err_response: GoogleErrorResponse = response.json().await?;
+        Err(err_response.error.into())
+    } else {
+        Ok(response.json().await?)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -21,74 +53,60 @@ pub struct ReadBatchResponse {
 
 pub async fn read_batch(
     spreadsheet_id: &str,
-    ranges: &[&str],
+    ranges: &[A1Range],
     credential: Credential,
 ) -> Result<ReadBatchResponse, Error> {
-    let formatted_ranges = ranges
+    let query = ranges
         .iter()
-        .map(|range| format!("ranges={range}"))
-        .collect::<Vec<String>>()
-        .join("&");
-
-    let response = match credential {
-        Credential::AccessToken(token) => {
-            let url =
-        format!("https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}/values:batchGet?{formatted_ranges}");
-            Client::new()
-                .get(url)
-                .header("Authorization", format!("Bearer {token}"))
-                .send()
-                .await?
-        }
-        Credential::ApiKey(api_key) => {
-            let url =
-        format!("https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}/values:batchGet?{formatted_ranges}&key={api_key}");
-            Client::new().get(url).send().await?
-        }
-    };
+        .map(|range| ("ranges", range.to_string()))
+        .collect::<Vec<_>>();
+
+    let url =
+        format!("https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}/values:batchGet");
+    let response = credential
+        .authorize(Client::new().get(url).query(&query))
+        .await?
+        .send()
+        .await?;
 
-    if response.status().as_u16() >= 400 {
-        let err_response: GoogleErrorResponse = response.json().await?;
-        Err(err_response.error.into())
-    } else {
-        let value: ReadBatchResponse = response.json().await?;
-        Ok(value)
-    }
+    parse_response(response).await
 }
 
 pub async fn read(
     spreadsheet_id: &str,
-    range: &str,
+    range: &A1Range,
     credential: Credential,
 ) -> Result<ValueRange, Error> {
-    let response = match credential {
-        Credential::AccessToken(token) => {
-            let url = format!(
-                "https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}/values/{range}"
-            );
-
-            Client::new()
-                .get(url)
-                .header("Authorization", format!("Bearer {token}"))
-                .send()
-                .await?
-        }
-        Credential::ApiKey(api_key) => {
-            let url = format!(
-                "https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}/values/{range}?key={api_key}"
-            );
+    let url = format!(
+        "https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}/values/{}",
+        range.url_encoded()
+    );
+    let response = credential
+        .authorize(Client::new().get(url))
+        .await?
+        .send()
+        .await?;
 
-            Client::new().get(url).send().await?
-        }
-    };
+    parse_response(response).await
+}
 
-    if response.status().as_u16() >= 400 {
-        let err_response: GoogleErrorResponse = response.json().await?;
-        Err(err_response.error.into())
-    } else {
-        let value_range: ValueRange = response.json().await?;
-        Ok(value_range)
-    }
+/// Clears values of the range, formats are kept.
+pub async fn clear(
+    spreadsheet_id: &str,
+    range: &A1Range,
+    credential: Credential,
+) -> Result<Value, Error> {
+    let url = format!(
+        "https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}/values/{}:clear",
+        range.url_encoded()
+    );
+    let response = credential
+        .authorize(Client::new().post(url).body("{}"))
+        .await?
+        .send()
+        .await?;
+
+    parse_response(response).await
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone, Default)]
@@ -104,12 +122,14 @@ pub enum Dimension {
 pub struct ValueRange {
     #[serde(rename = "majorDimension")]
     pub dimension: Dimension,
-    pub range: String,
+    pub range: A1Range,
+    /// Missing in responses for empty ranges.
+    #[serde(default)]
     pub values: Vec<Vec<Value>>,
 }
 
 impl ValueRange {
-    pub const fn new(dimension: Dimension, range: String, values: Vec<Vec<Value>>) -> ValueRange {
+    pub const fn new(dimension: Dimension, range: A1Range, values: Vec<Vec<Value>>) -> ValueRange {
         ValueRange {
             dimension,
             range,
@@ -118,51 +138,56 @@ impl ValueRange {
     }
 }
 
-#[tracing::instrument(skip(data, token))]
+#[tracing::instrument(skip(data, credential))]
 pub async fn batch_update(
     spreadsheet_id: &str,
     data: Vec<ValueRange>,
-    token: &str,
+    credential: Credential,
 ) -> Result<Value, Error> {
-    let response = Client::new()
+    let request = Client::new()
         .post(format!(
-            "https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}/values:batchUpdate
-"
+            "https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}/values:batchUpdate"
         ))
-        .header("Authorization", format!("Bearer {token}"))
         .body(
             json!({
                 "valueInputOption": "RAW",
                 "data": data
             })
             .to_string(),
-        )
-        .send()
-        .await?;
+        );
+    let response = credential.authorize(request).await?.send().await?;
 
-    if response.status().as_u16() >= 400 {
-        let err_response: GoogleErrorResponse = response.json().await?;
-        Err(err_response.error.into())
-    } else {
-        let write_values: Value = response.json().await?;
-        Ok(write_values)
-    }
+    parse_response(response).await
 }
 
-#[tracing::instrument(skip(values))]
+#[tracing::instrument(skip(values, credential))]
 pub async fn add_sheet_with_values<T: Serialize + Debug>(
     spreadsheet_id: &str,
     title: &str,
     values: Vec<Vec<Value>>,
-    token: &str,
+    style: Option<&SheetStyle>,
+    credential: Credential,
 ) -> Result<SheetUrl, Error> {
-    let add_sheet_data = add_sheet(spreadsheet_id, title, token).await?;
-    let _ = write_values_into_sheet(spreadsheet_id, title, token, values).await?;
-
-    Ok(SheetUrl::create(
+    let add_sheet_data = add_sheet(spreadsheet_id, title, credential.clone()).await?;
+    let sheet_id = add_sheet_data.properties.sheet_id;
+    let columns = values.iter().map(Vec::len).max().unwrap_or_default() as u32;
+    let _ = write_values_into_sheet(
         spreadsheet_id,
-        add_sheet_data.properties.sheet_id,
-    ))
+        &A1Range::sheet(title),
+        credential.clone(),
+        values,
+    )
+    .await?;
+    if let Some(style) = style {
+        format_sheet(
+            spreadsheet_id,
+            &style.requests(sheet_id, columns),
+            credential,
+        )
+        .await?;
+    }
+
+    Ok(SheetUrl::create(spreadsheet_id, sheet_id))
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -177,41 +202,36 @@ pub struct WriteValuesResponse {
 
 pub async fn write_values_into_sheet(
     spreadsheet_id: &str,
-    title: &str,
-    token: &str,
+    range: &A1Range,
+    credential: Credential,
     values: Vec<Vec<Value>>,
 ) -> Result<WriteValuesResponse, Error> {
-    let url = format!("https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}/values/{title}?valueInputOption=RAW
-");
+    let url = format!(
+        "https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}/values/{}?valueInputOption=RAW",
+        range.url_encoded()
+    );
 
     let body = serde_json::to_string(&json!({
-      "range": title,
+      "range": range,
       "majorDimension": "ROWS",
       "values": values
     }))?;
 
-    let response = Client::new()
-        .put(url)
-        .header("Authorization", format!("Bearer {token}"))
-        .body(body)
-        .send()
-        .await?;
+    let request = Client::new().put(url).body(body);
+    let response = credential.authorize(request).await?.send().await?;
 
-    if response.status().as_u16() >= 400 {
-        let err_response: GoogleErrorResponse = response.json().await?;
-        Err(err_response.error.into())
-    } else {
-        let write_values: WriteValuesResponse = response.json().await?;
-        Ok(write_values)
-    }
+    parse_response(response).await
 }
 
-pub async fn add_sheet(spreadsheet_id: &str, title: &str, token: &str) -> Result<AddSheet, Error> {
-    let response = Client::new()
+pub async fn add_sheet(
+    spreadsheet_id: &str,
+    title: &str,
+    credential: Credential,
+) -> Result<AddSheet, Error> {
+    let request = Client::new()
         .post(format!(
             "https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}:batchUpdate"
         ))
-        .header("Authorization", format!("Bearer {}", { token }))
         .body(
             json!({
                "requests":[
@@ -225,19 +245,13 @@ pub async fn add_sheet(spreadsheet_id: &str, title: &str, token: &str) -> Result
                ]
             })
             .to_string(),
-        )
-        .send()
-        .await?;
+        );
+    let response = credential.authorize(request).await?.send().await?;
 
-    if response.status().as_u16() >= 400 {
-        let err_response: GoogleErrorResponse = response.json().await?;
-        Err(err_response.error.into())
-    } else {
-        let response: BatchResponse = response.json().await?;
-        let add_sheet = response.replies[0].add_sheet.clone();
+    let response: BatchResponse = parse_response(response).await?;
+    let add_sheet = response.replies[0].add_sheet.clone();
 
-        Ok(add_sheet)
-    }
+    Ok(add_sheet)
 }
 
 #[cfg(test)]
@@ -330,13 +344,23 @@ pub struct AddSheetProperties {
     pub grid_properties: GridProperties,
 }
 
-#[derive(Debug, Serialize, Deserialize, Default, Clone, Hash, PartialEq, PartialOrd)]
+#[derive(Debug, Serialize, Deserialize, Default, Clone, Copy, Hash, PartialEq, PartialOrd)]
 pub struct SheetId(u32);
 impl Display for SheetId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         std::fmt::Display::fmt(&self.0, f)
     }
 }
+impl From<u32> for SheetId {
+    fn from(value: u32) -> Self {
+        SheetId(value)
+    }
+}
+impl From<SheetId> for u32 {
+    fn from(value: SheetId) -> Self {
+        value.0
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize, Default, Clone, Hash, PartialEq, PartialOrd)]
 pub struct SheetUrl(String);
@@ -353,6 +377,10 @@ impl SheetUrl {
 pub struct GridProperties {
     pub column_count: u32,
     pub row_count: u32,
+    #[serde(default)]
+    pub frozen_row_count: u32,
+    #[serde(default)]
+    pub frozen_column_count: u32,
 }
 
 // #[tauri::command]
@@ -0,0 +1,422 @@
+//! Spreadsheet metadata and rich cell data (`spreadsheets.get`).
+//! https://developers.google.com/sheets/api/reference/rest/v4/spreadsheets/get
+
+use crate::error::Error;
+use crate::format::{Color, NumberFormat};
+use crate::range::A1Range;
+use crate::sheet::{parse_response, Credential, GridProperties, SheetId};
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Spreadsheet {
+    pub spreadsheet_id: String,
+    #[serde(default)]
+    pub spreadsheet_url: Option<String>,
+    #[serde(default)]
+    pub properties: SpreadsheetProperties,
+    #[serde(default)]
+    pub sheets: Vec<Sheet>,
+}
+
+impl Spreadsheet {
+    pub fn sheet(&self, title: &str) -> Option<&Sheet> {
+        self.sheets
+            .iter()
+            .find(|sheet| sheet.properties.title == title)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SpreadsheetProperties {
+    #[serde(default)]
+    pub title: String,
+    pub locale: Option<String>,
+    pub time_zone: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Sheet {
+    pub properties: SheetProperties,
+    /// Present only when grid data is requested, one entry per requested range.
+    #[serde(default)]
+    pub data: Vec<GridData>,
+    /// Charts placed on the sheet.
+    #[serde(default)]
+    pub charts: Vec<EmbeddedChart>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EmbeddedChart {
+    pub chart_id: i64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SheetProperties {
+    pub sheet_id: SheetId,
+    pub title: String,
+    #[serde(default)]
+    pub index: u32,
+    #[serde(default)]
+    pub sheet_type: Option<String>,
+    /// Missing for sheets that hold a single chart.
+    pub grid_properties: Option<GridProperties>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct GridData {
+    #[serde(default)]
+    pub start_row: u32,
+    #[serde(default)]
+    pub start_column: u32,
+    #[serde(default)]
+    pub row_data: Vec<RowData>,
+}
+
+impl GridData {
+    /// Cell by zero-based row and column, relative to the start of the data.
+    pub fn cell(&self, row: usize, column: usize) -> Option<&CellData> {
+        self.row_data.get(row)?.values.get(column)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RowData {
+    #[serde(default)]
+    pub values: Vec<CellData>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CellData {
+    pub formatted_value: Option<String>,
+    pub effective_format: Option<CellFormat>,
+    #[serde(default)]
+    pub text_format_runs: Vec<TextFormatRun>,
+    pub hyperlink: Option<String>,
+    pub note: Option<String>,
+}
+
+impl CellData {
+    /// Splits the displayed text into fragments by format runs. Each fragment is paired with
+    /// its run format, text before the first run has no format of its own.
+    pub fn text_runs(&self) -> Vec<(&str, Option<&TextFormat>)> {
+        let text = self.formatted_value.as_deref().unwrap_or_default();
+        if self.text_format_runs.is_empty() {
+            return match text {
+                "" => vec![],
+                text => vec![(text, None)],
+            };
+        }
+
+        // Run start indexes are in UTF-16 code units.
+        let byte_index = |utf16_index: usize| -> usize {
+            let mut units = 0;
+            for (byte, ch) in text.char_indices() {
+                if units >= utf16_index {
+                    return byte;
+                }
+                units += ch.len_utf16();
+            }
+            text.len()
+        };
+
+        let mut fragments = vec![];
+        let first_start = byte_index(self.text_format_runs[0].start_index);
+        if first_start > 0 {
+            fragments.push((&text[..first_start], None));
+        }
+        for (i, run) in self.text_format_runs.iter().enumerate() {
+            let start = byte_index(run.start_index);
+            let end = self
+                .text_format_runs
+                .get(i + 1)
+                .map_or(text.len(), |next| byte_index(next.start_index));
+            if start < end {
+                fragments.push((&text[start..end], run.format.as_ref()));
+            }
+        }
+        fragments
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CellFormat {
+    pub background_color: Option<Color>,
+    pub text_format: Option<TextFormat>,
+    pub number_format: Option<NumberFormat>,
+}
+
+/// Fields are `None` when not set, runs inherit them from the cell format.
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TextFormat {
+    pub foreground_color: Option<Color>,
+    pub font_family: Option<String>,
+    pub font_size: Option<u32>,
+    pub bold: Option<bool>,
+    pub italic: Option<bool>,
+    pub strikethrough: Option<bool>,
+    pub underline: Option<bool>,
+    pub link: Option<Link>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Link {
+    pub uri: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct TextFormatRun {
+    /// Missing for a run that starts at the beginning.
+    #[serde(default)]
+    pub start_index: usize,
+    pub format: Option<TextFormat>,
+}
+
+/// Spreadsheet to create with [`create_spreadsheet`]. Without tabs, Sheets adds a single "Sheet1".
+#[derive(Debug, Clone, Default)]
+pub struct NewSpreadsheet {
+    title: String,
+    locale: Option<String>,
+    time_zone: Option<String>,
+    sheets: Vec<String>,
+}
+
+impl NewSpreadsheet {
+    pub fn new(title: impl Into<String>) -> Self {
+        NewSpreadsheet {
+            title: title.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Locale in ISO 639-1 format, e.g. `en_US`. Defaults to the locale of the account.
+    pub fn locale(mut self, locale: impl Into<String>) -> Self {
+        self.locale = Some(locale.into());
+        self
+    }
+
+    /// Time zone in CLDR format, e.g. `Europe/Berlin`.
+    pub fn time_zone(mut self, time_zone: impl Into<String>) -> Self {
+        self.time_zone = Some(time_zone.into());
+        self
+    }
+
+    /// Adds a tab, tabs keep the order they are added in.
+    pub fn sheet(mut self, title: impl Into<String>) -> Self {
+        self.sheets.push(title.into());
+        self
+    }
+
+    /// Body of the `spreadsheets.create` request.
+    pub fn to_json(&self) -> Value {
+        let mut properties = json!({ "title": self.title });
+        if let Some(locale) = &self.locale {
+            properties["locale"] = json!(locale);
+        }
+        if let Some(time_zone) = &self.time_zone {
+            properties["timeZone"] = json!(time_zone);
+        }
+        let sheets: Vec<Value> = self
+            .sheets
+            .iter()
+            .zip(0u32..)
+            .map(|(title, index)| json!({ "properties": { "title": title, "index": index } }))
+            .collect();
+
+        json!({ "properties": properties, "sheets": sheets })
+    }
+}
+
+/// Creates a spreadsheet owned by the credential's account.
+#[tracing::instrument(skip(credential))]
+pub async fn create_spreadsheet(
+    new: &NewSpreadsheet,
+    credential: Credential,
+) -> Result<Spreadsheet, Error> {
+    let request = Client::new()
+        .post("https://sheets.googleapis.com/v4/spreadsheets")
+        .body(new.to_json().to_string());
+    let response = credential.authorize(request).await?.send().await?;
+
+    parse_response(response).await
+}
+
+/// Spreadsheet title, properties and chart ids of all sheets, without cell data.
+pub async fn spreadsheet(
+    spreadsheet_id: &str,
+    credential: Credential,
+) -> Result<Spreadsheet, Error> {
+    let request = Client::new()
+        .get(format!(
+            "https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}"
+        ))
+        .query(&[(
+            "fields",
+            "spreadsheetId,properties,sheets.properties,sheets.charts.chartId",
+        )]);
+    let response = credential.authorize(request).await?.send().await?;
+
+    parse_response(response).await
+}
+
+/// Properties of the sheet with the given title.
+pub async fn sheet_by_title(
+    spreadsheet_id: &str,
+    title: &str,
+    credential: Credential,
+) -> Result<SheetProperties, Error> {
+    spreadsheet(spreadsheet_id, credential)
+        .await?
+        .sheets
+        .into_iter()
+        .map(|sheet| sheet.properties)
+        .find(|properties| properties.title == title)
+        .ok_or_else(|| Error::SheetNotFound(title.to_owned()))
+}
+
+/// Cells of the ranges with formats, text runs, hyperlinks and notes.
+pub async fn read_grid(
+    spreadsheet_id: &str,
+    ranges: &[A1Range],
+    credential: Credential,
+) -> Result<Spreadsheet, Error> {
+    let mut query = ranges
+        .iter()
+        .map(|range| ("ranges", range.to_string()))
+        .collect::<Vec<_>>();
+    query.push(("includeGridData", "true".to_owned()));
+
+    let request = Client::new()
+        .get(format!(
+            "https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}"
+        ))
+        .query(&query);
+    let response = credential.authorize(request).await?.send().await?;
+
+    parse_response(response).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GRID_JSON: &str = r#"{
+        "spreadsheetId": "abc",
+        "properties": { "title": "Divcord", "locale": "en_US" },
+        "sheets": [{
+            "properties": {
+                "sheetId": 0,
+                "title": "Cards_and_Hypotheses",
+                "index": 0,
+                "sheetType": "GRID",
+                "gridProperties": { "rowCount": 700, "columnCount": 26, "frozenRowCount": 2 }
+            },
+            "data": [{
+                "startRow": 2,
+                "startColumn": 6,
+                "rowData": [
+                    { "values": [{
+                        "formattedValue": "‘Ossuary’; Vault (A5)",
+                        "effectiveFormat": {
+                            "backgroundColor": { "red": 1, "green": 1, "blue": 1 },
+                            "textFormat": { "foregroundColor": {}, "italic": false, "strikethrough": false }
+                        },
+                        "textFormatRuns": [
+                            { "format": {} },
+                            { "startIndex": 11, "format": { "italic": true } }
+                        ],
+                        "note": "checked"
+                    }] },
+                    {},
+                    { "values": [{ "hyperlink": "https://poewiki.net", "formattedValue": "wiki" }] }
+                ]
+            }]
+        }, {
+            "properties": { "sheetId": 42, "title": "Chart", "index": 1, "sheetType": "OBJECT" },
+            "charts": [{ "chartId": 1729 }]
+        }]
+    }"#;
+
+    #[test]
+    fn parses_grid_data() {
+        let spreadsheet: Spreadsheet = serde_json::from_str(GRID_JSON).unwrap();
+        assert_eq!(spreadsheet.properties.title, "Divcord");
+        assert!(spreadsheet
+            .sheet("Chart")
+            .unwrap()
+            .properties
+            .grid_properties
+            .is_none());
+        assert_eq!(spreadsheet.sheet("Chart").unwrap().charts[0].chart_id, 1729);
+
+        let sheet = spreadsheet.sheet("Cards_and_Hypotheses").unwrap();
+        let grid = sheet.properties.grid_properties.as_ref().unwrap();
+        assert_eq!((grid.row_count, grid.frozen_row_count), (700, 2));
+
+        let data = &sheet.data[0];
+        assert_eq!((data.start_row, data.start_column), (2, 6));
+        let cell = data.cell(0, 0).unwrap();
+        assert_eq!(cell.note.as_deref(), Some("checked"));
+        let text_format = cell
+            .effective_format
+            .as_ref()
+            .and_then(|format| format.text_format.as_ref())
+            .unwrap();
+        assert_eq!(
+            text_format.foreground_color,
+            Some(Color::rgb(0.0, 0.0, 0.0))
+        );
+        assert_eq!(text_format.italic, Some(false));
+
+        assert!(data.cell(1, 0).is_none());
+        assert_eq!(
+            data.cell(2, 0).unwrap().hyperlink.as_deref(),
+            Some("https://poewiki.net")
+        );
+    }
+
+    #[test]
+    fn new_spreadsheet_body() {
+        let body = NewSpreadsheet::new("Divination cards")
+            .locale("en_US")
+            .sheet("Sample")
+            .sheet("Sample summary")
+            .to_json();
+        assert_eq!(
+            body["properties"],
+            json!({ "title": "Divination cards", "locale": "en_US" })
+        );
+        assert_eq!(body["sheets"][1]["properties"]["title"], "Sample summary");
+        assert_eq!(body["sheets"][1]["properties"]["index"], 1);
+        assert_eq!(NewSpreadsheet::new("Empty").to_json()["sheets"], json!([]));
+    }
+
+    #[test]
+    fn splits_text_by_utf16_runs() {
+        let spreadsheet: Spreadsheet = serde_json::from_str(GRID_JSON).unwrap();
+        let cell = spreadsheet.sheets[0].data[0].cell(0, 0).unwrap();
+        let runs = cell.text_runs();
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[0].0, "‘Ossuary’; ");
+        assert_eq!(runs[1].0, "Vault (A5)");
+        assert_eq!(runs[1].1.and_then(|format| format.italic), Some(true));
+
+        let plain = CellData {
+            formatted_value: Some("plain".to_owned()),
+            ..Default::default()
+        };
+        assert_eq!(plain.text_runs(), [("plain", None)]);
+    }
+}
@@ -106,7 +106,8 @@ mod etc {
         let source_types = Source::types();
 
         let now = Instant::now();
-        let cards = divcord::cards::cards_by_source_types(&source_types, &records, &poe_data);
+        let graph = divcord::graph::SourceGraph::new(&poe_data);
+        let cards = divcord::cards::cards_by_source_types(&source_types, &records, &graph);
         dbg!(now.elapsed().as_micros());
 
         jsonsave("cards.json", &cards);
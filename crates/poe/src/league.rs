@@ -1,5 +1,6 @@
 use serde::{Deserialize, Serialize};
 use std::fmt::Display;
+use std::str::FromStr;
 
 #[derive(Debug, Serialize, Deserialize, Clone, Hash, PartialEq, Eq, Default)]
 pub enum League {
@@ -75,6 +76,21 @@ impl Display for TradeLeague {
     }
 }
 
+/// Parses the [`Display`] name, e.g. the key of a cached `{league}-prices.json`.
+impl FromStr for TradeLeague {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "Standard" => Ok(TradeLeague::Standard),
+            "Hardcore" => Ok(TradeLeague::Hardcore),
+            "Keepers" => Ok(TradeLeague::Keepers),
+            "Hardcore Keepers" => Ok(TradeLeague::HardcoreKeepers),
+            _ => Err(format!("{s} is not a trade league")),
+        }
+    }
+}
+
 impl TryFrom<League> for TradeLeague {
     type Error = &'static str;
 
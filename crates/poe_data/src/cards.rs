@@ -2,6 +2,8 @@ use crate::league::LeagueReleaseInfo;
 #[cfg(feature = "fs_cache_fetcher")]
 use crate::league::ReleaseVersion;
 #[cfg(feature = "fs_cache_fetcher")]
+use googlesheets::A1Range;
+#[cfg(feature = "fs_cache_fetcher")]
 use once_cell::sync::Lazy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -17,35 +19,40 @@ pub struct LeagueRanges {
     /// The league version, e.g., "3.26".
     pub version: ReleaseVersion,
     /// The sheet range for card names, e.g., "3.26!H3:H".
-    pub names_range: String,
+    pub names_range: A1Range,
     /// The sheet range for card weights, e.g., "3.26!S3:S".
-    pub weights_range: String,
+    pub weights_range: A1Range,
 }
 
 #[cfg(feature = "fs_cache_fetcher")]
 pub static LEAGUE_RANGES: Lazy<[LeagueRanges; 4]> = Lazy::new(|| {
     use crate::league::ReleaseVersion;
 
+    fn range(a1: &str) -> A1Range {
+        a1.parse()
+            .expect("League range should be valid A1 notation")
+    }
+
     [
         LeagueRanges {
             version: ReleaseVersion::new("3.26".to_string()),
-            names_range: "3.26!H3:H".to_string(),
-            weights_range: "3.26!S3:S".to_string(),
+            names_range: range("3.26!H3:H"),
+            weights_range: range("3.26!S3:S"),
         },
         LeagueRanges {
             version: ReleaseVersion::new("3.25".to_string()),
-            names_range: "3.25!F3:F".to_string(),
-            weights_range: "3.25!Q3:Q".to_string(),
+            names_range: range("3.25!F3:F"),
+            weights_range: range("3.25!Q3:Q"),
         },
         LeagueRanges {
             version: ReleaseVersion::new("3.24".to_string()),
-            names_range: "3.24!D3:D".to_string(),
-            weights_range: "3.24!O3:O".to_string(),
+            names_range: range("3.24!D3:D"),
+            weights_range: range("3.24!O3:O"),
         },
         LeagueRanges {
             version: ReleaseVersion::new("3.23".to_string()),
-            names_range: "3.23!D3:D".to_string(),
-            weights_range: "3.23!P3:P".to_string(),
+            names_range: range("3.23!D3:D"),
+            weights_range: range("3.23!P3:P"),
         },
     ]
 });
@@ -159,7 +166,10 @@ pub mod fetch {
     ) -> Result<Sample, Error> {
         let batch_read = googlesheets::read_batch(
             WEIGHT_SPREADSHEET_ID,
-            &[&league_ranges.names_range, &league_ranges.weights_range],
+            &[
+                league_ranges.names_range.clone(),
+                league_ranges.weights_range.clone(),
+            ],
             Credential::ApiKey(api_key),
         )
         .await?;
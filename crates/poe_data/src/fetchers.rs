@@ -1,6 +1,6 @@
 #![cfg(feature = "fs_cache_fetcher")]
 use crate::{act::ActArea, cards::CardsData, error::Error, mapbosses::MapBoss, maps::Map, PoeData};
-use fs_cache_fetcher::{Config, DataFetcher, Stale};
+use fs_cache_fetcher::{Codec, Config, DataFetcher, LoadPolicy, Stale};
 use std::time::Duration;
 
 pub struct PoeDataFetcher {
@@ -15,8 +15,11 @@ impl Default for PoeDataFetcher {
         Self {
             config: Config {
                 save: true,
-                filename: "poeData.json",
+                filename: "poeData.json".to_owned(),
                 stale: Stale::ReloadEveryTime,
+                codec: Codec::Cbor,
+                policy: LoadPolicy::StaleOnError { max_age: None },
+                ..Default::default()
             },
             acts: Default::default(),
             cards: Default::default(),
@@ -57,8 +60,9 @@ impl Default for MapBossesFetcher {
     fn default() -> Self {
         Self(Config {
             save: true,
-            filename: "mapBosses.json",
+            filename: "mapBosses.json".to_owned(),
             stale: Stale::Never,
+            ..Default::default()
         })
     }
 }
@@ -82,8 +86,9 @@ impl Default for MapsFetcher {
     fn default() -> Self {
         Self(Config {
             save: true,
-            filename: "maps.json",
+            filename: "maps.json".to_owned(),
             stale: Stale::Never,
+            ..Default::default()
         })
     }
 }
@@ -107,8 +112,9 @@ impl Default for ActsFetcher {
     fn default() -> Self {
         Self(Config {
             save: true,
-            filename: "acts.json",
+            filename: "acts.json".to_owned(),
             stale: Stale::Never,
+            ..Default::default()
         })
     }
 }
@@ -132,8 +138,10 @@ impl Default for CardsFetcher {
     fn default() -> Self {
         Self(Config {
             save: true,
-            filename: "cards.json",
+            filename: "cards.json".to_owned(),
             stale: Stale::After(Duration::from_secs(86_400)),
+            policy: LoadPolicy::StaleOnError { max_age: None },
+            ..Default::default()
         })
     }
 }
@@ -35,9 +35,11 @@ impl PoeData {
     }
 
     #[cfg(feature = "fs_cache_fetcher")]
-    pub fn filename() -> &'static str {
+    pub fn filename() -> String {
         use fs_cache_fetcher::DataFetcher;
-        crate::fetchers::PoeDataFetcher::default().config().filename
+        crate::fetchers::PoeDataFetcher::default()
+            .filename()
+            .to_owned()
     }
 
     pub fn mapboss(&self, name: &str) -> Option<&MapBoss> {
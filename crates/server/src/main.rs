@@ -6,7 +6,7 @@ use axum::{
 use lib::event::{Event, Notifier};
 use lib::prices::AppCardPrices;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use tokio::sync::{Mutex, OnceCell};
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
 
@@ -18,6 +18,57 @@ use lib::version::AppVersion;
 struct AppState {
     prices: Mutex<AppCardPrices>,
     version: AppVersion,
+    /// Empty until loaded in the background, divcord routes answer 503 meanwhile.
+    divcord: OnceCell<DivcordState>,
+}
+
+/// Records and PoeData, loaded once at startup and shared by divcord routes.
+struct DivcordState {
+    records: Vec<divcord::Record>,
+    poe_data: poe_data::PoeData,
+    index: divcord::DivcordIndex,
+}
+
+impl DivcordState {
+    async fn load() -> Result<Self, String> {
+        let poe_data = poe_data::PoeData::load()
+            .await
+            .map_err(|err| format!("Could not load PoeData. {err}"))?;
+        let spreadsheet = divcord::Spreadsheet::load()
+            .await
+            .map_err(|err| format!("Could not load divcord spreadsheet. {err:?}"))?;
+        // Sources with errors are dropped, the rest of the record is kept.
+        let records: Vec<divcord::Record> = divcord::records_iter(&spreadsheet, &poe_data)
+            .filter_map(|result| match result {
+                Ok(parsed) => {
+                    for err in &parsed.errors {
+                        tracing::warn!("Record {}: {err}", parsed.record.id);
+                    }
+                    Some(parsed.record)
+                }
+                Err(err) => {
+                    tracing::warn!("{err}");
+                    None
+                }
+            })
+            .collect();
+        let index = divcord::DivcordIndex::new(&records, &poe_data);
+        Ok(DivcordState {
+            records,
+            poe_data,
+            index,
+        })
+    }
+}
+
+/// Divcord state if it is loaded, otherwise 503.
+fn divcord_state(state: &AppState) -> Result<&DivcordState, (StatusCode, Json<serde_json::Value>)> {
+    state.divcord.get().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(serde_json::json!({ "error": "Divcord data is not loaded yet" })),
+        )
+    })
 }
 
 struct AxumNotifier;
@@ -37,6 +88,18 @@ async fn main() {
     let state = Arc::new(AppState {
         prices: Mutex::new(prices),
         version,
+        divcord: OnceCell::new(),
+    });
+
+    let loading = state.clone();
+    tokio::spawn(async move {
+        match DivcordState::load().await {
+            Ok(divcord) => {
+                let _ = loading.divcord.set(divcord);
+                tracing::info!("Divcord data loaded");
+            }
+            Err(err) => tracing::error!("{err}"),
+        }
     });
 
     let app = Router::new()
@@ -50,6 +113,8 @@ async fn main() {
         .route("/api/sample_from_tab", get(get_sample_from_tab))
         .route("/api/tab_with_items", get(get_tab_with_items))
         .route("/api/poe/token", post(post_poe_token))
+        .route("/api/divcord/query", post(post_divcord_query))
+        .route("/api/divcord/farm", post(post_divcord_farm))
         .layer(CorsLayer::permissive())
         .layer(TraceLayer::new_for_http())
         .with_state(state);
@@ -291,3 +356,49 @@ async fn post_poe_token(
 
     Ok(Json(json))
 }
+
+#[derive(serde::Deserialize)]
+struct DivcordQueryRequest {
+    query: String,
+}
+
+async fn post_divcord_query(
+    State(state): State<Arc<AppState>>,
+    Json(payload): Json<DivcordQueryRequest>,
+) -> Result<Json<divcord::QueryResult>, (StatusCode, Json<serde_json::Value>)> {
+    let divcord = divcord_state(&state)?;
+    divcord::query_records(&payload.query, &divcord.records, &divcord.poe_data)
+        .map(Json)
+        .map_err(|err| {
+            (
+                StatusCode::BAD_REQUEST,
+                Json(serde_json::json!({ "error": err.to_string() })),
+            )
+        })
+}
+
+#[derive(serde::Deserialize)]
+struct FarmParams {
+    /// League of the divination card prices, cards without a price there fall back to PoeData.
+    #[serde(default)]
+    league: divi::TradeLeague,
+}
+
+async fn post_divcord_farm(
+    State(state): State<Arc<AppState>>,
+    Query(params): Query<FarmParams>,
+    Json(query): Json<divcord::farm::FarmQuery>,
+) -> Result<Json<Vec<divcord::farm::SourceValue>>, (StatusCode, Json<serde_json::Value>)> {
+    let divcord = divcord_state(&state)?;
+    let prices = state
+        .prices
+        .lock()
+        .await
+        .get_price(&params.league, &AxumNotifier)
+        .await;
+    Ok(Json(query.run(
+        &divcord.index,
+        &divcord.poe_data,
+        Some(&prices),
+    )))
+}
@@ -5,15 +5,33 @@ use crate::google::AccessTokenState;
 use crate::google::{AccessTokenStorage, Persist};
 use crate::poe::error::AuthError;
 use chrono::Utc;
+#[cfg(feature = "desktop")]
+use divi::report::Report;
 use divi::sample::Sample;
 #[cfg(feature = "desktop")]
+use divi::sample::{Column, TablePreferences};
+#[cfg(feature = "desktop")]
+use divi::sheet_sync::SyncOptions;
+#[cfg(feature = "desktop")]
+use divi::sheet_template::SheetTemplate;
+#[cfg(feature = "desktop")]
 use divi::League;
-use googlesheets::sheet::{Credential, Dimension, ReadBatchResponse, SheetUrl, ValueRange};
-use reqwest::Client;
-use serde_json::json;
+#[cfg(feature = "desktop")]
+use googlesheets::error::RangeError;
+#[cfg(feature = "desktop")]
+use googlesheets::format::{Color, FormatRequest, NumberFormat};
+#[cfg(feature = "desktop")]
+use googlesheets::sheet::SheetId;
+use googlesheets::sheet::{Credential, ReadBatchResponse, SheetUrl, ValueRange};
+#[cfg(feature = "desktop")]
+use googlesheets::spreadsheet::Spreadsheet;
+#[cfg(feature = "desktop")]
+use googlesheets::{A1Range, NewSpreadsheet, SheetStyle};
 #[cfg(feature = "desktop")]
 use tauri::State;
 use tracing::debug;
+#[cfg(feature = "desktop")]
+use tracing::warn;
 
 #[cfg(feature = "desktop")]
 #[tauri::command]
@@ -24,93 +42,220 @@ pub async fn new_sheet_with_sample(
     sample: Sample,
     league: League,
     preferences: Option<divi::sample::TablePreferences>,
+    template: Option<SheetTemplate>,
     token_state: State<'_, AccessTokenState>,
 ) -> Result<SheetUrl, Error> {
-    let token = match token_state.0.lock().await.clone() {
-        Some(t) => t,
-        None => AccessTokenStorage::new()
-            .get()
-            .map_err(|_| Error::AuthError(AuthError::Failed("Missing access token".to_string())))?,
-    };
-    let sheet_gid: String = match googlesheets::add_sheet(spreadsheet_id, title, &token).await {
-        Ok(add) => add.properties.sheet_id.to_string(),
-        Err(_) => get_sheet_gid_by_title(spreadsheet_id, title, &token).await?,
-    };
-
-    let sample_values = ValueRange {
-        dimension: Dimension::Rows,
-        range: format!("{title}!A1"),
-        values: sample.into_serde_values(preferences),
-    };
+    let credential = credential(&token_state).await?;
+    let (sheet_id, created) = get_or_add_sheet(spreadsheet_id, title, &credential).await?;
 
-    let aside_values = ValueRange {
-        dimension: Dimension::Rows,
-        range: format!("{title}!H5"),
-        values: vec![vec![json!(format!(
-            "{} {league} League",
-            Utc::now().date_naive().format("%-d %b, %C%y")
-        ))]],
-    };
+    let preferences = preferences.unwrap_or_default();
+    let template = template.unwrap_or_default();
+    let (origin_column, origin_row) = template.table_origin();
+    // Conditional format rules are added, not replaced: a re-exported tab keeps its color scale.
+    let style = default_style(&preferences, created).origin(origin_column, origin_row);
+    let columns = preferences.ordered_columns().len() as u32;
+    let label = format!(
+        "{} {league} League",
+        Utc::now().date_naive().format("%-d %b, %C%y")
+    );
+    let values = template.value_ranges(title, sample, preferences, &label);
 
     let batch_response =
-        googlesheets::batch_update(spreadsheet_id, vec![sample_values, aside_values], &token)
-            .await?;
+        googlesheets::batch_update(spreadsheet_id, values, credential.clone()).await?;
 
     debug!("{batch_response}");
 
-    let url =
-        format!("https://docs.google.com/spreadsheets/d/{spreadsheet_id}/edit#gid={sheet_gid}");
-    let sheet_url: SheetUrl = serde_json::from_value(json!(url))?;
-    Ok(sheet_url)
+    // Values are already written, a failed formatting should not fail the export.
+    if let Err(err) = googlesheets::format_sheet(
+        spreadsheet_id,
+        &style.requests(sheet_id, columns),
+        credential,
+    )
+    .await
+    {
+        warn!("Could not format sheet {title}: {err}");
+    }
+
+    Ok(SheetUrl::create(spreadsheet_id, sheet_id))
 }
 
-#[derive(serde::Deserialize)]
-struct SheetsListResponse {
-    sheets: Vec<SheetEntry>,
+/// Creates a spreadsheet in the user's Drive, so that samples can be exported without
+/// an existing spreadsheet id.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+#[tracing::instrument(skip(token_state))]
+pub async fn new_spreadsheet(
+    title: &str,
+    locale: Option<String>,
+    tabs: Vec<String>,
+    token_state: State<'_, AccessTokenState>,
+) -> Result<Spreadsheet, Error> {
+    let credential = credential(&token_state).await?;
+    let new = tabs
+        .into_iter()
+        .fold(NewSpreadsheet::new(title), NewSpreadsheet::sheet);
+    let new = match locale {
+        Some(locale) => new.locale(locale),
+        None => new,
+    };
+
+    Ok(googlesheets::create_spreadsheet(&new, credential).await?)
 }
 
-#[derive(serde::Deserialize)]
-struct SheetEntry {
-    properties: SheetProps,
+/// Access token of the signed-in user, from memory or the keyring.
+#[cfg(feature = "desktop")]
+async fn credential(token_state: &AccessTokenState) -> Result<Credential, Error> {
+    let token = match token_state.0.lock().await.clone() {
+        Some(t) => t,
+        None => AccessTokenStorage::new()
+            .get()
+            .map_err(|_| Error::AuthError(AuthError::Failed("Missing access token".to_string())))?,
+    };
+    Ok(Credential::AccessToken(token))
+}
+
+/// Id of the tab with the title, which is added if missing. `true` if the tab was just added.
+#[cfg(feature = "desktop")]
+async fn get_or_add_sheet(
+    spreadsheet_id: &str,
+    title: &str,
+    credential: &Credential,
+) -> Result<(SheetId, bool), Error> {
+    match googlesheets::add_sheet(spreadsheet_id, title, credential.clone()).await {
+        Ok(add) => Ok((add.properties.sheet_id, true)),
+        Err(_) => {
+            let sheet =
+                googlesheets::sheet_by_title(spreadsheet_id, title, credential.clone()).await?;
+            Ok((sheet.sheet_id, false))
+        }
+    }
 }
 
-#[derive(serde::Deserialize)]
-struct SheetProps {
-    title: String,
-    #[serde(rename = "sheetId")]
-    sheet_id: u32,
+/// Bold frozen header, number formats for numeric columns and, if `color_scale`, a color scale
+/// on price.
+#[cfg(feature = "desktop")]
+fn default_style(preferences: &TablePreferences, color_scale: bool) -> SheetStyle {
+    preferences.ordered_columns().into_iter().zip(0u32..).fold(
+        SheetStyle::new().header().auto_resize(),
+        |style, (column, index)| match column {
+            Column::Name => style,
+            Column::Amount => style.number_format(index, NumberFormat::number("#,##0")),
+            Column::Weight => style.number_format(index, NumberFormat::number("#,##0.00")),
+            Column::Price => {
+                let style = style.number_format(index, NumberFormat::number("#,##0.0"));
+                if color_scale {
+                    style.color_scale(index, Color::WHITE, Color::GREEN)
+                } else {
+                    style
+                }
+            }
+            Column::Sum => style.number_format(index, NumberFormat::number("#,##0.0")),
+        },
+    )
 }
 
-async fn get_sheet_gid_by_title(
+/// Merges the sample into an existing tab (created if missing): rows are matched by card name,
+/// new cards are appended and columns added by the user are left alone.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+#[tracing::instrument(skip(sample))]
+pub async fn sync_sample_into_sheet(
     spreadsheet_id: &str,
     title: &str,
-    token: &str,
-) -> Result<String, Error> {
-    let url = format!(
-        "https://sheets.googleapis.com/v4/spreadsheets/{spreadsheet_id}?fields=sheets(properties(title,sheetId))"
+    sample: Sample,
+    preferences: Option<TablePreferences>,
+    keep_history: bool,
+    template: Option<SheetTemplate>,
+    token_state: State<'_, AccessTokenState>,
+) -> Result<SheetUrl, Error> {
+    let credential = credential(&token_state).await?;
+    let (sheet_id, _) = get_or_add_sheet(spreadsheet_id, title, &credential).await?;
+
+    let existing =
+        googlesheets::read(spreadsheet_id, &A1Range::sheet(title), credential.clone()).await?;
+    let options = SyncOptions {
+        preferences: preferences.unwrap_or_default(),
+        history_column: keep_history
+            .then(|| Utc::now().date_naive().format("%-d %b, %C%y").to_string()),
+        template: template.unwrap_or_default(),
+    };
+    let sync = sample.sync_into(&existing.values, &options)?;
+    debug!(
+        "Sync into {title}: {} updated, {} appended",
+        sync.updated, sync.appended
     );
-    let resp = Client::new()
-        .get(url)
-        .header("Authorization", format!("Bearer {token}"))
-        .send()
+
+    googlesheets::batch_update(spreadsheet_id, sync.value_ranges(title), credential).await?;
+
+    Ok(SheetUrl::create(spreadsheet_id, sheet_id))
+}
+
+/// Exports the sample like [`new_sheet_with_sample`] and adds a "{title} summary" tab next to it
+/// with totals, top cards by value and charts. Returns the url of the summary tab.
+#[cfg(feature = "desktop")]
+#[tauri::command]
+#[tracing::instrument(skip(sample))]
+pub async fn new_report_with_sample(
+    spreadsheet_id: &str,
+    title: &str,
+    sample: Sample,
+    league: League,
+    preferences: Option<TablePreferences>,
+    template: Option<SheetTemplate>,
+    token_state: State<'_, AccessTokenState>,
+) -> Result<SheetUrl, Error> {
+    let report = Report::new(&sample, &league, 10);
+    new_sheet_with_sample(
+        spreadsheet_id,
+        title,
+        sample,
+        league,
+        preferences,
+        template,
+        token_state.clone(),
+    )
+    .await?;
+
+    let credential = credential(&token_state).await?;
+    let summary_title = format!("{title} summary");
+    let (sheet_id, created) = get_or_add_sheet(spreadsheet_id, &summary_title, &credential).await?;
+
+    // A re-exported summary tab may hold a longer report, so its old values are cleared first.
+    if !created {
+        googlesheets::clear(
+            spreadsheet_id,
+            &A1Range::sheet(&summary_title),
+            credential.clone(),
+        )
         .await?;
-    if resp.status().as_u16() >= 400 {
-        let err: googlesheets::error::GoogleErrorResponse = resp.json().await?;
-        return Err(googlesheets::error::Error::GoogleError(err.error).into());
     }
-    let data: SheetsListResponse = resp.json().await?;
-    let id = data
-        .sheets
-        .into_iter()
-        .find(|s| s.properties.title == title)
-        .map(|s| s.properties.sheet_id.to_string())
-        .ok_or_else(|| googlesheets::error::GoogleError {
-            code: 404,
-            message: "Sheet not found after creation".to_string(),
-            status: "NOT_FOUND".to_string(),
-        })
-        .map_err(|e| googlesheets::error::Error::GoogleError(e))?;
-    Ok(id)
+    googlesheets::batch_update(
+        spreadsheet_id,
+        report.value_ranges(&summary_title),
+        credential.clone(),
+    )
+    .await?;
+
+    // Charts read the values written above, so they are added after them. Charts of a
+    // re-exported summary tab point at the old ranges and are replaced.
+    let mut requests: Vec<FormatRequest> = Vec::new();
+    if !created {
+        let spreadsheet = googlesheets::spreadsheet(spreadsheet_id, credential.clone()).await?;
+        if let Some(sheet) = spreadsheet.sheet(&summary_title) {
+            requests.extend(
+                sheet
+                    .charts
+                    .iter()
+                    .map(|chart| FormatRequest::DeleteChart(chart.chart_id)),
+            );
+        }
+    }
+    requests.extend(report.requests(sheet_id));
+    if let Err(err) = googlesheets::format_sheet(spreadsheet_id, &requests, credential).await {
+        warn!("Could not add charts to {summary_title}: {err}");
+    }
+
+    Ok(SheetUrl::create(spreadsheet_id, sheet_id))
 }
 
 #[cfg(feature = "desktop")]
@@ -121,14 +266,13 @@ pub async fn read_batch(
     ranges: Vec<&str>,
     token_state: State<'_, AccessTokenState>,
 ) -> Result<ReadBatchResponse, Error> {
-    let token = match token_state.0.lock().await.clone() {
-        Some(t) => t,
-        None => AccessTokenStorage::new()
-            .get()
-            .map_err(|_| Error::AuthError(AuthError::Failed("Missing access token".to_string())))?,
-    };
-    let value =
-        googlesheets::read_batch(spreadsheet_id, &ranges, Credential::AccessToken(token)).await?;
+    let credential = credential(&token_state).await?;
+    let ranges = ranges
+        .iter()
+        .map(|range| range.parse())
+        .collect::<Result<Vec<A1Range>, RangeError>>()
+        .map_err(googlesheets::error::Error::from)?;
+    let value = googlesheets::read_batch(spreadsheet_id, &ranges, credential).await?;
 
     Ok(value)
 }
@@ -141,14 +285,9 @@ pub async fn read_sheet(
     range: &str,
     token_state: State<'_, AccessTokenState>,
 ) -> Result<ValueRange, Error> {
-    let token = match token_state.0.lock().await.clone() {
-        Some(t) => t,
-        None => AccessTokenStorage::new()
-            .get()
-            .map_err(|_| Error::AuthError(AuthError::Failed("Missing access token".to_string())))?,
-    };
-    let value_range =
-        googlesheets::read(spreadsheet_id, range, Credential::AccessToken(token)).await?;
+    let credential = credential(&token_state).await?;
+    let range: A1Range = range.parse().map_err(googlesheets::error::Error::from)?;
+    let value_range = googlesheets::read(spreadsheet_id, &range, credential).await?;
 
     Ok(value_range)
 }
@@ -3,6 +3,7 @@ use crate::{
     event::{Event, Notifier, ToastVariant},
 };
 use divi::{prices::Prices, Error as DiviError, TradeLeague};
+use fs_cache_fetcher::{Config, KeyedDataFetcher, LoadPolicy, Loaded, Stale};
 use ninja::{
     fetch_stash_currency_overview, fetch_stash_dense_overviews_raw, fetch_stash_item_overview,
 };
@@ -12,20 +13,59 @@ use std::sync::{
     atomic::{AtomicU64, Ordering},
     Mutex, OnceLock,
 };
+use std::time::Duration;
 use std::time::Instant;
 use std::{collections::HashMap, fs, path::PathBuf};
 use tracing::{debug, info, instrument};
 
 pub const MINUTE_AS_SECS: f64 = 60.0;
-const UP_TO_DATE_THRESHOLD_MINUTES: f32 = 20.0;
-const STILL_USABLE_THRESHOLD_MINUTES: f32 = 20.0;
-
-pub enum LeagueFileState {
-    UpToDate(Prices),
-    StillUsable(Prices, f32),
-    TooOld,
-    Invalid,
-    NoFile,
+const UP_TO_DATE_THRESHOLD_MINUTES: u64 = 20;
+const STILL_USABLE_THRESHOLD_MINUTES: u64 = 20;
+
+/// Card prices from poe.ninja, cached per league as `{league}-prices.json`.
+/// Outdated file is still served if poe.ninja is unavailable, unless it is older than
+/// [`STILL_USABLE_THRESHOLD_MINUTES`].
+#[derive(Debug, Clone)]
+pub struct PricesFetcher(pub Config);
+
+impl PricesFetcher {
+    pub fn new(dir: PathBuf) -> Self {
+        Self::default_with_mut_config(|config| {
+            config.cache_dir(dir.clone());
+        })
+    }
+}
+
+impl Default for PricesFetcher {
+    fn default() -> Self {
+        Self(Config {
+            save: true,
+            filename: "{key}-prices.json".to_owned(),
+            stale: Stale::After(Duration::from_secs(UP_TO_DATE_THRESHOLD_MINUTES * 60)),
+            policy: LoadPolicy::StaleOnError {
+                max_age: Some(Duration::from_secs(STILL_USABLE_THRESHOLD_MINUTES * 60)),
+            },
+            ..Default::default()
+        })
+    }
+}
+
+impl KeyedDataFetcher for PricesFetcher {
+    type Key = TradeLeague;
+    type Item = Prices;
+    type Error = Error;
+
+    async fn fetch(&self, league: &TradeLeague) -> Result<Prices, Error> {
+        Ok(Prices::fetch(league).await.map_err(DiviError::NinjaError)?)
+    }
+
+    fn config(&self) -> &Config {
+        &self.0
+    }
+
+    fn config_mut(&mut self) -> &mut Config {
+        &mut self.0
+    }
 }
 
 impl AppCardPrices {
@@ -35,53 +75,26 @@ impl AppCardPrices {
             return prices.to_owned();
         }
 
-        match self.read_file(league) {
-            LeagueFileState::UpToDate(prices) => prices,
-            LeagueFileState::StillUsable(prices, minutes_old) => self
-                .fetch_and_update(league)
-                .await
-                .unwrap_or_else(|_| {
-                       let message = format!("Prices are not up-to-date, but still usable ({minutes_old:.0} minutes old). Unable to load new prices.");
-                        Event::Toast {
-                            variant: ToastVariant::Warning,
-                            message,
-                        }
-                        .notify(notifier);
-                        prices
-                }),
-            _ => self
-                .fetch_and_update(league)
-                .await
-                .unwrap_or_else(|err| {
-                    self.send_default_prices_with_toast_warning(&err, league, notifier)
-                }),
-        }
-    }
-
-    pub fn read_file(&self, league: &TradeLeague) -> LeagueFileState {
-        if !self.league_file_exists(league) {
-            return LeagueFileState::NoFile;
-        }
-
-        let Ok(prices) = self.read_from_file(league) else {
-            return LeagueFileState::Invalid;
-        };
-
-        if let Some(minutes_old) = self.file_minutes_old(league) {
-            match minutes_old {
-                n if n <= UP_TO_DATE_THRESHOLD_MINUTES => LeagueFileState::UpToDate(prices),
-                n if n <= STILL_USABLE_THRESHOLD_MINUTES => LeagueFileState::StillUsable(prices, n),
-                _ => LeagueFileState::TooOld,
+        let prices = match self.fetcher().load_detailed(league).await {
+            Ok(Loaded::Stale { item, age }) => {
+                let minutes_old = age.as_secs_f64() / MINUTE_AS_SECS;
+                let message = format!("Prices are not up-to-date, but still usable ({minutes_old:.0} minutes old). Unable to load new prices.");
+                Event::Toast {
+                    variant: ToastVariant::Warning,
+                    message,
+                }
+                .notify(notifier);
+                // not kept in memory, so that the next call tries to fetch again
+                return item;
             }
-        } else {
-            LeagueFileState::NoFile
-        }
-    }
+            Ok(loaded) => loaded.into_inner(),
+            Err(err) => return self.send_default_prices_with_toast_warning(&err, league, notifier),
+        };
 
-    pub fn read_league_file(&self, league: &TradeLeague) -> Result<Prices, Error> {
-        let json = std::fs::read_to_string(self.league_path(league))?;
-        let prices = serde_json::from_str::<Prices>(&json)?;
-        Ok(prices)
+        debug!("get_price: loaded prices for {league}");
+        self.prices_by_league
+            .insert(league.to_owned(), prices.clone());
+        prices
     }
 }
 
@@ -597,90 +610,12 @@ impl AppCardPrices {
         Prices::default()
     }
 
-    #[instrument(skip(self))]
-    fn read_from_file_update_and_return(&mut self, league: &TradeLeague) -> Result<Prices, Error> {
-        let json = std::fs::read_to_string(self.league_path(league))?;
-        let prices = serde_json::from_str::<Prices>(&json)?;
-        self.prices_by_league
-            .insert(league.to_owned(), prices.clone());
-        Ok(prices)
+    pub fn fetcher(&self) -> PricesFetcher {
+        PricesFetcher::new(self.dir.clone())
     }
 
     #[instrument(skip(self))]
     pub fn league_path(&self, league: &TradeLeague) -> PathBuf {
-        self.dir.join(format!("{}-prices.json", { league }))
-    }
-
-    #[instrument(skip(self))]
-    async fn fetch_and_update(&mut self, league: &TradeLeague) -> Result<Prices, Error> {
-        let prices = Prices::fetch(league).await.map_err(DiviError::NinjaError)?;
-        debug!("fetch_and_update: fetched. Serializing to json");
-        let json = serde_json::to_string(&prices)?;
-
-        debug!("fetch_and_update: Serialized. Next write to file");
-
-        std::fs::write(self.league_path(league), json)?;
-
-        debug!("fetch_and_update: wrote to file");
-        self.prices_by_league
-            .insert(league.to_owned(), prices.clone());
-
-        Ok(prices)
-    }
-
-    #[instrument(skip(self))]
-    fn read_from_file(&self, league: &TradeLeague) -> Result<Prices, Error> {
-        let json = std::fs::read_to_string(self.league_path(league))?;
-        let prices = serde_json::from_str::<Prices>(&json)?;
-        Ok(prices)
-    }
-
-    #[instrument(skip(self))]
-    fn file_is_up_to_date(&self, league: &TradeLeague) -> bool {
-        match self.file_minutes_old(league) {
-            Some(minutes_old) => minutes_old <= UP_TO_DATE_THRESHOLD_MINUTES,
-            None => false,
-        }
-    }
-
-    #[instrument(skip(self))]
-    fn file_is_still_usable(&self, league: &TradeLeague) -> bool {
-        match self.file_minutes_old(league) {
-            Some(minutes_old) => minutes_old <= STILL_USABLE_THRESHOLD_MINUTES,
-            None => false,
-        }
-    }
-
-    #[instrument(skip(self))]
-    fn file_minutes_old(&self, league: &TradeLeague) -> Option<f32> {
-        let path = self.league_path(league);
-        match fs::metadata(&path) {
-            Ok(metadata) => match metadata.modified() {
-                Ok(modified_time) => match modified_time.elapsed() {
-                    Ok(duration) => Some((duration.as_secs_f64() / MINUTE_AS_SECS) as f32),
-                    Err(_e) => {
-                        // SystemTimeError: modified time is later than current time.
-                        debug!(
-                            "File {:?} modification time is in the future. Treating as needing update.",
-                            path
-                        );
-                        None
-                    }
-                },
-                Err(e) => {
-                    debug!("Failed to read modification time for {:?}: {}", path, e);
-                    None
-                }
-            },
-            Err(e) => {
-                debug!("Failed to read metadata for {:?}: {}", path, e);
-                None
-            }
-        }
-    }
-
-    #[instrument(skip(self))]
-    fn league_file_exists(&self, league: &TradeLeague) -> bool {
-        self.league_path(league).try_exists().unwrap_or(false)
+        self.fetcher().file_path(league)
     }
 }
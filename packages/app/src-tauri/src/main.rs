@@ -68,6 +68,9 @@ async fn main() {
             google::auth::google_logout,
             google::auth::google_identity,
             google::sheets::new_sheet_with_sample,
+            google::sheets::sync_sample_into_sheet,
+            google::sheets::new_report_with_sample,
+            google::sheets::new_spreadsheet,
             google::sheets::read_sheet,
             google::sheets::read_batch,
             poe::stash::sample_from_tab_with_items,
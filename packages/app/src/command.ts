@@ -17,6 +17,16 @@ export type ValueRange = {
 	values: Array<Array<string | number | null | undefined>>;
 };
 type Preferences = Omit<TablePreferences, 'columns'> & { columns: Column[] };
+/** Cells in A1 notation, e.g. `A1`. Defaults: table at `A1`, label at `H5`, no totals. */
+export type SheetTemplate = {
+	table: string;
+	label: string | null;
+	totals: string | null;
+};
+export type Spreadsheet = {
+	spreadsheetId: string;
+	spreadsheetUrl: string | null;
+};
 
 export interface Commands {
 	version: () => string;
@@ -28,7 +38,25 @@ export interface Commands {
 		sample: DivinationCardsSample;
 		league: League;
 		preferences: Preferences;
+		template?: SheetTemplate;
+	}) => string;
+	sync_sample_into_sheet: (args: {
+		spreadsheetId: string;
+		title: string;
+		sample: DivinationCardsSample;
+		preferences: Preferences;
+		keepHistory: boolean;
+		template?: SheetTemplate;
+	}) => string;
+	new_report_with_sample: (args: {
+		spreadsheetId: string;
+		title: string;
+		sample: DivinationCardsSample;
+		league: League;
+		preferences: Preferences;
+		template?: SheetTemplate;
 	}) => string;
+	new_spreadsheet: (args: { title: string; locale?: string; tabs: string[] }) => Spreadsheet;
 	google_logout: () => void;
 	google_identity: () => GoogleIdentity;
 	google_auth: () => void;